@@ -12,46 +12,24 @@ fn app() -> Html {
     }
 }
 
-static DEFAULT_RELAYS: std::sync::LazyLock<[nostr_minions::relay_pool::UserRelay; 4]> =
-    std::sync::LazyLock::new(|| {
-        [
-            nostr_minions::relay_pool::UserRelay {
-                url: "wss://purplepag.es".to_string(),
-                read: true,
-                write: true,
-            },
-            nostr_minions::relay_pool::UserRelay {
-                url: "wss://relay.unkownk.com".to_string(),
-                read: true,
-                write: true,
-            },
-            nostr_minions::relay_pool::UserRelay {
-                url: "wss://nos.lol".to_string(),
-                read: true,
-                write: true,
-            },
-            nostr_minions::relay_pool::UserRelay {
-                url: "wss://relay.illuminodes.com".to_string(),
-                read: true,
-                write: true,
-            },
-        ]
-    });
 #[function_component(AppContext)]
 fn app() -> HtmlResult {
     let relays = yew::suspense::use_future(|| async move {
         nostr_minions::init_nostr_db().unwrap();
+        if bunker::idb::init_bunker_db().await.is_err() {
+            web_sys::console::error_1(&"Failed to open bunker database".into());
+        }
         match nostr_minions::relay_pool::UserRelay::retrieve_all_from_store().await {
             Ok(saved_relays) => {
                 if saved_relays.is_empty() {
-                    DEFAULT_RELAYS.to_vec()
+                    bunker::default_relays()
                 } else {
                     saved_relays
                 }
             }
             Err(_) => {
                 web_sys::console::log_1(&"Using default relays".into());
-                DEFAULT_RELAYS.to_vec()
+                bunker::default_relays()
             }
         }
     })?;
@@ -60,16 +38,38 @@ fn app() -> HtmlResult {
             <bunker::language::LanguageConfigsProvider>
             <nostr_minions::key_manager::NostrIdProvider>
                 <nostr_minions::relay_pool::NostrRelayPoolProvider relays={(*relays).clone()}>
+                <bunker::relay_stats::RelayStatsProvider>
+                <bunker::relay_reconnect::RelayReconnectProvider>
+                <bunker::signer::SignerProvider>
+                    <bunker::key_lock::KeyLockProvider>
+                    <bunker::accounts::AccountsProvider>
+                    <bunker::profiles::ProfileCacheProvider>
                     <LoginCheck>
+                        <bunker::outbox::OutboxProvider>
                         <bunker::live_game::AnnotatedGameHistoryProvider>
+                        <bunker::labels::GameLabelsProvider>
+                        <bunker::backup::GameBackupProvider>
+                        <bunker::imports::ImportJobsProvider>
+                        <bunker::lichess_sync::LichessSyncProvider>
                             <div class={classes!("h-screen", "w-full", "flex")}>
                                 <Navbar />
                                 <main class={classes!("flex-1")}>
                                     <bunker::MainPages />
                                 </main>
                             </div>
+                        </bunker::lichess_sync::LichessSyncProvider>
+                        </bunker::imports::ImportJobsProvider>
+                        </bunker::backup::GameBackupProvider>
+                        </bunker::labels::GameLabelsProvider>
                         </bunker::live_game::AnnotatedGameHistoryProvider>
+                        </bunker::outbox::OutboxProvider>
                     </LoginCheck>
+                    </bunker::profiles::ProfileCacheProvider>
+                    </bunker::accounts::AccountsProvider>
+                    </bunker::key_lock::KeyLockProvider>
+                </bunker::signer::SignerProvider>
+                </bunker::relay_reconnect::RelayReconnectProvider>
+                </bunker::relay_stats::RelayStatsProvider>
                 </nostr_minions::relay_pool::NostrRelayPoolProvider>
             </nostr_minions::key_manager::NostrIdProvider>
             </bunker::language::LanguageConfigsProvider>
@@ -137,6 +137,32 @@ fn navbar() -> Html {
                     <span class={classes!("")}>{ language_ctx.t("navbar_repertoire") }</span>
                 </div>
             </yew_router::components::Link<bunker::MainRoute>>
+            <yew_router::components::Link<bunker::MainRoute> to={bunker::MainRoute::Feed}>
+                <div class={classes!(
+                    navbar_button_class.clone(),
+                    if matches!(current_route, Some(bunker::MainRoute::Feed)) {
+                        "bg-primary"
+                    } else {
+                        "bg-zinc-800"
+                    }
+                    )}>
+                    <lucide_yew::Rss class={classes!("size-6", "lg:size-8", "xl:size-10", "2xl:size-12", )} />
+                    <span class={classes!("")}>{ language_ctx.t("navbar_feed") }</span>
+                </div>
+            </yew_router::components::Link<bunker::MainRoute>>
+            <yew_router::components::Link<bunker::MainRoute> to={bunker::MainRoute::Stats}>
+                <div class={classes!(
+                    navbar_button_class.clone(),
+                    if matches!(current_route, Some(bunker::MainRoute::Stats)) {
+                        "bg-primary"
+                    } else {
+                        "bg-zinc-800"
+                    }
+                    )}>
+                    <lucide_yew::ChartColumn class={classes!("size-6", "lg:size-8", "xl:size-10", "2xl:size-12", )} />
+                    <span class={classes!("")}>{ language_ctx.t("navbar_stats") }</span>
+                </div>
+            </yew_router::components::Link<bunker::MainRoute>>
             <yew_router::components::Link<bunker::MainRoute> to={bunker::MainRoute::Search}>
                 <div class={classes!(
                     navbar_button_class.clone(),
@@ -163,6 +189,7 @@ fn navbar() -> Html {
                     <span class={classes!("")}>{ language_ctx.t("common_settings") }</span>
                 </div>
             </yew_router::components::Link<bunker::MainRoute>>
+            <bunker::AccountSwitcher class={classes!(navbar_button_class, "bg-zinc-800")} />
         </navbar>
     }
 }
@@ -170,13 +197,23 @@ fn navbar() -> Html {
 #[function_component(LoginCheck)]
 fn login_check(props: &yew::html::ChildrenProps) -> HtmlResult {
     let key_ctx = nostr_minions::key_manager::use_nostr_id_ctx();
-    let nostr_id = yew::suspense::use_future_with(key_ctx, |_| async move {
+    let signer_ctx = bunker::signer::use_signer_ctx();
+    let lock_ctx = bunker::key_lock::use_key_lock_ctx();
+    let nostr_id = yew::suspense::use_future_with(key_ctx.clone(), |_| async move {
         nostr_minions::key_manager::UserIdentity::find_identity().await
     })?;
     Ok(match *nostr_id {
         Ok(ref _id) => html! {
             {props.children.clone()}
         },
+        Err(_) if signer_ctx.is_connected() || key_ctx.get_identity().is_some() => html! {
+            {props.children.clone()}
+        },
+        Err(_) if lock_ctx.is_protected() => html! {
+            <div class={"h-screen w-full flex flex-col gap-4 items-center justify-center"}>
+                <bunker::UnlockKey />
+            </div>
+        },
         Err(_) => {
             html! {
                 <div class={"h-screen w-full flex flex-col gap-4 items-center justify-center"}>
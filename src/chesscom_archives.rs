@@ -0,0 +1,107 @@
+//! The Chess.com archive list, naming the months a player has games in.
+//!
+//! `external::ChessComClient` fetches a single month; this reads which months exist.
+
+const CHESSCOM_API_URL: &str = "https://api.chess.com";
+
+#[derive(serde::Deserialize)]
+struct ArchiveList {
+    archives: Vec<String>,
+}
+
+/// Reads archive lists from a Chess.com API server, api.chess.com unless told otherwise.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChessComArchives {
+    pub base_url: String,
+}
+
+impl Default for ChessComArchives {
+    fn default() -> Self {
+        Self {
+            base_url: CHESSCOM_API_URL.to_string(),
+        }
+    }
+}
+
+/// The `(year, month)` of a monthly archive URL such as `.../games/2024/03`.
+fn archive_month(url: &str) -> Option<(u32, u32)> {
+    let mut parts = url.trim_end_matches('/').rsplit('/');
+    let month = parts
+        .next()?
+        .parse::<u32>()
+        .ok()
+        .filter(|month| (1..=12).contains(month))?;
+    let year = parts.next()?.parse::<u32>().ok()?;
+    Some((year, month))
+}
+
+impl ChessComArchives {
+    #[must_use]
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    #[must_use]
+    pub fn archives_url(&self, username: &str) -> String {
+        format!(
+            "{}/pub/player/{}/games/archives",
+            self.base_url,
+            username.trim().to_lowercase()
+        )
+    }
+
+    /// The months `username` has games in, oldest first.
+    ///
+    /// # Errors
+    /// Returns the request error if the list cannot be fetched or read.
+    pub async fn months(&self, username: &str) -> Result<Vec<(u32, u32)>, gloo::net::Error> {
+        let url = self.archives_url(username);
+        let response = gloo::net::http::Request::get(&url).send().await?;
+        if !response.ok() {
+            return Err(gloo::net::Error::GlooError(format!(
+                "{url} returned {}",
+                response.status()
+            )));
+        }
+        let mut months = response
+            .json::<ArchiveList>()
+            .await?
+            .archives
+            .iter()
+            .filter_map(|url| archive_month(url))
+            .collect::<Vec<_>>();
+        months.sort_unstable();
+        Ok(months)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_archive_months() {
+        assert_eq!(
+            archive_month("https://api.chess.com/pub/player/hikaru/games/2024/03"),
+            Some((2024, 3))
+        );
+        assert_eq!(
+            archive_month("https://api.chess.com/pub/player/hikaru/games/2024/13"),
+            None
+        );
+        assert_eq!(
+            archive_month("https://api.chess.com/pub/player/hikaru"),
+            None
+        );
+    }
+
+    #[test]
+    fn builds_archive_url_for_lowercased_player() {
+        assert_eq!(
+            ChessComArchives::new("http://localhost:8080/").archives_url(" Hikaru "),
+            "http://localhost:8080/pub/player/hikaru/games/archives"
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use shady_minions::ui::{
+    Button, ButtonVariant, Card, CardContent, CardDescription, CardHeader, CardTitle, Modal,
+};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct AccountSwitcherProps {
+    #[prop_or_default]
+    pub class: Classes,
+}
+
+/// The active identity in the navbar; opens the list of accounts on this device.
+#[function_component(AccountSwitcher)]
+pub fn account_switcher(props: &AccountSwitcherProps) -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let accounts_ctx = crate::accounts::use_accounts();
+    let signer = crate::signer::use_signer();
+    let open = use_state(|| false);
+    let active = signer.as_ref().map(crate::signer::BunkerSigner::public_key);
+    let name = active.as_ref().map_or_else(
+        || language_ctx.t("accounts_none"),
+        |pubkey| {
+            accounts_ctx.find(pubkey).map_or_else(
+                || crate::accounts::short_npub(pubkey),
+                |account| account.display_name(),
+            )
+        },
+    );
+    let onclick = {
+        let open = open.clone();
+        Callback::from(move |_| open.set(!*open))
+    };
+    html! {
+        <>
+        <button {onclick} title={language_ctx.t("accounts_switch")} class={props.class.clone()}>
+            <lucide_yew::CircleUser class={classes!("size-6", "lg:size-8", "xl:size-10", "2xl:size-12")} />
+            <span class={classes!("max-w-full", "truncate", "text-xs")}>{ name }</span>
+        </button>
+        <Modal is_open={open}>
+            <AccountList />
+        </Modal>
+        </>
+    }
+}
+
+/// Every account kept on this device, with the active one marked.
+#[function_component(AccountList)]
+pub fn account_list() -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let switch = crate::accounts::use_account_switch();
+    let accounts = switch.accounts.accounts();
+    let add_account = {
+        let switch = switch.clone();
+        Callback::from(move |_| switch.sign_out())
+    };
+    html! {
+        <Card class={classes!("min-w-sm", "max-w-md")}>
+            <CardHeader>
+                <CardTitle>{ language_ctx.t("accounts_title") }</CardTitle>
+                <CardDescription>{ language_ctx.t("accounts_description") }</CardDescription>
+            </CardHeader>
+            <CardContent class={classes!("space-y-2")}>
+                { accounts.into_iter().map(|account| {
+                    let is_active = switch.active.as_deref() == Some(account.pubkey.as_str());
+                    let key = account.pubkey.clone();
+                    html! {
+                        <AccountRow
+                            {key}
+                            {account}
+                            {is_active}
+                            switch={switch.clone()} />
+                    }
+                }).collect::<Html>() }
+                <Button
+                    onclick={add_account}
+                    r#type={shady_minions::ui::ButtonType::Button}
+                    variant={ButtonVariant::Outline}
+                    class={classes!("w-full", "mt-2")}>
+                    <lucide_yew::UserPlus class={classes!("size-4", "mr-2")} />
+                    { language_ctx.t("accounts_add") }
+                </Button>
+            </CardContent>
+        </Card>
+    }
+}
+
+#[derive(Properties, Clone, PartialEq)]
+struct AccountRowProps {
+    account: crate::accounts::StoredAccount,
+    is_active: bool,
+    switch: crate::accounts::AccountSwitch,
+}
+
+#[function_component(AccountRow)]
+fn account_row(props: &AccountRowProps) -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let account = props.account.clone();
+    let switch_to = {
+        let switch = props.switch.clone();
+        let account = account.clone();
+        let is_active = props.is_active;
+        Callback::from(move |_| {
+            if !is_active {
+                switch.switch_to(account.clone());
+            }
+        })
+    };
+    let rename = {
+        let accounts = props.switch.accounts.clone();
+        let account = account.clone();
+        let prompt = language_ctx.t("accounts_rename_prompt");
+        Callback::from(move |e: MouseEvent| {
+            e.stop_propagation();
+            if let Some(Some(label)) = web_sys::window().and_then(|win| {
+                win.prompt_with_message_and_default(&prompt, &account.label)
+                    .ok()
+            }) {
+                crate::accounts::rename_account(&accounts, &account.pubkey, label);
+            }
+        })
+    };
+    let forget = {
+        let accounts = props.switch.accounts.clone();
+        let pubkey = account.pubkey.clone();
+        let confirm_message = language_ctx.t("accounts_forget_confirm");
+        Callback::from(move |e: MouseEvent| {
+            e.stop_propagation();
+            if let Some(true) =
+                web_sys::window().and_then(|win| win.confirm_with_message(&confirm_message).ok())
+            {
+                crate::accounts::forget_account(&accounts, &pubkey);
+            }
+        })
+    };
+    html! {
+        <div
+            onclick={switch_to}
+            class={classes!(
+                "flex", "items-center", "gap-3", "p-3", "rounded-lg", "cursor-pointer",
+                if props.is_active { "bg-primary" } else { "bg-muted" },
+            )}>
+            <lucide_yew::CircleUser class={classes!("size-6", "flex-shrink-0")} />
+            <div class={classes!("flex-1", "min-w-0")}>
+                <p class={classes!("font-medium", "truncate")}>{ account.display_name() }</p>
+                <p class={classes!("text-xs", "text-muted-foreground", "truncate")}>
+                    { language_ctx.t(account.secret.label_key()) }
+                    { " · " }
+                    { crate::accounts::short_npub(&account.pubkey) }
+                </p>
+            </div>
+            {if props.is_active {
+                html! {
+                    <span class={classes!("text-xs", "font-bold")}>{ language_ctx.t("accounts_active") }</span>
+                }
+            } else {
+                html! {}
+            }}
+            <button onclick={rename} title={language_ctx.t("accounts_rename")}>
+                <lucide_yew::Pencil class={classes!("size-4")} />
+            </button>
+            {if props.is_active {
+                html! {}
+            } else {
+                html! {
+                    <button onclick={forget} title={language_ctx.t("accounts_forget")}>
+                        <lucide_yew::Trash2 class={classes!("size-4")} />
+                    </button>
+                }
+            }}
+        </div>
+    }
+}
+
+/// Saved accounts on the login screen, so a signed-out identity is one click away.
+#[function_component(SavedAccounts)]
+pub fn saved_accounts() -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let switch = crate::accounts::use_account_switch();
+    let accounts = switch.accounts.accounts();
+    if accounts.is_empty() {
+        return html! {};
+    }
+    html! {
+        <div class={classes!("mt-4", "space-y-2")}>
+            <p class={classes!("text-sm", "text-muted-foreground")}>
+                { language_ctx.t("accounts_saved") }
+            </p>
+            { accounts.into_iter().map(|account| {
+                let key = account.pubkey.clone();
+                html! {
+                    <AccountRow
+                        {key}
+                        {account}
+                        is_active={false}
+                        switch={switch.clone()} />
+                }
+            }).collect::<Html>() }
+        </div>
+    }
+}
+
+/// Names the identity that will sign, next to actions that publish or send notes.
+#[function_component(SigningAs)]
+pub fn signing_as() -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let accounts_ctx = crate::accounts::use_accounts();
+    let Some(signer) = crate::signer::use_signer() else {
+        return html! {};
+    };
+    let pubkey = signer.public_key();
+    let name = accounts_ctx.find(&pubkey).map_or_else(
+        || crate::accounts::short_npub(&pubkey),
+        |account| account.display_name(),
+    );
+    html! {
+        <p class={classes!("flex", "items-center", "gap-2", "text-xs", "text-muted-foreground")}>
+            <lucide_yew::PenLine class={classes!("size-4")} />
+            { language_ctx.t("accounts_signing_as").replace("{0}", &name) }
+        </p>
+    }
+}
@@ -0,0 +1,216 @@
+use shady_minions::ui::{Button, ButtonType, ButtonVariant, Form, Input};
+use web_sys::wasm_bindgen::JsCast;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct GameLabelsEditorProps {
+    pub game_id: String,
+}
+
+#[function_component(GameLabelsEditor)]
+pub fn game_labels_editor(props: &GameLabelsEditorProps) -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let labels_ctx = crate::labels::use_game_labels();
+    let backups = crate::backup::use_game_backups();
+    let labels = labels_ctx.labels(&props.game_id);
+
+    let update = {
+        let labels_ctx = labels_ctx.clone();
+        Callback::from(move |labels: crate::labels::GameLabels| {
+            crate::labels::save_labels(&labels_ctx, &backups, labels);
+        })
+    };
+    let add_tag = {
+        let labels = labels.clone();
+        let update = update.clone();
+        Callback::from(move |form: web_sys::HtmlFormElement| {
+            let Some(tag) = form
+                .get_with_name("game-tag")
+                .map(|x| x.unchecked_into::<web_sys::HtmlInputElement>().value())
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty())
+            else {
+                return;
+            };
+            form.reset();
+            let mut labels = labels.clone();
+            if labels.tags.insert(tag) {
+                update.emit(labels);
+            }
+        })
+    };
+    let add_collection = {
+        let labels = labels.clone();
+        let labels_ctx = labels_ctx.clone();
+        let update = update.clone();
+        Callback::from(move |form: web_sys::HtmlFormElement| {
+            let Some(name) = form
+                .get_with_name("game-collection")
+                .map(|x| x.unchecked_into::<web_sys::HtmlInputElement>().value())
+            else {
+                return;
+            };
+            let Some(collection) = crate::labels::create_collection(&labels_ctx, &name) else {
+                return;
+            };
+            form.reset();
+            let mut labels = labels.clone();
+            if labels.collections.insert(collection.name) {
+                update.emit(labels);
+            }
+        })
+    };
+
+    html! {
+        <div class="space-y-3 text-white">
+            <div class="flex flex-wrap gap-2">
+                {for labels.tags.iter().map(|tag| {
+                    let remove = {
+                        let labels = labels.clone();
+                        let update = update.clone();
+                        let tag = tag.clone();
+                        Callback::from(move |_| {
+                            let mut labels = labels.clone();
+                            labels.tags.remove(&tag);
+                            update.emit(labels);
+                        })
+                    };
+                    html! {
+                        <span class="flex items-center gap-1 px-2 py-1 rounded-full bg-primary text-xs">
+                            <lucide_yew::Tag class="size-3" />
+                            {tag}
+                            <button onclick={remove}><lucide_yew::X class="size-3" /></button>
+                        </span>
+                    }
+                })}
+            </div>
+            <Form onsubmit={add_tag} class="flex gap-2">
+                <Input
+                    id="game-tag"
+                    name="game-tag"
+                    placeholder={language_ctx.t("labels_add_tag")}
+                />
+                <Button r#type={ButtonType::Submit} variant={ButtonVariant::Outline}>
+                    <lucide_yew::Plus class="size-4" />
+                </Button>
+            </Form>
+            <div class="flex flex-col gap-1">
+                <span class="text-sm font-bold">{language_ctx.t("labels_collections")}</span>
+                {for labels_ctx.collections().into_iter().map(|collection| {
+                    let is_member = labels.collections.contains(&collection.name);
+                    let toggle = {
+                        let labels = labels.clone();
+                        let update = update.clone();
+                        let name = collection.name.clone();
+                        Callback::from(move |_| {
+                            let mut labels = labels.clone();
+                            if !labels.collections.remove(&name) {
+                                labels.collections.insert(name.clone());
+                            }
+                            update.emit(labels);
+                        })
+                    };
+                    html! {
+                        <label class="flex items-center gap-2 text-sm">
+                            <input type="checkbox" checked={is_member} onchange={toggle} />
+                            {collection.name}
+                        </label>
+                    }
+                })}
+            </div>
+            <Form onsubmit={add_collection} class="flex gap-2">
+                <Input
+                    id="game-collection"
+                    name="game-collection"
+                    placeholder={language_ctx.t("labels_new_collection")}
+                />
+                <Button r#type={ButtonType::Submit} variant={ButtonVariant::Outline}>
+                    <lucide_yew::FolderPlus class="size-4" />
+                </Button>
+            </Form>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct LabelFilterSelectProps {
+    pub filter: UseStateHandle<Option<crate::labels::LabelFilter>>,
+}
+
+/// Narrows the games list to one tag or collection.
+#[function_component(LabelFilterSelect)]
+pub fn label_filter_select(props: &LabelFilterSelectProps) -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let labels_ctx = crate::labels::use_game_labels();
+    let tags = labels_ctx.tags();
+    let collections = labels_ctx.collections();
+    let onchange = {
+        let filter = props.filter.clone();
+        Callback::from(move |e: Event| {
+            let Some(select) = e.target_dyn_into::<web_sys::HtmlSelectElement>() else {
+                return;
+            };
+            let value = select.value();
+            filter.set(if let Some(tag) = value.strip_prefix("tag:") {
+                Some(crate::labels::LabelFilter::Tag(tag.to_string()))
+            } else {
+                value
+                    .strip_prefix("collection:")
+                    .map(|name| crate::labels::LabelFilter::Collection(name.to_string()))
+            });
+        })
+    };
+    let selected = match &*props.filter {
+        Some(crate::labels::LabelFilter::Tag(tag)) => format!("tag:{tag}"),
+        Some(crate::labels::LabelFilter::Collection(name)) => format!("collection:{name}"),
+        None => String::new(),
+    };
+    let delete_collection = {
+        let labels_ctx = labels_ctx.clone();
+        let filter = props.filter.clone();
+        Callback::from(move |_| {
+            if let Some(crate::labels::LabelFilter::Collection(name)) = &*filter {
+                crate::labels::delete_collection(&labels_ctx, name);
+                filter.set(None);
+            }
+        })
+    };
+    html! {
+        <div class="flex items-center gap-2">
+            <select
+                class="h-10 rounded-md border border-border bg-background px-3 text-sm text-white"
+                {onchange}>
+                <option value="" selected={selected.is_empty()}>{language_ctx.t("labels_all_games")}</option>
+                <optgroup label={language_ctx.t("labels_collections")}>
+                    {for collections.iter().map(|collection| {
+                        let value = format!("collection:{}", collection.name);
+                        let is_selected = value == selected;
+                        html! {
+                            <option selected={is_selected} {value}>{&collection.name}</option>
+                        }
+                    })}
+                </optgroup>
+                <optgroup label={language_ctx.t("labels_tags")}>
+                    {for tags.iter().map(|tag| {
+                        let value = format!("tag:{tag}");
+                        let is_selected = value == selected;
+                        html! {
+                            <option selected={is_selected} {value}>{tag}</option>
+                        }
+                    })}
+                </optgroup>
+            </select>
+            {if matches!(&*props.filter, Some(crate::labels::LabelFilter::Collection(_))) {
+                html! {
+                    <Button
+                        variant={ButtonVariant::Outline}
+                        onclick={delete_collection}>
+                        <lucide_yew::FolderX class="size-5" />
+                    </Button>
+                }
+            } else {
+                html! {}
+            }}
+        </div>
+    }
+}
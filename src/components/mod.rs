@@ -1,5 +1,9 @@
+mod accounts;
+mod labels;
 mod notifications;
 mod profile;
+pub use accounts::*;
+pub use labels::*;
 pub use notifications::*;
 pub use profile::*;
 
@@ -131,6 +135,14 @@ pub fn js_chess_game(props: &JsChessGameProps) -> Html {
         });
     }
 
+    // Only games opened from the database have labels to edit.
+    let saved_id = (!game_id.is_empty()).then(|| game_id.clone());
+    let own_pubkey = crate::signer::use_signer().map(|signer| signer.public_key());
+    let author = game_context
+        .rooky_game_entries()
+        .iter()
+        .find(|entry| entry.id == game_id)
+        .and_then(|entry| crate::live_game::game_author(entry, own_pubkey.as_deref()));
     html! {
         <>
         <Card class="h-fit w-fit">
@@ -167,7 +179,10 @@ pub fn js_chess_game(props: &JsChessGameProps) -> Html {
                 </CardTitle>
             </CardHeader>
             <CardContent>
-            <GameCard pgn_game={props.game.clone()}  />
+            <GameCard
+                pgn_game={props.game.clone()}
+                game_id={saved_id}
+                {author} />
             <div class="flex flex-col gap-2 p-6">
                 <ShareRookyGame ..props.clone() />
                 <DirectMessageRookyGame ..props.clone() />
@@ -191,6 +206,7 @@ pub fn share_rooky_game_card(props: &JsChessGameProps) -> Html {
                 </CardDescription>
             </CardHeader>
             <CardContent class="flex flex-col gap-2">
+                <SigningAs />
                 <ShareRookyGame ..props.clone() />
                 <DirectMessageRookyGame ..props.clone() />
                 <SaveTxtRookyGame ..props.clone() />
@@ -206,7 +222,8 @@ pub fn share_rooky_game(props: &JsChessGameProps) -> Html {
     let relay_ctx = use_context::<nostr_minions::relay_pool::NostrRelayPoolStore>()
         .expect("Relay context not found");
     let game_ctx = crate::live_game::use_game_history();
-    let Some(keypair) = nostr_minions::key_manager::use_nostr_key() else {
+    let outbox = crate::outbox::use_outbox();
+    let Some(keypair) = crate::signer::use_signer() else {
         return html! {
             <lucide_yew::Share2 class={classes!("size-5", "bg-muted", "text-muted-foreground")} />
         };
@@ -217,24 +234,34 @@ pub fn share_rooky_game(props: &JsChessGameProps) -> Html {
         let relay_ctx = relay_ctx.clone();
         let game_ctx = game_ctx.dispatcher();
         Callback::from(move |_| {
-            let mut game_note: nostr_minions::nostro2::NostrNote = game.clone().into();
-            if keypair.sign_note(&mut game_note).is_err() {
-                web_sys::console::error_1(&"Failed to sign note".into());
-                nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
-                    "Failed to sign note",
+            let keypair = keypair.clone();
+            let game = game.clone();
+            let relay_ctx = relay_ctx.clone();
+            let game_ctx = game_ctx.clone();
+            let outbox = outbox.clone();
+            yew::platform::spawn_local(async move {
+                let mut game_note: nostr_minions::nostro2::NostrNote = game.into();
+                if keypair.sign_note(&mut game_note).await.is_err() {
+                    web_sys::console::error_1(&"Failed to sign note".into());
+                    nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
+                        "Failed to sign note",
+                    )
+                    .show();
+                    return;
+                }
+                let game_entry = rooky_core::idb::RookyGameEntry {
+                    id: game_note.id.clone().unwrap_or_default(),
+                    note: game_note.clone(),
+                    origin: rooky_core::idb::GameOrigin::Annotated,
+                };
+                game_ctx.dispatch(crate::live_game::AnnotatedGameHistoryAction::AddGame(
+                    game_entry.clone(),
+                ));
+                crate::outbox::publish_note(&outbox, &relay_ctx, game_note);
+                nostr_minions::widgets::toastify::ToastifyOptions::new_success(
+                    "Game shared successfully",
                 )
                 .show();
-                return;
-            }
-            let game_entry = rooky_core::idb::RookyGameEntry {
-                id: game_note.id.clone().unwrap_or_default(),
-                note: game_note.clone(),
-                origin: rooky_core::idb::GameOrigin::Annotated,
-            };
-            game_ctx.dispatch(crate::live_game::AnnotatedGameHistoryAction::AddGame(
-                game_entry.clone(),
-            ));
-            yew::platform::spawn_local(async move {
                 if game_entry.save_to_store().await.is_err() {
                     web_sys::console::error_1(&"Failed to save game".into());
                     nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
@@ -243,11 +270,6 @@ pub fn share_rooky_game(props: &JsChessGameProps) -> Html {
                     .show();
                 }
             });
-            relay_ctx.send(game_note);
-            nostr_minions::widgets::toastify::ToastifyOptions::new_success(
-                "Game shared successfully",
-            )
-            .show();
         })
     };
 
@@ -259,14 +281,14 @@ pub fn share_rooky_game(props: &JsChessGameProps) -> Html {
         </Button>
     }
 }
-use nostr_minions::nostro2_signer::nostro2_nips::Nip17;
 #[function_component(DirectMessageRookyGame)]
 pub fn dm_rooky_game(props: &JsChessGameProps) -> Html {
     let language_ctx = crate::contexts::language::use_language_ctx();
     let relay_ctx = use_context::<nostr_minions::relay_pool::NostrRelayPoolStore>()
         .expect("Relay context not found");
     let game_ctx = crate::live_game::use_game_history();
-    let Some(keypair) = nostr_minions::key_manager::use_nostr_key() else {
+    let outbox = crate::outbox::use_outbox();
+    let Some(keypair) = crate::signer::use_signer() else {
         return html! {
             <lucide_yew::Share2 class={classes!("size-5", "bg-muted", "text-muted-foreground")} />
         };
@@ -289,33 +311,55 @@ pub fn dm_rooky_game(props: &JsChessGameProps) -> Html {
                 .show();
                 return;
             };
-            let mut note = game.clone().into();
-            if keypair.sign_note(&mut note).is_err() {
-                web_sys::console::error_1(&"Failed to sign note".into());
-                nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
-                    "Failed to sign note",
-                )
-                .show();
-                return;
-            }
-            let note_entry = rooky_core::idb::RookyGameEntry {
-                id: note.id.clone().unwrap_or_default(),
-                note: note.clone(),
-                origin: rooky_core::idb::GameOrigin::Annotated,
-            };
-            if let Ok(dm_game) = keypair.private_dm(&game.to_pgn(), &recipient) {
-                relay_ctx.send(dm_game);
-                game_ctx.dispatch(crate::live_game::AnnotatedGameHistoryAction::AddGame(
-                    note_entry.clone(),
-                ));
-            } else {
-                web_sys::console::error_1(&"Failed to send DM".into());
-                nostr_minions::widgets::toastify::ToastifyOptions::new_failure("Failed to send DM")
+            let keypair = keypair.clone();
+            let game = game.clone();
+            let relay_ctx = relay_ctx.clone();
+            let game_ctx = game_ctx.clone();
+            let outbox = outbox.clone();
+            yew::platform::spawn_local(async move {
+                let mut note = game.clone().into();
+                if keypair.sign_note(&mut note).await.is_err() {
+                    web_sys::console::error_1(&"Failed to sign note".into());
+                    nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
+                        "Failed to sign note",
+                    )
                     .show();
-                return;
-            }
-            nostr_minions::widgets::toastify::ToastifyOptions::new_success("DM sent successfully")
+                    return;
+                }
+                let note_entry = rooky_core::idb::RookyGameEntry {
+                    id: note.id.clone().unwrap_or_default(),
+                    note: note.clone(),
+                    origin: rooky_core::idb::GameOrigin::Annotated,
+                };
+                let recipient = crate::accounts::hex_pubkey(&recipient).unwrap_or(recipient);
+                if let Ok(dm_game) = keypair.private_dm(&game.to_pgn(), &recipient).await {
+                    crate::outbox::publish_note(&outbox, &relay_ctx, dm_game.clone());
+                    game_ctx.dispatch(crate::live_game::AnnotatedGameHistoryAction::AddGame(
+                        note_entry.clone(),
+                    ));
+                    // NIP-17: also deliver to the inbox relays the recipient asked for, if any.
+                    let pool = relay_ctx.relay_health().keys().cloned().collect::<Vec<_>>();
+                    let dm_relays = crate::fetch_dm_relays(&pool, &recipient).await;
+                    if !dm_relays.is_empty()
+                        && crate::deliver_to_dm_relays(&dm_relays, &dm_game).await == 0
+                    {
+                        web_sys::console::error_1(
+                            &"No DM relay of the recipient accepted the game".into(),
+                        );
+                    }
+                } else {
+                    web_sys::console::error_1(&"Failed to send DM".into());
+                    nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
+                        "Failed to send DM",
+                    )
+                    .show();
+                    return;
+                }
+                nostr_minions::widgets::toastify::ToastifyOptions::new_success(
+                    "DM sent successfully",
+                )
                 .show();
+            });
         })
     };
 
@@ -348,6 +392,12 @@ pub fn dm_rooky_game(props: &JsChessGameProps) -> Html {
 #[derive(Properties, PartialEq)]
 pub struct GameCardProps {
     pub pgn_game: rooky_core::RookyGame,
+    /// Set for saved games, to show their tags and collections.
+    #[prop_or_default]
+    pub game_id: Option<String>,
+    /// Set for games from someone else, to show who sent or published them.
+    #[prop_or_default]
+    pub author: Option<String>,
 }
 
 #[function_component(GameCard)]
@@ -383,6 +433,11 @@ pub fn game_card(props: &GameCardProps) -> Html {
                     <h3 class="text-lg font-bold text-white">
                         { format!("{white_name} vs {black_name}") }
                     </h3>
+                    {if let Some(pubkey) = props.author.clone() {
+                        html! { <ProfileBadge {pubkey} class="text-sm text-white" /> }
+                    } else {
+                        html! {}
+                    }}
                     <div class="flex justify-between text-white">
                         <span class="text-sm font-bold">{language_ctx.t("game_details_date")}</span>
                         <span class="text-sm">{ date.format("%Y-%m-%d").to_string() }</span>
@@ -438,6 +493,16 @@ pub fn game_card(props: &GameCardProps) -> Html {
                     }
                 </div>
                 <div id="separator" class="h-[0.5px] bg-secondary my-4" />
+                {if let Some(game_id) = props.game_id.clone() {
+                    html! {
+                        <>
+                            <GameLabelsEditor {game_id} />
+                            <div class="h-[0.5px] bg-secondary my-4" />
+                        </>
+                    }
+                } else {
+                    html! {}
+                }}
             </>
     }
 }
@@ -448,36 +513,38 @@ pub fn save_txt_rooky_game(props: &JsChessGameProps) -> Html {
     use nostr_minions::browser_api::IdbStoreManager;
     let game = props.game.clone();
     let game_context = crate::live_game::use_game_history();
-    let keypair = nostr_minions::key_manager::use_nostr_key();
+    let keypair = crate::signer::use_signer();
     let onclick = {
         let game = game.clone();
         let keypair = keypair.clone();
+        let game_context = game_context.dispatcher();
         Callback::from(move |_| {
-            let mut note = game.clone().into();
-            if let Some(keypair) = keypair.as_ref() {
-                if keypair.sign_note(&mut note).is_err() {
-                    nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
-                        "Failed to sign note",
-                    )
-                    .show();
-                    return;
-                }
-            } else {
+            let Some(keypair) = keypair.clone() else {
                 nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
                     "No Nostr keypair found",
                 )
                 .show();
                 return;
-            }
-            let id = note.id.clone().unwrap_or_default();
-            let note_entry = rooky_core::idb::RookyGameEntry {
-                id: id.clone(),
-                note,
-                origin: rooky_core::idb::GameOrigin::Annotated,
             };
-            let note_entry_clone = note_entry.clone();
+            let game = game.clone();
+            let game_context = game_context.clone();
             yew::platform::spawn_local(async move {
-                note_entry_clone
+                let mut note = game.clone().into();
+                if keypair.sign_note(&mut note).await.is_err() {
+                    nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
+                        "Failed to sign note",
+                    )
+                    .show();
+                    return;
+                }
+                let id = note.id.clone().unwrap_or_default();
+                let note_entry = rooky_core::idb::RookyGameEntry {
+                    id: id.clone(),
+                    note,
+                    origin: rooky_core::idb::GameOrigin::Annotated,
+                };
+                note_entry
+                    .clone()
                     .save_to_store()
                     .await
                     .unwrap_or_else(|err| {
@@ -488,31 +555,31 @@ pub fn save_txt_rooky_game(props: &JsChessGameProps) -> Html {
                         )
                         .show();
                     });
-            });
-            game_context.dispatch(crate::live_game::AnnotatedGameHistoryAction::AddGame(
-                note_entry,
-            ));
-            let blob_parts = web_sys::js_sys::Array::new();
-            blob_parts.push(&web_sys::wasm_bindgen::JsValue::from_str(&game.to_pgn()));
-            let blob = web_sys::Blob::new_with_str_sequence(&blob_parts).unwrap();
+                game_context.dispatch(crate::live_game::AnnotatedGameHistoryAction::AddGame(
+                    note_entry,
+                ));
+                let blob_parts = web_sys::js_sys::Array::new();
+                blob_parts.push(&web_sys::wasm_bindgen::JsValue::from_str(&game.to_pgn()));
+                let blob = web_sys::Blob::new_with_str_sequence(&blob_parts).unwrap();
 
-            let url = web_sys::Url::create_object_url_with_blob(&blob).unwrap();
-            let a = web_sys::window()
-                .unwrap()
-                .document()
-                .unwrap()
-                .create_element("a")
-                .unwrap();
-            a.set_attribute("href", &url).unwrap();
-            a.set_attribute("download", &format!("game-{id}.pgn"))
-                .unwrap();
-            a.dispatch_event(&web_sys::MouseEvent::new("click").unwrap())
-                .unwrap();
-            web_sys::Url::revoke_object_url(&url).unwrap();
-            nostr_minions::widgets::toastify::ToastifyOptions::new_success(
-                "Game saved successfully",
-            )
-            .show();
+                let url = web_sys::Url::create_object_url_with_blob(&blob).unwrap();
+                let a = web_sys::window()
+                    .unwrap()
+                    .document()
+                    .unwrap()
+                    .create_element("a")
+                    .unwrap();
+                a.set_attribute("href", &url).unwrap();
+                a.set_attribute("download", &format!("game-{id}.pgn"))
+                    .unwrap();
+                a.dispatch_event(&web_sys::MouseEvent::new("click").unwrap())
+                    .unwrap();
+                web_sys::Url::revoke_object_url(&url).unwrap();
+                nostr_minions::widgets::toastify::ToastifyOptions::new_success(
+                    "Game saved successfully",
+                )
+                .show();
+            });
         })
     };
 
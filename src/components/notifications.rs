@@ -37,6 +37,7 @@ pub fn notification_icon() -> Html {
                                     >
                                         <div class="rounded hover:bg-primary transition-colors">
                                             <p class="font-semibold">{format!("{}: {}", language_ctx.t("notifications_new_game"), &game.note.id.clone().unwrap_or_default()[..8])}</p>
+                                            <crate::components::ProfileBadge pubkey={game.note.pubkey.clone()} class="text-sm" />
                                             <p class="text-sm text-gray-300">{format!("{} {} {}", rooky_game.white, language_ctx.t("common_versus"), rooky_game.black)}</p>
                                         </div>
                                 </yew_router::components::Link<crate::router::MainRoute>>
@@ -1,19 +1,102 @@
+use shady_minions::ui::{
+    Button, ButtonVariant, Card, CardContent, CardDescription, CardHeader, CardTitle, Form, Input,
+    Modal,
+};
+use web_sys::wasm_bindgen::JsCast;
 use yew::prelude::*;
 
+/// Kind-0 profile metadata. Fields this app does not know about are kept in `extra`
+/// so that publishing an edit never drops what other clients wrote.
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct NostrMetadata {
+    #[serde(default, deserialize_with = "lenient_name")]
     pub name: String,
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub display_name: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub about: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub picture: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub banner: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub website: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub nip05: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub lud16: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub fide_id: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub lichess: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub chesscom: Option<String>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Reads a text field other clients may have written as a number or boolean, such as a
+/// numeric `fide_id`; any other value is read as missing.
+fn lenient_string<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    Ok(
+        match <serde_json::Value as serde::Deserialize>::deserialize(deserializer)? {
+            serde_json::Value::String(value) => Some(value),
+            serde_json::Value::Number(value) => Some(value.to_string()),
+            serde_json::Value::Bool(value) => Some(value.to_string()),
+            _ => None,
+        },
+    )
+}
+
+fn lenient_name<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(lenient_string(deserializer)?.unwrap_or_default())
 }
 
 impl Default for NostrMetadata {
     fn default() -> Self {
-        Self {
-            name: "Anon".to_string(),
-            about: None,
-            picture: None,
-        }
+        Self::new("Anon".to_string(), None, None)
     }
 }
 
@@ -28,8 +111,8 @@ impl TryFrom<&nostr_minions::nostro2::NostrNote> for NostrMetadata {
     type Error = nostr_minions::nostro2::errors::NostrErrors;
     fn try_from(note: &nostr_minions::nostro2::NostrNote) -> Result<Self, Self::Error> {
         if note.kind != 0 {
-            return Err(nostr_minions::nostro2::errors::NostrErrors::from(
-                "Wrong Kind - expected kind 0",
+            return Err(nostr_minions::nostro2::errors::NostrErrors::SerdeError(
+                serde::de::Error::custom("Wrong Kind - expected kind 0"),
             ));
         }
         let metadata: Self = note.content.parse()?;
@@ -38,52 +121,76 @@ impl TryFrom<&nostr_minions::nostro2::NostrNote> for NostrMetadata {
 }
 
 impl NostrMetadata {
-    pub const fn new(name: String, about: Option<String>, picture: Option<String>) -> Self {
+    #[must_use]
+    pub fn new(name: String, about: Option<String>, picture: Option<String>) -> Self {
         Self {
             name,
+            display_name: None,
             about,
             picture,
+            banner: None,
+            website: None,
+            nip05: None,
+            lud16: None,
+            fide_id: None,
+            lichess: None,
+            chesscom: None,
+            extra: serde_json::Map::new(),
         }
     }
 
+    /// The name to show: `display_name` when set, otherwise `name`.
+    #[must_use]
+    pub fn shown_name(&self) -> Option<String> {
+        self.display_name
+            .clone()
+            .filter(|name| !name.is_empty())
+            .or_else(|| Some(self.name.clone()).filter(|name| !name.is_empty()))
+    }
+
     /// # Errors
     /// Returns a `serde_json::Error` if the struct cannot be serialized to JSON.
     /// This can happen if the struct contains invalid data that cannot be represented in JSON.
     pub fn to_json_string(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string(self)
     }
+
+    /// An unsigned kind-0 note publishing this metadata for `pubkey`.
+    ///
+    /// # Errors
+    /// Returns a `serde_json::Error` if the metadata cannot be serialized.
+    pub fn to_note(
+        &self,
+        pubkey: String,
+    ) -> Result<nostr_minions::nostro2::NostrNote, serde_json::Error> {
+        Ok(nostr_minions::nostro2::NostrNote {
+            kind: 0,
+            pubkey,
+            content: self.to_json_string()?,
+            ..Default::default()
+        })
+    }
 }
 
-/// A profile card component that uses the UserMetadataProvider context
+/// The signed-in user's kind-0 profile, read from the profile cache and editable in place.
 #[function_component(UserProfileCard)]
 pub fn user_profile_card() -> Html {
     // Get contexts needed
     let language_ctx = crate::contexts::language::use_language_ctx();
-    let relay_ctx = use_context::<nostr_minions::relay_pool::NostrRelayPoolStore>()
-        .expect("Relay context not found");
-    let pubkey = nostr_minions::key_manager::use_nostr_key();
+    let pubkey = crate::signer::use_signer();
     let copied_hex = use_state(|| false);
     let copied_npub = use_state(|| false);
-
-    let relay_clone = relay_ctx.clone();
-    use_memo(pubkey.clone(), move |pubkey| {
-        if let Some(pubkey) = pubkey.as_ref().map(|pk| pk.public_key()) {
-            let filter = nostr_minions::nostro2::NostrSubscription {
-                kinds: vec![0].into(), // Kind 0 for user metadata
-                authors: vec![pubkey.clone()].into(),
-                ..Default::default()
-            };
-            relay_clone.send(filter);
-        }
-    });
-    let metadata = use_memo(relay_ctx.unique_notes.clone(), move |notes| {
-        let note = notes.last()?;
-        (note.kind == 0)
-            .then(|| NostrMetadata::try_from(note).ok())
-            .flatten()
-    });
+    let metadata =
+        crate::profiles::use_profile(pubkey.as_ref().map(crate::signer::BunkerSigner::public_key));
+    let profile_cache = crate::profiles::use_profile_cache();
+    // Editing before the newest kind-0 arrives would publish over it.
+    let is_loaded = pubkey
+        .as_ref()
+        .is_some_and(|signer| profile_cache.is_settled(&signer.public_key()));
+    let editing = use_state(|| false);
 
     html! {
+        <>
         <div class="p-6">
             <div class="flex items-start gap-6">
                 // <div class="flex-shrink-0">
@@ -98,11 +205,12 @@ pub fn user_profile_card() -> Html {
                 <div class="flex-1 space-y-4">
                     <div>
                         <h2 class="text-xl font-semibold mb-2">
-                            {metadata.as_ref().as_ref().map(|p| p.name.clone()).unwrap_or_else(|| language_ctx.t("anonymous_user"))}
+                            {metadata.as_ref().and_then(NostrMetadata::shown_name).unwrap_or_else(|| language_ctx.t("anonymous_user"))}
                         </h2>
                         <p class="text-sm text-muted leading-relaxed">
-                            {metadata.as_ref().as_ref().and_then(|p| p.about.clone()).unwrap_or_else(|| language_ctx.t("no_bio"))}
+                            {metadata.as_ref().and_then(|p| p.about.clone()).unwrap_or_else(|| language_ctx.t("no_bio"))}
                         </p>
+                        {metadata.as_ref().map(|metadata| html! { <ProfileDetails metadata={metadata.clone()} /> }).unwrap_or_default()}
                     </div>
 
                     <div class="grid grid-cols-2 gap-4">
@@ -152,7 +260,7 @@ pub fn user_profile_card() -> Html {
                                     let pubkey_clone = pubkey.clone();
                                     let copied = copied_npub.setter();
                                     Callback::from(move |_| {
-                                        if let Some(pubkey) = pubkey_clone.as_ref().and_then(|pk| pk.npub().ok()) {
+                                        if let Some(pubkey) = pubkey_clone.as_ref().map(|pk| crate::accounts::npub(&pk.public_key())) {
                                             nostr_minions::browser_api::clipboard_copy(&pubkey);
                                             copied.set(true);
                                             let copied = copied.clone();
@@ -168,7 +276,7 @@ pub fn user_profile_card() -> Html {
                                             if *copied_npub {
                                                 language_ctx.t("notification_copied_to_clipboard")
                                             } else {
-                                                pubkey.as_ref().and_then(|pk| pk.npub().ok()).unwrap_or(
+                                                pubkey.as_ref().map(|pk| crate::accounts::npub(&pk.public_key())).unwrap_or(
                                                 language_ctx.t("profile_no_public_key")
                                                 )
                                             }
@@ -190,13 +298,260 @@ pub fn user_profile_card() -> Html {
             </div>
         </div>
 
-          //   <Button
-          //       // {onclick}
-          //       variant={shady_minions::ui::ButtonVariant::Outline}
-          //       class="w-full text-sm xs:text-base"
-          //   >
-          //       <lucide_yew::Pen class="size-4 mr-2" />
-          //       { language_ctx.t("edit_profile") }
-          //   </Button>
+            <Button
+                onclick={
+                    let editing = editing.clone();
+                    Callback::from(move |_| editing.set(true))
+                }
+                variant={ButtonVariant::Outline}
+                disabled={!is_loaded}
+                class="w-full text-sm xs:text-base"
+            >
+                <lucide_yew::Pen class="size-4 mr-2" />
+                { if is_loaded { language_ctx.t("edit_profile") } else { language_ctx.t("profile_loading") } }
+            </Button>
+            <Modal is_open={editing.clone()}>
+                <ProfileEditor
+                    metadata={metadata.clone().unwrap_or_else(|| NostrMetadata::new(String::new(), None, None))}
+                    onsaved={Callback::from(move |_| editing.set(false))} />
+            </Modal>
+        </>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ProfileBadgeProps {
+    pub pubkey: String,
+    #[prop_or_default]
+    pub class: Classes,
+}
+
+/// Avatar and name for any pubkey, falling back to a short npub until its profile arrives.
+#[function_component(ProfileBadge)]
+pub fn profile_badge(props: &ProfileBadgeProps) -> Html {
+    let metadata = crate::profiles::use_profile(Some(props.pubkey.clone()));
+    let name = metadata
+        .as_ref()
+        .and_then(NostrMetadata::shown_name)
+        .unwrap_or_else(|| crate::accounts::short_npub(&props.pubkey));
+    let picture = metadata
+        .and_then(|metadata| metadata.picture)
+        .filter(|picture| picture.starts_with("https://") || picture.starts_with("http://"));
+    html! {
+        <span class={classes!("inline-flex", "items-center", "gap-2", "min-w-0", props.class.clone())}>
+            {if let Some(src) = picture {
+                html! { <img {src} alt="" class="size-5 flex-shrink-0 rounded-full object-cover" /> }
+            } else {
+                html! { <lucide_yew::CircleUser class="size-5 flex-shrink-0" /> }
+            }}
+            <span class="truncate">{ name }</span>
+        </span>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ProfileDetailsProps {
+    pub metadata: NostrMetadata,
+}
+
+#[function_component(ProfileDetails)]
+fn profile_details(props: &ProfileDetailsProps) -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let metadata = &props.metadata;
+    let details = [
+        ("profile_nip05", metadata.nip05.clone()),
+        ("profile_website", metadata.website.clone()),
+        ("profile_lud16", metadata.lud16.clone()),
+        ("profile_fide_id", metadata.fide_id.clone()),
+        ("profile_lichess", metadata.lichess.clone()),
+        ("profile_chesscom", metadata.chesscom.clone()),
+    ];
+    html! {
+        <dl class="grid grid-cols-2 gap-x-4 gap-y-1 mt-2 text-sm">
+            { details.into_iter().filter_map(|(label, value)| {
+                let value = value.filter(|value| !value.is_empty())?;
+                Some(html! {
+                    <>
+                        <dt class="text-muted">{ language_ctx.t(label) }</dt>
+                        <dd class="truncate">{ value }</dd>
+                    </>
+                })
+            }).collect::<Html>() }
+        </dl>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ProfileEditorProps {
+    pub metadata: NostrMetadata,
+    pub onsaved: Callback<NostrMetadata>,
+}
+
+fn form_value(form: &web_sys::HtmlFormElement, name: &str) -> Option<String> {
+    let element = form.get_with_name(name)?;
+    let value = element
+        .dyn_ref::<web_sys::HtmlTextAreaElement>()
+        .map_or_else(
+            || element.unchecked_ref::<web_sys::HtmlInputElement>().value(),
+            web_sys::HtmlTextAreaElement::value,
+        );
+    Some(value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+/// Edits every kind-0 field and publishes the result; unknown fields pass through untouched.
+#[function_component(ProfileEditor)]
+pub fn profile_editor(props: &ProfileEditorProps) -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let relay_ctx = nostr_minions::relay_pool::use_nostr_relay_pool();
+    let outbox = crate::outbox::use_outbox();
+    let profile_cache = crate::profiles::use_profile_cache();
+    let signer = crate::signer::use_signer();
+    let saving = use_state(|| false);
+    let error = use_state(|| None::<String>);
+
+    let onsubmit = {
+        let signer = signer.clone();
+        let metadata = props.metadata.clone();
+        let onsaved = props.onsaved.clone();
+        let saving = saving.clone();
+        let error = error.clone();
+        let invalid_address = language_ctx.t("profile_invalid_address");
+        let invalid_website = language_ctx.t("profile_invalid_website");
+        let sign_failed = language_ctx.t("profile_sign_failed");
+        Callback::from(move |form: web_sys::HtmlFormElement| {
+            let Some(signer) = signer.clone() else {
+                return;
+            };
+            let updated = NostrMetadata {
+                name: form_value(&form, "name").unwrap_or_default(),
+                display_name: form_value(&form, "display_name"),
+                about: form_value(&form, "about"),
+                picture: form_value(&form, "picture"),
+                banner: form_value(&form, "banner"),
+                website: form_value(&form, "website"),
+                nip05: form_value(&form, "nip05"),
+                lud16: form_value(&form, "lud16"),
+                fide_id: form_value(&form, "fide_id"),
+                lichess: form_value(&form, "lichess"),
+                chesscom: form_value(&form, "chesscom"),
+                extra: metadata.extra.clone(),
+            };
+            let is_address = |value: &Option<String>| {
+                value.as_ref().is_none_or(|value| {
+                    value
+                        .split_once('@')
+                        .is_some_and(|(name, domain)| !name.is_empty() && domain.contains('.'))
+                })
+            };
+            if !is_address(&updated.nip05) || !is_address(&updated.lud16) {
+                error.set(Some(invalid_address.clone()));
+                return;
+            }
+            if !updated.website.as_ref().is_none_or(|website| {
+                website.starts_with("https://") || website.starts_with("http://")
+            }) {
+                error.set(Some(invalid_website.clone()));
+                return;
+            }
+            error.set(None);
+            saving.set(true);
+            let relay_ctx = relay_ctx.clone();
+            let outbox = outbox.clone();
+            let profile_cache = profile_cache.clone();
+            let onsaved = onsaved.clone();
+            let saving = saving.clone();
+            let sign_failed = sign_failed.clone();
+            yew::platform::spawn_local(async move {
+                let signed = match updated.to_note(signer.public_key()) {
+                    Ok(mut note) => signer.sign_note(&mut note).await.is_ok().then_some(note),
+                    Err(_) => None,
+                };
+                if let Some(note) = signed {
+                    // Shown right away, before any relay echoes it back.
+                    crate::profiles::cache_profile_note(&profile_cache, &note);
+                    crate::outbox::publish_note(&outbox, &relay_ctx, note);
+                    onsaved.emit(updated);
+                } else {
+                    crate::toast_failure(&sign_failed);
+                }
+                saving.set(false);
+            });
+        })
+    };
+
+    let metadata = &props.metadata;
+    let field = |name: &'static str, label: &'static str, value: Option<String>| {
+        html! {
+            <div>
+                <label class="block text-sm font-medium mb-1">{ language_ctx.t(label) }</label>
+                <Input
+                    name={name}
+                    r#type={shady_minions::ui::InputType::Text}
+                    value={value.unwrap_or_default()}
+                    class="w-full" />
+            </div>
+        }
+    };
+    html! {
+        <Card class="max-w-lg max-h-[90vh] overflow-y-auto">
+            <CardHeader>
+                <CardTitle>{ language_ctx.t("edit_profile") }</CardTitle>
+                <CardDescription>{ language_ctx.t("profile_editor_description") }</CardDescription>
+            </CardHeader>
+            <CardContent>
+                <Form {onsubmit} class="flex flex-col gap-3">
+                    { field("name", "profile_name", Some(metadata.name.clone())) }
+                    { field("display_name", "profile_display_name", metadata.display_name.clone()) }
+                    <div>
+                        <label class="block text-sm font-medium mb-1">{ language_ctx.t("profile_about") }</label>
+                        <textarea
+                            name="about"
+                            rows="3"
+                            value={metadata.about.clone().unwrap_or_default()}
+                            class="w-full rounded-md border border-input bg-transparent px-3 py-2 text-sm" />
+                    </div>
+                    { field("picture", "profile_picture", metadata.picture.clone()) }
+                    { field("banner", "profile_banner", metadata.banner.clone()) }
+                    { field("website", "profile_website", metadata.website.clone()) }
+                    { field("nip05", "profile_nip05", metadata.nip05.clone()) }
+                    { field("lud16", "profile_lud16", metadata.lud16.clone()) }
+                    <h3 class="text-lg font-medium text-muted mt-2">{ language_ctx.t("profile_chess_section") }</h3>
+                    { field("fide_id", "profile_fide_id", metadata.fide_id.clone()) }
+                    { field("lichess", "profile_lichess", metadata.lichess.clone()) }
+                    { field("chesscom", "profile_chesscom", metadata.chesscom.clone()) }
+                    {if let Some(error) = (*error).clone() {
+                        html! { <p class="text-sm text-red-500">{ error }</p> }
+                    } else {
+                        html! {}
+                    }}
+                    <Button
+                        r#type={shady_minions::ui::ButtonType::Submit}
+                        disabled={*saving || signer.is_none()}>
+                        { language_ctx.t("profile_publish") }
+                    </Button>
+                </Form>
+            </CardContent>
+        </Card>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_numeric_fields_and_keeps_unknown_ones() {
+        let metadata = r#"{"name":"Judit","fide_id":700070,"lud16":null,"badges":[1,2]}"#
+            .parse::<NostrMetadata>()
+            .expect("metadata parses");
+        assert_eq!(metadata.name, "Judit");
+        assert_eq!(metadata.fide_id.as_deref(), Some("700070"));
+        assert_eq!(metadata.lud16, None);
+        assert_eq!(
+            metadata.extra.get("badges"),
+            Some(&serde_json::json!([1, 2]))
+        );
+        let metadata = r#"{"name":42}"#.parse::<NostrMetadata>().expect("metadata parses");
+        assert_eq!(metadata.name, "42");
     }
 }
@@ -0,0 +1,624 @@
+//! Identities kept on this device and switching between them.
+//!
+//! Each account keeps its own games (through `GameOwner`), relay list, tracked Lichess
+//! accounts and inbox sync time; the last three are moved in and out of their stores on
+//! every switch. Labels, backups and revisions are keyed by game id, so they follow the
+//! games they belong to. Collections, import history and the outbox stay shared by every
+//! account on the device: outbox notes are already signed and publish the same whoever is
+//! active.
+
+use std::rc::Rc;
+
+use nostr_minions::browser_api::IdbStoreManager;
+use web_sys::wasm_bindgen::JsCast;
+use yew::prelude::*;
+
+/// How an account signs in again when switched to.
+///
+/// No variant holds a plaintext key: an unprotected local key stays in the browser as a
+/// [`ParkedKey`] and the account only refers to it.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum AccountSecret {
+    Local,
+    Ncryptsec(String),
+    Extension,
+    Remote(crate::nip46::RemoteSignerSession),
+}
+
+impl AccountSecret {
+    /// Translation key describing the sign-in method.
+    #[must_use]
+    pub const fn label_key(&self) -> &'static str {
+        match self {
+            Self::Local => "accounts_local_key",
+            Self::Ncryptsec(_) => "accounts_protected_key",
+            Self::Extension => "accounts_extension",
+            Self::Remote(_) => "accounts_remote_signer",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AccountRelay {
+    pub url: String,
+    pub read: bool,
+    pub write: bool,
+}
+
+impl From<AccountRelay> for nostr_minions::relay_pool::UserRelay {
+    fn from(relay: AccountRelay) -> Self {
+        Self {
+            url: relay.url,
+            read: relay.read,
+            write: relay.write,
+        }
+    }
+}
+
+impl From<nostr_minions::relay_pool::UserRelay> for AccountRelay {
+    fn from(relay: nostr_minions::relay_pool::UserRelay) -> Self {
+        Self {
+            url: relay.url,
+            read: relay.read,
+            write: relay.write,
+        }
+    }
+}
+
+/// An identity kept on this device, with its own relay list.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct StoredAccount {
+    pub pubkey: String,
+    pub label: String,
+    pub secret: AccountSecret,
+    pub relays: Vec<AccountRelay>,
+    /// Lichess accounts this identity imports from, kept while another account is active.
+    #[serde(default)]
+    pub lichess_accounts: Vec<crate::lichess_sync::TrackedLichessAccount>,
+    /// Where this identity's gift wrap inbox resumes, in milliseconds.
+    #[serde(default)]
+    pub inbox_synced_at: i64,
+    pub last_active_at: u64,
+}
+
+impl StoredAccount {
+    #[must_use]
+    pub fn display_name(&self) -> String {
+        if self.label.is_empty() {
+            short_npub(&self.pubkey)
+        } else {
+            self.label.clone()
+        }
+    }
+}
+
+impl IdbStoreManager for StoredAccount {
+    fn config() -> nostr_minions::browser_api::IdbStoreConfig {
+        crate::idb::store_config("accounts", "pubkey")
+    }
+    fn key(&self) -> web_sys::wasm_bindgen::JsValue {
+        web_sys::wasm_bindgen::JsValue::from_str(&self.pubkey)
+    }
+}
+
+impl From<StoredAccount> for web_sys::wasm_bindgen::JsValue {
+    fn from(value: StoredAccount) -> Self {
+        serde_wasm_bindgen::to_value(&value).unwrap_or_default()
+    }
+}
+
+impl TryFrom<web_sys::wasm_bindgen::JsValue> for StoredAccount {
+    type Error = web_sys::wasm_bindgen::JsValue;
+    fn try_from(value: web_sys::wasm_bindgen::JsValue) -> Result<Self, Self::Error> {
+        Ok(serde_wasm_bindgen::from_value(value)?)
+    }
+}
+
+/// An unprotected local key set aside while another account is active.
+///
+/// It is the same non-serialized `CryptoKey` the active identity is stored as, so switching
+/// never writes the key out as an `nsec`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParkedKey {
+    pub pubkey: String,
+    pub crypto_key: web_sys::CryptoKey,
+}
+
+impl IdbStoreManager for ParkedKey {
+    fn config() -> nostr_minions::browser_api::IdbStoreConfig {
+        crate::idb::store_config("account_keys", "pubkey")
+    }
+    fn key(&self) -> web_sys::wasm_bindgen::JsValue {
+        web_sys::wasm_bindgen::JsValue::from_str(&self.pubkey)
+    }
+}
+
+impl From<ParkedKey> for web_sys::wasm_bindgen::JsValue {
+    fn from(value: ParkedKey) -> Self {
+        let object = web_sys::js_sys::Object::new();
+        let _ = web_sys::js_sys::Reflect::set(&object, &"pubkey".into(), &value.pubkey.into());
+        let _ = web_sys::js_sys::Reflect::set(&object, &"crypto_key".into(), &value.crypto_key);
+        object.into()
+    }
+}
+
+impl TryFrom<web_sys::wasm_bindgen::JsValue> for ParkedKey {
+    type Error = web_sys::wasm_bindgen::JsValue;
+    fn try_from(value: web_sys::wasm_bindgen::JsValue) -> Result<Self, Self::Error> {
+        let pubkey = web_sys::js_sys::Reflect::get(&value, &"pubkey".into())?
+            .as_string()
+            .ok_or_else(|| web_sys::wasm_bindgen::JsValue::from_str("Missing pubkey"))?;
+        let crypto_key = web_sys::js_sys::Reflect::get(&value, &"crypto_key".into())?
+            .dyn_into::<web_sys::CryptoKey>()?;
+        Ok(Self { pubkey, crypto_key })
+    }
+}
+
+impl ParkedKey {
+    /// The active identity slot holding this key again.
+    fn identity(&self) -> Result<nostr_minions::key_manager::UserIdentity, String> {
+        crate::key_lock::local_identity(&self.crypto_key)
+            .map_err(|_| "Invalid stored key".to_string())
+    }
+}
+
+/// Bech32 `npub` for a hex public key, or the hex itself if it is malformed.
+#[must_use]
+pub fn npub(pubkey: &str) -> String {
+    let bytes = (0..pubkey.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(pubkey.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>();
+    bech32::Hrp::parse("npub")
+        .ok()
+        .zip(bytes)
+        .and_then(|(hrp, bytes)| bech32::encode::<bech32::Bech32>(hrp, &bytes).ok())
+        .unwrap_or_else(|| pubkey.to_string())
+}
+
+/// Accepts either an `npub` or a 64 character hex public key, returning the hex form.
+#[must_use]
+pub fn hex_pubkey(input: &str) -> Option<String> {
+    let input = input.trim();
+    if input.starts_with("npub") {
+        let (hrp, data) = bech32::decode(input).ok()?;
+        (hrp.as_str() == "npub" && data.len() == 32)
+            .then(|| data.iter().map(|byte| format!("{byte:02x}")).collect())
+    } else {
+        (input.len() == 64 && input.chars().all(|c| c.is_ascii_hexdigit()))
+            .then(|| input.to_lowercase())
+    }
+}
+
+#[must_use]
+pub fn short_npub(pubkey: &str) -> String {
+    let npub = npub(pubkey);
+    if npub.len() > 16 {
+        format!("{}…{}", &npub[..10], &npub[npub.len() - 6..])
+    } else {
+        npub
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Accounts {
+    accounts: Vec<StoredAccount>,
+}
+
+impl Accounts {
+    /// Accounts with the most recently used first.
+    #[must_use]
+    pub fn accounts(&self) -> Vec<StoredAccount> {
+        let mut accounts = self.accounts.clone();
+        accounts.sort_by(|a, b| b.last_active_at.cmp(&a.last_active_at));
+        accounts
+    }
+    #[must_use]
+    pub fn find(&self, pubkey: &str) -> Option<&StoredAccount> {
+        self.accounts
+            .iter()
+            .find(|account| account.pubkey == pubkey)
+    }
+}
+
+pub enum AccountsAction {
+    Saved(StoredAccount),
+    Removed(String),
+}
+
+impl Reducible for Accounts {
+    type Action = AccountsAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut accounts = self.accounts.clone();
+        match action {
+            AccountsAction::Saved(account) => {
+                if let Some(known) = accounts
+                    .iter_mut()
+                    .find(|known| known.pubkey == account.pubkey)
+                {
+                    *known = account;
+                } else {
+                    accounts.push(account);
+                }
+            }
+            AccountsAction::Removed(pubkey) => accounts.retain(|known| known.pubkey != pubkey),
+        }
+        Rc::new(Self { accounts })
+    }
+}
+
+pub type AccountsStore = UseReducerHandle<Accounts>;
+
+fn save_account(ctx: &UseReducerDispatcher<Accounts>, account: StoredAccount) {
+    let ctx = ctx.clone();
+    yew::platform::spawn_local(async move {
+        if account.clone().save_to_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to save account".into());
+        }
+        ctx.dispatch(AccountsAction::Saved(account));
+    });
+}
+
+/// Renames an account in the switcher.
+pub fn rename_account(ctx: &AccountsStore, pubkey: &str, label: String) {
+    let Some(account) = ctx.find(pubkey).cloned() else {
+        return;
+    };
+    save_account(
+        &ctx.dispatcher(),
+        StoredAccount {
+            label: label.trim().to_string(),
+            ..account
+        },
+    );
+}
+
+/// Removes an account from this device; its games stay until deleted.
+pub fn forget_account(ctx: &AccountsStore, pubkey: &str) {
+    let Some(account) = ctx.find(pubkey).cloned() else {
+        return;
+    };
+    ctx.dispatch(AccountsAction::Removed(account.pubkey.clone()));
+    yew::platform::spawn_local(async move {
+        if let Ok(parked) = ParkedKey::retrieve_from_store::<ParkedKey>(&account.key()).await {
+            if parked.delete_from_store().await.is_err() {
+                web_sys::console::error_1(&"Failed to forget account key".into());
+            }
+        }
+        if account.delete_from_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to forget account".into());
+        }
+    });
+}
+
+/// Signs the active identity out of every slot it may occupy, keeping it in the account list.
+///
+/// An unprotected local key is parked under `active` so the account can be switched back to.
+async fn clear_active_identity(
+    key_ctx: &nostr_minions::key_manager::NostrIdStore,
+    lock_ctx: &crate::key_lock::KeyLockStore,
+    signer_ctx: &crate::signer::SignerStore,
+    active: Option<&str>,
+) {
+    let is_protected = lock_ctx
+        .encrypted()
+        .is_some_and(|encrypted| Some(encrypted.pubkey.as_str()) == active);
+    if let Some(identity) = key_ctx.get_identity().cloned() {
+        if let (false, Some(pubkey), nostr_minions::key_manager::NostrIdType::Local(crypto_key)) =
+            (is_protected, active, &identity.signer)
+        {
+            let parked = ParkedKey {
+                pubkey: pubkey.to_string(),
+                crypto_key: crypto_key.clone(),
+            };
+            if parked.save_to_store().await.is_err() {
+                web_sys::console::error_1(&"Failed to keep key for switching".into());
+            }
+        }
+        if identity.delete_from_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to sign out key".into());
+        }
+    }
+    if let Some(encrypted) = lock_ctx.encrypted().cloned() {
+        if encrypted.delete_from_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to sign out protected key".into());
+        }
+    }
+    if let Some(extension) = signer_ctx.extension().cloned() {
+        if extension.delete_from_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to sign out extension".into());
+        }
+    }
+    if let Some(remote) = signer_ctx.remote().cloned() {
+        if remote.delete_from_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to sign out remote signer".into());
+        }
+    }
+}
+
+/// The fields `LastSyncTime` stores, which it only builds for the current time itself.
+#[derive(serde::Serialize)]
+struct InboxSyncTime {
+    id: &'static str,
+    timestamp: i64,
+}
+
+/// Points the gift wrap inbox at `timestamp`, going through `LastSyncTime`'s serde form
+/// since it has no setter of its own.
+async fn set_inbox_synced_at(timestamp: i64) {
+    let saved = match serde_wasm_bindgen::to_value(&InboxSyncTime {
+        id: "last_sync_time",
+        timestamp,
+    })
+    .map_err(web_sys::wasm_bindgen::JsValue::from)
+    .and_then(nostr_minions::LastSyncTime::try_from)
+    {
+        Ok(sync_time) => sync_time.save_to_store().await,
+        Err(error) => Err(error),
+    };
+    if saved.is_err() {
+        web_sys::console::error_1(&"Failed to reset inbox sync time".into());
+    }
+}
+
+/// Keeps the relay list, tracked Lichess accounts and inbox sync time in use with the
+/// account that is leaving, and clears them for the next one.
+async fn stash_account_state(ctx: &AccountsStore, pubkey: Option<String>) {
+    let relays = nostr_minions::relay_pool::UserRelay::retrieve_all_from_store()
+        .await
+        .unwrap_or_default();
+    let lichess_accounts = crate::lichess_sync::TrackedLichessAccount::retrieve_all_from_store()
+        .await
+        .unwrap_or_default();
+    if let Some(account) = pubkey.and_then(|pubkey| ctx.find(&pubkey).cloned()) {
+        let account = StoredAccount {
+            relays: relays.iter().cloned().map(AccountRelay::from).collect(),
+            lichess_accounts: lichess_accounts.clone(),
+            inbox_synced_at: nostr_minions::LastSyncTime::find().await,
+            ..account
+        };
+        if account.save_to_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to save account relays".into());
+        }
+    }
+    for relay in relays {
+        if relay.delete_from_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to clear relay list".into());
+        }
+    }
+    for lichess_account in lichess_accounts {
+        if lichess_account.delete_from_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to clear Lichess accounts".into());
+        }
+    }
+    // A new identity reads its whole inbox.
+    set_inbox_synced_at(0).await;
+}
+
+fn reload() {
+    if let Some(window) = web_sys::window() {
+        if window.location().reload().is_err() {
+            web_sys::console::error_1(&"Failed to reload".into());
+        }
+    }
+}
+
+/// The contexts needed to move an identity in or out of the active slot.
+#[derive(Clone, PartialEq)]
+pub struct AccountSwitch {
+    pub accounts: AccountsStore,
+    pub key_ctx: nostr_minions::key_manager::NostrIdStore,
+    pub lock_ctx: crate::key_lock::KeyLockStore,
+    pub signer_ctx: crate::signer::SignerStore,
+    pub active: Option<String>,
+}
+
+#[hook]
+pub fn use_account_switch() -> AccountSwitch {
+    AccountSwitch {
+        accounts: use_accounts(),
+        key_ctx: nostr_minions::key_manager::use_nostr_id_ctx(),
+        lock_ctx: crate::key_lock::use_key_lock_ctx(),
+        signer_ctx: crate::signer::use_signer_ctx(),
+        active: crate::signer::use_signer().map(|signer| signer.public_key()),
+    }
+}
+
+impl AccountSwitch {
+    /// A locked key has never signed this session, so it may not be in the list yet.
+    async fn remember_locked_key(&self) {
+        let Some(encrypted) = self.lock_ctx.encrypted().cloned() else {
+            return;
+        };
+        if self.accounts.find(&encrypted.pubkey).is_some() {
+            return;
+        }
+        let account = StoredAccount {
+            pubkey: encrypted.pubkey,
+            label: String::new(),
+            secret: AccountSecret::Ncryptsec(encrypted.ncryptsec),
+            relays: vec![],
+            lichess_accounts: vec![],
+            inbox_synced_at: 0,
+            last_active_at: web_sys::js_sys::Date::now() as u64,
+        };
+        if account.save_to_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to save account".into());
+        }
+    }
+
+    /// Moves the active identity and its state out of the shared stores.
+    async fn leave(&self) {
+        self.remember_locked_key().await;
+        clear_active_identity(
+            &self.key_ctx,
+            &self.lock_ctx,
+            &self.signer_ctx,
+            self.active.as_deref(),
+        )
+        .await;
+        stash_account_state(&self.accounts, self.active.clone()).await;
+    }
+
+    /// Signs out so another account can be added; the current one stays in the list.
+    pub fn sign_out(&self) {
+        let switch = self.clone();
+        yew::platform::spawn_local(async move {
+            switch.leave().await;
+            reload();
+        });
+    }
+
+    /// Makes `account` the signing identity and reloads the app with its games and relays.
+    pub fn switch_to(&self, account: StoredAccount) {
+        let switch = self.clone();
+        yew::platform::spawn_local(async move {
+            switch.leave().await;
+            if let Err(error) = activate(&account).await {
+                crate::toast_failure(&error);
+                return;
+            }
+            reload();
+        });
+    }
+}
+
+async fn activate(account: &StoredAccount) -> Result<(), String> {
+    match &account.secret {
+        AccountSecret::Local => {
+            let parked = ParkedKey::retrieve_from_store::<ParkedKey>(&account.key())
+                .await
+                .map_err(|_| "This key is no longer on this device")?;
+            parked
+                .identity()?
+                .save_to_store()
+                .await
+                .map_err(|_| "Failed to save the key")?;
+            if parked.delete_from_store().await.is_err() {
+                web_sys::console::error_1(&"Failed to clear parked key".into());
+            }
+        }
+        AccountSecret::Ncryptsec(ncryptsec) => {
+            crate::key_lock::EncryptedKey {
+                pubkey: account.pubkey.clone(),
+                ncryptsec: ncryptsec.clone(),
+            }
+            .save_to_store()
+            .await
+            .map_err(|_| "Failed to save the encrypted key")?;
+        }
+        AccountSecret::Extension => {
+            crate::signer::ExtensionSigner {
+                pubkey: account.pubkey.clone(),
+            }
+            .save_to_store()
+            .await
+            .map_err(|_| "Failed to save extension login")?;
+        }
+        AccountSecret::Remote(session) => {
+            session
+                .clone()
+                .save_to_store()
+                .await
+                .map_err(|_| "Failed to save remote signer session")?;
+        }
+    }
+    for relay in account.relays.iter().cloned() {
+        if nostr_minions::relay_pool::UserRelay::from(relay)
+            .save_to_store()
+            .await
+            .is_err()
+        {
+            web_sys::console::error_1(&"Failed to restore account relays".into());
+        }
+    }
+    for lichess_account in account.lichess_accounts.iter().cloned() {
+        if lichess_account.save_to_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to restore Lichess accounts".into());
+        }
+    }
+    set_inbox_synced_at(account.inbox_synced_at).await;
+    let account = StoredAccount {
+        last_active_at: web_sys::js_sys::Date::now() as u64,
+        ..account.clone()
+    };
+    account
+        .save_to_store()
+        .await
+        .map_err(|_| "Failed to save account")?;
+    Ok(())
+}
+
+#[function_component(AccountsProvider)]
+pub fn accounts_provider(props: &yew::html::ChildrenProps) -> HtmlResult {
+    let stored = yew::suspense::use_future_with((), |_| async move {
+        StoredAccount::retrieve_all_from_store()
+            .await
+            .unwrap_or_default()
+    })?;
+    let ctx = use_reducer(|| Accounts {
+        accounts: (*stored).clone(),
+    });
+    let signer = crate::signer::use_signer();
+    let lock_ctx = crate::key_lock::use_key_lock_ctx();
+
+    // Whatever identity is signing gets remembered, so it can be switched back to later.
+    {
+        let dispatcher = ctx.dispatcher();
+        let known = ctx.clone();
+        let encrypted = lock_ctx.encrypted().cloned();
+        use_effect_with((signer, encrypted), move |(signer, encrypted)| {
+            let secret = match signer {
+                Some(crate::signer::BunkerSigner::Local(keypair)) => Some(
+                    encrypted
+                        .as_ref()
+                        .filter(|encrypted| encrypted.pubkey == keypair.public_key())
+                        .map_or(AccountSecret::Local, |encrypted| {
+                            AccountSecret::Ncryptsec(encrypted.ncryptsec.clone())
+                        }),
+                ),
+                Some(crate::signer::BunkerSigner::Extension(_)) => Some(AccountSecret::Extension),
+                Some(crate::signer::BunkerSigner::Remote(remote)) => {
+                    Some(AccountSecret::Remote(remote.session.clone()))
+                }
+                None => None,
+            };
+            if let (Some(signer), Some(secret)) = (signer, secret) {
+                let pubkey = signer.public_key();
+                let previous = known.find(&pubkey).cloned();
+                if previous.as_ref().map(|account| &account.secret) != Some(&secret) {
+                    let account = previous.map_or_else(
+                        || StoredAccount {
+                            pubkey: pubkey.clone(),
+                            label: String::new(),
+                            secret: secret.clone(),
+                            relays: vec![],
+                            lichess_accounts: vec![],
+                            inbox_synced_at: 0,
+                            last_active_at: web_sys::js_sys::Date::now() as u64,
+                        },
+                        |account| StoredAccount {
+                            secret: secret.clone(),
+                            ..account
+                        },
+                    );
+                    save_account(&dispatcher, account);
+                }
+            }
+            || {}
+        });
+    }
+
+    Ok(html! {
+        <ContextProvider<AccountsStore> context={ctx}>
+            {props.children.clone()}
+        </ContextProvider<AccountsStore>>
+    })
+}
+
+#[hook]
+pub fn use_accounts() -> AccountsStore {
+    use_context::<AccountsStore>().expect("AccountsStore context not set")
+}
@@ -0,0 +1,484 @@
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use nostr_minions::browser_api::IdbStoreManager;
+use yew::prelude::*;
+
+const BACKUP_FORMAT_VERSION: u32 = 1;
+/// Tombstones carry a newer version so clients that predate them skip them instead of
+/// restoring the deleted game.
+const TOMBSTONE_FORMAT_VERSION: u32 = 2;
+const BACKUP_BATCH_SIZE: usize = 25;
+const BACKUP_TICK_MS: u32 = 10_000;
+
+/// A local game entry, gift-wrapped to the user's own key so other devices can restore it.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GameBackup {
+    pub bunker_backup: u32,
+    pub entry: rooky_core::idb::RookyGameEntry,
+    #[serde(default)]
+    pub labels: Option<crate::labels::GameLabels>,
+    /// The collections `labels` refers to, so other devices can list them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collections: Vec<crate::labels::GameCollection>,
+    /// Set when the game was deleted, so other devices drop their copy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<u64>,
+}
+
+impl GameBackup {
+    #[must_use]
+    pub fn new(
+        entry: rooky_core::idb::RookyGameEntry,
+        labels: crate::labels::GameLabels,
+        collections: Vec<crate::labels::GameCollection>,
+    ) -> Self {
+        let collections = collections
+            .into_iter()
+            .filter(|collection| labels.collections.contains(&collection.name))
+            .collect();
+        Self {
+            bunker_backup: BACKUP_FORMAT_VERSION,
+            entry,
+            labels: (!labels.is_empty()).then_some(labels),
+            collections,
+            deleted_at: None,
+        }
+    }
+
+    /// Records that `entry` was deleted; the game itself is left out.
+    #[must_use]
+    pub fn tombstone(mut entry: rooky_core::idb::RookyGameEntry) -> Self {
+        entry.note.content.clear();
+        Self {
+            bunker_backup: TOMBSTONE_FORMAT_VERSION,
+            entry,
+            labels: None,
+            collections: vec![],
+            deleted_at: Some(web_sys::js_sys::Date::now() as u64),
+        }
+    }
+
+    /// Parses a backup out of a rumor, only if it was written by `own_pubkey`.
+    #[must_use]
+    pub fn from_rumor(rumor: &nostr_minions::nostro2::NostrNote, own_pubkey: &str) -> Option<Self> {
+        if rumor.pubkey != own_pubkey {
+            return None;
+        }
+        serde_json::from_str::<Self>(&rumor.content)
+            .ok()
+            .filter(|backup| backup.bunker_backup <= TOMBSTONE_FORMAT_VERSION)
+    }
+
+    /// # Errors
+    /// Returns a `serde_json::Error` if the entry cannot be serialized.
+    pub fn to_json_string(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BackedUpGame {
+    pub id: String,
+    pub backed_up_at: u64,
+}
+
+impl IdbStoreManager for BackedUpGame {
+    fn config() -> nostr_minions::browser_api::IdbStoreConfig {
+        crate::idb::store_config("game_backups", "id")
+    }
+    fn key(&self) -> web_sys::wasm_bindgen::JsValue {
+        web_sys::wasm_bindgen::JsValue::from_str(&self.id)
+    }
+}
+
+impl From<BackedUpGame> for web_sys::wasm_bindgen::JsValue {
+    fn from(value: BackedUpGame) -> Self {
+        serde_wasm_bindgen::to_value(&value).unwrap_or_default()
+    }
+}
+
+impl TryFrom<web_sys::wasm_bindgen::JsValue> for BackedUpGame {
+    type Error = web_sys::wasm_bindgen::JsValue;
+    fn try_from(value: web_sys::wasm_bindgen::JsValue) -> Result<Self, Self::Error> {
+        Ok(serde_wasm_bindgen::from_value(value)?)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct GameBackups {
+    backed_up: HashSet<String>,
+}
+
+impl GameBackups {
+    #[must_use]
+    pub fn is_backed_up(&self, id: &str) -> bool {
+        self.backed_up.contains(id)
+    }
+    #[must_use]
+    pub fn backed_up_count(&self) -> usize {
+        self.backed_up.len()
+    }
+}
+
+pub enum GameBackupsAction {
+    BackedUp(Vec<String>),
+    Outdated(Vec<String>),
+}
+
+impl Reducible for GameBackups {
+    type Action = GameBackupsAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        match action {
+            GameBackupsAction::BackedUp(ids) => {
+                let mut backed_up = self.backed_up.clone();
+                backed_up.extend(ids);
+                Rc::new(Self { backed_up })
+            }
+            GameBackupsAction::Outdated(ids) => {
+                let mut backed_up = self.backed_up.clone();
+                for id in &ids {
+                    backed_up.remove(id);
+                }
+                Rc::new(Self { backed_up })
+            }
+        }
+    }
+}
+
+pub type GameBackupsStore = UseReducerHandle<GameBackups>;
+
+/// Games that belong in the user's backup: everything except games received from others,
+/// unless the user labelled them.
+#[must_use]
+pub fn needs_backup(
+    entry: &rooky_core::idb::RookyGameEntry,
+    backups: &GameBackups,
+    labels: &crate::labels::GameLabelsState,
+) -> bool {
+    (entry.origin != rooky_core::idb::GameOrigin::Received || !labels.labels(&entry.id).is_empty())
+        && !backups.is_backed_up(&entry.id)
+}
+
+/// Queues games for another backup, e.g. after their labels changed.
+pub fn mark_outdated(backups: &GameBackupsStore, ids: Vec<String>) {
+    backups.dispatch(GameBackupsAction::Outdated(ids.clone()));
+    yew::platform::spawn_local(async move {
+        for id in ids {
+            let record = BackedUpGame {
+                id,
+                backed_up_at: 0,
+            };
+            if record.delete_from_store().await.is_err() {
+                web_sys::console::error_1(&"Failed to update game backup".into());
+            }
+        }
+    });
+}
+
+/// Gift-wraps each entry to the user's own key and queues it in the outbox.
+///
+/// The entries count as backed up from the start, so a tick arriving while they are still
+/// being wrapped does not send them again; the ones that fail are queued for the next tick.
+pub fn send_backups(
+    keypair: &crate::signer::BunkerSigner,
+    outbox: &crate::outbox::OutboxStore,
+    relay_ctx: &nostr_minions::relay_pool::NostrRelayPoolStore,
+    backups: &GameBackupsStore,
+    labels: &crate::labels::GameLabelsState,
+    entries: Vec<rooky_core::idb::RookyGameEntry>,
+) {
+    if entries.is_empty() {
+        return;
+    }
+    let own_pubkey = keypair.public_key();
+    let backups = backups.dispatcher();
+    backups.dispatch(GameBackupsAction::BackedUp(
+        entries.iter().map(|entry| entry.id.clone()).collect(),
+    ));
+    let outbox = outbox.clone();
+    let relay_ctx = relay_ctx.clone();
+    let keypair = keypair.clone();
+    let pending = entries
+        .into_iter()
+        .map(|entry| {
+            let game_labels = labels.labels(&entry.id);
+            GameBackup::new(entry, game_labels, labels.collections())
+        })
+        .collect::<Vec<_>>();
+    yew::platform::spawn_local(async move {
+        let mut sent = vec![];
+        let mut failed = vec![];
+        for backup in pending {
+            let wrapped = match backup.to_json_string() {
+                Ok(content) => keypair.private_dm(&content, &own_pubkey).await.ok(),
+                Err(_) => None,
+            };
+            let Some(wrapped) = wrapped else {
+                web_sys::console::error_1(&"Failed to wrap game backup".into());
+                failed.push(backup.entry.id);
+                continue;
+            };
+            crate::outbox::publish_note(&outbox, &relay_ctx, wrapped);
+            sent.push(backup.entry.id);
+        }
+        if !failed.is_empty() {
+            backups.dispatch(GameBackupsAction::Outdated(failed));
+        }
+        let backed_up_at = web_sys::js_sys::Date::now() as u64;
+        for id in sent {
+            if (BackedUpGame { id, backed_up_at })
+                .save_to_store()
+                .await
+                .is_err()
+            {
+                web_sys::console::error_1(&"Failed to record game backup".into());
+            }
+        }
+    });
+}
+
+/// Gift-wraps a tombstone for each deleted entry and queues it in the outbox, so the
+/// user's other devices drop their copies.
+pub async fn send_tombstones(
+    keypair: &crate::signer::BunkerSigner,
+    outbox: &crate::outbox::OutboxStore,
+    relay_ctx: &nostr_minions::relay_pool::NostrRelayPoolStore,
+    entries: &[rooky_core::idb::RookyGameEntry],
+) {
+    let own_pubkey = keypair.public_key();
+    for entry in entries {
+        let Ok(content) = GameBackup::tombstone(entry.clone()).to_json_string() else {
+            continue;
+        };
+        let Ok(wrapped) = keypair.private_dm(&content, &own_pubkey).await else {
+            web_sys::console::error_1(&"Failed to wrap game tombstone".into());
+            continue;
+        };
+        crate::outbox::publish_note(outbox, relay_ctx, wrapped);
+    }
+}
+
+/// Removes a game deleted on another device, unless it was edited here after the
+/// deleted revision.
+async fn apply_tombstone(
+    ctx: &GameBackupsStore,
+    game_ctx: &crate::live_game::AnnotatedGameHistoryStore,
+    entry: rooky_core::idb::RookyGameEntry,
+) {
+    let stored = game_ctx
+        .rooky_game_entries()
+        .into_iter()
+        .find(|known| known.id == entry.id);
+    if stored
+        .as_ref()
+        .is_some_and(|stored| stored.note.created_at > entry.note.created_at)
+    {
+        return;
+    }
+    // Keeping the backup record makes later copies of the deleted game count as known.
+    ctx.dispatch(GameBackupsAction::BackedUp(vec![entry.id.clone()]));
+    let backup_record = BackedUpGame {
+        id: entry.id.clone(),
+        backed_up_at: web_sys::js_sys::Date::now() as u64,
+    };
+    if backup_record.save_to_store().await.is_err() {
+        web_sys::console::error_1(&"Failed to record game backup".into());
+    }
+    if let Some(stored) = stored {
+        game_ctx.dispatch(crate::live_game::AnnotatedGameHistoryAction::RemoveGames(
+            vec![stored.id.clone()],
+        ));
+        crate::live_game::remove_local_games(vec![stored]).await;
+    }
+}
+
+/// Restores a game from a backup addressed to the user, unless this device already holds
+/// the same or a newer revision of it.
+async fn restore_backup(
+    ctx: &GameBackupsStore,
+    game_ctx: &crate::live_game::AnnotatedGameHistoryStore,
+    labels_ctx: &crate::labels::GameLabelsStore,
+    backup: GameBackup,
+) {
+    let GameBackup {
+        entry,
+        labels,
+        collections,
+        deleted_at,
+        ..
+    } = backup;
+    if deleted_at.is_some() {
+        apply_tombstone(ctx, game_ctx, entry).await;
+        return;
+    }
+    let stored = game_ctx
+        .rooky_game_entries()
+        .into_iter()
+        .find(|known| known.id == entry.id);
+    let is_stored = stored.is_some();
+    // Edits re-sign the note, so its timestamp tells which device holds the newer revision.
+    let is_newer = stored
+        .as_ref()
+        .is_some_and(|stored| entry.note.created_at > stored.note.created_at);
+    // Games backed up from here but no longer stored were deleted on purpose.
+    let is_known = ctx.is_backed_up(&entry.id) || is_stored;
+    // Labels are merged rather than replaced, so tags added on either device survive.
+    if let Some(labels) = labels.filter(|_| is_stored || !is_known) {
+        let known_collections = labels_ctx.collections();
+        for collection in collections {
+            if known_collections
+                .iter()
+                .any(|known| known.name == collection.name)
+            {
+                continue;
+            }
+            labels_ctx.dispatch(crate::labels::GameLabelsAction::AddCollection(
+                collection.clone(),
+            ));
+            if collection.save_to_store().await.is_err() {
+                web_sys::console::error_1(&"Failed to restore collection".into());
+            }
+        }
+        let local = labels_ctx.labels(&entry.id);
+        let merged = local.merged(&labels);
+        if merged != local {
+            labels_ctx.dispatch(crate::labels::GameLabelsAction::SetLabels(merged.clone()));
+            if merged.save_to_store().await.is_err() {
+                web_sys::console::error_1(&"Failed to restore game labels".into());
+            }
+        }
+    }
+    ctx.dispatch(GameBackupsAction::BackedUp(vec![entry.id.clone()]));
+    let backup_record = BackedUpGame {
+        id: entry.id.clone(),
+        backed_up_at: web_sys::js_sys::Date::now() as u64,
+    };
+    if backup_record.save_to_store().await.is_err() {
+        web_sys::console::error_1(&"Failed to record game backup".into());
+    }
+    if is_known && !is_newer {
+        return;
+    }
+    if entry.clone().save_to_store().await.is_err() {
+        web_sys::console::error_1(&"Failed to restore game".into());
+        return;
+    }
+    game_ctx.dispatch(crate::live_game::AnnotatedGameHistoryAction::AddGame(entry));
+}
+
+/// The contexts a gift wrap addressed to the user can update.
+#[derive(Clone)]
+pub struct Inbox {
+    pub backups: GameBackupsStore,
+    pub games: crate::live_game::AnnotatedGameHistoryStore,
+    pub labels: crate::labels::GameLabelsStore,
+}
+
+impl Inbox {
+    /// Routes a rumor unwrapped from a gift wrap addressed to `own_pubkey`: the user's own
+    /// backups are restored and anything else is tried as a game someone sent.
+    pub async fn receive_rumor(&self, own_pubkey: &str, rumor: nostr_minions::nostro2::NostrNote) {
+        match GameBackup::from_rumor(&rumor, own_pubkey) {
+            Some(backup) => restore_backup(&self.backups, &self.games, &self.labels, backup).await,
+            None => {
+                crate::live_game::receive_game(
+                    &self.games.rooky_game_entries(),
+                    &self.games.dispatcher(),
+                    rumor,
+                )
+                .await;
+            }
+        }
+    }
+}
+
+#[function_component(GameBackupProvider)]
+pub fn game_backup_provider(props: &yew::html::ChildrenProps) -> HtmlResult {
+    let relay_ctx = nostr_minions::relay_pool::use_nostr_relay_pool();
+    let outbox = crate::outbox::use_outbox();
+    let game_ctx = crate::live_game::use_game_history();
+    let labels_ctx = crate::labels::use_game_labels();
+    let keypair = crate::signer::use_signer();
+    let backed_up = yew::suspense::use_future_with((), |_| async move {
+        BackedUpGame::retrieve_all_from_store()
+            .await
+            .unwrap_or_default()
+    })?;
+    let ctx = use_reducer(|| GameBackups {
+        backed_up: backed_up.iter().map(|backup| backup.id.clone()).collect(),
+    });
+    let tick = use_state(|| 0u32);
+
+    {
+        let tick = tick.clone();
+        use_effect_with((), move |()| {
+            let interval = gloo::timers::callback::Interval::new(BACKUP_TICK_MS, move || {
+                tick.set((*tick).wrapping_add(1));
+            });
+            move || drop(interval)
+        });
+    }
+    {
+        let ctx = ctx.clone();
+        let relay_ctx = relay_ctx.clone();
+        let keypair = keypair.clone();
+        let entries = game_ctx.rooky_game_entries();
+        let labels_ctx = labels_ctx.clone();
+        use_effect_with(
+            (game_ctx.synced, entries.len(), *tick),
+            move |(synced, _, _)| {
+                if let (true, Some(keypair)) = (*synced, keypair.as_ref()) {
+                    let pending = entries
+                        .into_iter()
+                        .filter(|entry| needs_backup(entry, &ctx, &labels_ctx))
+                        .take(BACKUP_BATCH_SIZE)
+                        .collect::<Vec<_>>();
+                    send_backups(keypair, &outbox, &relay_ctx, &ctx, &labels_ctx, pending);
+                }
+                || {}
+            },
+        );
+    }
+
+    {
+        let inbox = Inbox {
+            backups: ctx.clone(),
+            games: game_ctx.clone(),
+            labels: labels_ctx.clone(),
+        };
+        let cursor = use_mut_ref(|| 0usize);
+        use_effect_with(relay_ctx.unique_notes.clone(), move |notes| {
+            let wraps = crate::unseen(&cursor, notes)
+                .iter()
+                .filter(|note| note.kind == 1059)
+                .cloned()
+                .collect::<Vec<_>>();
+            if let (Some(keypair), false) = (keypair.clone(), wraps.is_empty()) {
+                // Each wrap is unwrapped here only, since extension and remote signers
+                // decrypt through a round trip, possibly with a prompt.
+                yew::platform::spawn_local(async move {
+                    let own_pubkey = keypair.public_key();
+                    for note in wraps {
+                        if let Ok(rumor) = keypair.extract_rumor(&note).await {
+                            inbox.receive_rumor(&own_pubkey, rumor).await;
+                        }
+                    }
+                });
+            }
+            || {}
+        });
+    }
+
+    Ok(html! {
+        <ContextProvider<GameBackupsStore> context={ctx}>
+            {props.children.clone()}
+        </ContextProvider<GameBackupsStore>>
+    })
+}
+
+#[hook]
+pub fn use_game_backups() -> GameBackupsStore {
+    use_context::<GameBackupsStore>().expect("GameBackupsStore context not set")
+}
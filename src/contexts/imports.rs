@@ -0,0 +1,388 @@
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use nostr_minions::browser_api::IdbStoreManager;
+use yew::prelude::*;
+
+const HISTORY_LIMIT: usize = 50;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ImportSource {
+    Lichess,
+    LichessSync,
+    ChessCom,
+    Pgn,
+    LichessStudy,
+    LichessBroadcast,
+}
+
+impl ImportSource {
+    /// Translation key for the source's label.
+    #[must_use]
+    pub const fn label_key(&self) -> &'static str {
+        match self {
+            Self::Lichess => "import_source_lichess",
+            Self::LichessSync => "import_source_lichess_sync",
+            Self::ChessCom => "import_source_chesscom",
+            Self::Pgn => "import_source_pgn",
+            Self::LichessStudy => "import_source_lichess_study",
+            Self::LichessBroadcast => "import_source_lichess_broadcast",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ImportStatus {
+    Running,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+/// One run of an external fetcher, with live counts of what it brought in.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ImportJob {
+    pub id: String,
+    pub source: ImportSource,
+    /// What was imported, such as a username or a month range.
+    pub query: String,
+    pub started_at: u64,
+    pub finished_at: Option<u64>,
+    pub status: ImportStatus,
+    /// Requests the job is split into, such as Chess.com months.
+    pub batches_total: usize,
+    pub batches_done: usize,
+    pub fetched: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    /// Ids of the entries saved by this job.
+    pub saved: Vec<String>,
+}
+
+impl IdbStoreManager for ImportJob {
+    fn config() -> nostr_minions::browser_api::IdbStoreConfig {
+        crate::idb::store_config("import_jobs", "id")
+    }
+    fn key(&self) -> web_sys::wasm_bindgen::JsValue {
+        web_sys::wasm_bindgen::JsValue::from_str(&self.id)
+    }
+}
+
+impl From<ImportJob> for web_sys::wasm_bindgen::JsValue {
+    fn from(value: ImportJob) -> Self {
+        serde_wasm_bindgen::to_value(&value).unwrap_or_default()
+    }
+}
+
+impl TryFrom<web_sys::wasm_bindgen::JsValue> for ImportJob {
+    type Error = web_sys::wasm_bindgen::JsValue;
+    fn try_from(value: web_sys::wasm_bindgen::JsValue) -> Result<Self, Self::Error> {
+        Ok(serde_wasm_bindgen::from_value(value)?)
+    }
+}
+
+/// Shared flag a running job polls between games.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Rc<Cell<bool>>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.set(true);
+    }
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.get()
+    }
+}
+
+impl PartialEq for CancelToken {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ImportJobs {
+    jobs: Vec<ImportJob>,
+    tokens: HashMap<String, CancelToken>,
+    /// Jobs asked to stop that have not finished yet, so the page can show it.
+    cancelling: HashSet<String>,
+}
+
+impl ImportJobs {
+    /// Running jobs first, then finished ones from newest to oldest.
+    #[must_use]
+    pub fn jobs(&self) -> Vec<ImportJob> {
+        let mut jobs = self.jobs.clone();
+        jobs.sort_by_key(|job| {
+            (
+                job.status != ImportStatus::Running,
+                std::cmp::Reverse(job.started_at),
+            )
+        });
+        jobs
+    }
+    #[must_use]
+    pub fn is_running(&self, source: ImportSource) -> bool {
+        self.jobs
+            .iter()
+            .any(|job| job.source == source && job.status == ImportStatus::Running)
+    }
+    #[must_use]
+    pub fn is_cancelling(&self, id: &str) -> bool {
+        self.cancelling.contains(id)
+    }
+}
+
+pub enum ImportJobsAction {
+    Started(ImportJob, CancelToken),
+    Updated(ImportJob),
+    /// Asks a running job to stop at its next game.
+    Cancel(String),
+    Finished(ImportJob),
+    ClearHistory,
+}
+
+impl Reducible for ImportJobs {
+    type Action = ImportJobsAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut jobs = self.jobs.clone();
+        let mut tokens = self.tokens.clone();
+        let mut cancelling = self.cancelling.clone();
+        match action {
+            ImportJobsAction::Started(job, token) => {
+                tokens.insert(job.id.clone(), token);
+                jobs.push(job);
+            }
+            ImportJobsAction::Updated(job) => {
+                if let Some(known) = jobs.iter_mut().find(|known| known.id == job.id) {
+                    *known = job;
+                }
+            }
+            ImportJobsAction::Cancel(id) => {
+                if let Some(token) = tokens.get(&id) {
+                    token.cancel();
+                    cancelling.insert(id);
+                }
+            }
+            ImportJobsAction::Finished(job) => {
+                tokens.remove(&job.id);
+                cancelling.remove(&job.id);
+                jobs.retain(|known| known.id != job.id);
+                jobs.push(job);
+                jobs = keep_recent(jobs);
+            }
+            ImportJobsAction::ClearHistory => {
+                jobs.retain(|job| job.status == ImportStatus::Running);
+            }
+        }
+        Rc::new(Self {
+            jobs,
+            tokens,
+            cancelling,
+        })
+    }
+}
+
+pub type ImportJobsStore = UseReducerHandle<ImportJobs>;
+
+/// Keeps running jobs and the `HISTORY_LIMIT` newest finished ones.
+fn keep_recent(mut jobs: Vec<ImportJob>) -> Vec<ImportJob> {
+    jobs.sort_by_key(|job| std::cmp::Reverse(job.started_at));
+    let mut finished = 0;
+    jobs.retain(|job| {
+        if job.status == ImportStatus::Running {
+            return true;
+        }
+        finished += 1;
+        finished <= HISTORY_LIMIT
+    });
+    jobs
+}
+
+/// Deletes stored jobs that fell out of the history.
+async fn prune_history() {
+    let stored = ImportJob::retrieve_all_from_store()
+        .await
+        .unwrap_or_default();
+    let kept = keep_recent(stored.clone())
+        .into_iter()
+        .map(|job| job.id)
+        .collect::<HashSet<_>>();
+    for job in stored.into_iter().filter(|job| !kept.contains(&job.id)) {
+        if job.delete_from_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to prune import job".into());
+        }
+    }
+}
+
+/// The fetcher's side of a job: counts progress, imports games and reports back to the context.
+pub struct ImportJobHandle {
+    job: ImportJob,
+    token: CancelToken,
+    jobs: UseReducerDispatcher<ImportJobs>,
+}
+
+impl ImportJobHandle {
+    #[must_use]
+    pub fn start(
+        jobs: UseReducerDispatcher<ImportJobs>,
+        source: ImportSource,
+        query: &str,
+    ) -> Self {
+        let started_at = web_sys::js_sys::Date::now() as u64;
+        let job = ImportJob {
+            id: format!("{source:?}-{started_at}"),
+            source,
+            query: query.to_string(),
+            started_at,
+            finished_at: None,
+            status: ImportStatus::Running,
+            batches_total: 0,
+            batches_done: 0,
+            fetched: 0,
+            skipped: 0,
+            failed: 0,
+            saved: vec![],
+        };
+        let token = CancelToken::default();
+        jobs.dispatch(ImportJobsAction::Started(job.clone(), token.clone()));
+        Self { job, token, jobs }
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    #[must_use]
+    pub const fn job(&self) -> &ImportJob {
+        &self.job
+    }
+
+    fn update(&self) {
+        self.jobs
+            .dispatch(ImportJobsAction::Updated(self.job.clone()));
+    }
+
+    pub fn set_batches(&mut self, total: usize) {
+        self.job.batches_total = total;
+        self.update();
+    }
+
+    pub fn batch_done(&mut self) {
+        self.job.batches_done += 1;
+        self.update();
+    }
+
+    pub fn failed(&mut self) {
+        self.job.failed += 1;
+        self.update();
+    }
+
+    /// Signs and saves a fetched game unless `known` already holds it, then shows it in the game list.
+    pub async fn import_note(
+        &mut self,
+        keypair: &crate::signer::BunkerSigner,
+        known: &mut HashSet<String>,
+        game_ctx: &UseReducerDispatcher<crate::live_game::AnnotatedGameHistory>,
+        mut note: nostr_minions::nostro2::NostrNote,
+    ) -> Option<rooky_core::idb::RookyGameEntry> {
+        self.job.fetched += 1;
+        let Some(fingerprint) = crate::note_fingerprint(&note) else {
+            self.failed();
+            return None;
+        };
+        if !known.insert(fingerprint) {
+            self.job.skipped += 1;
+            self.update();
+            return None;
+        }
+        if keypair.sign_note(&mut note).await.is_err() {
+            self.failed();
+            return None;
+        }
+        let entry = rooky_core::idb::RookyGameEntry {
+            id: note.id.clone().unwrap_or_default(),
+            note,
+            origin: rooky_core::idb::GameOrigin::Public,
+        };
+        if entry.clone().save_to_store().await.is_err() {
+            self.failed();
+            return None;
+        }
+        game_ctx.dispatch(crate::live_game::AnnotatedGameHistoryAction::AddGame(
+            entry.clone(),
+        ));
+        self.job.saved.push(entry.id.clone());
+        self.update();
+        Some(entry)
+    }
+
+    /// Records the job in the history; a cancelled token overrides `status`.
+    pub fn finish(mut self, status: ImportStatus) -> ImportJob {
+        self.job.status = if self.is_cancelled() {
+            ImportStatus::Cancelled
+        } else {
+            status
+        };
+        self.job.finished_at = Some(web_sys::js_sys::Date::now() as u64);
+        self.jobs
+            .dispatch(ImportJobsAction::Finished(self.job.clone()));
+        let job = self.job.clone();
+        yew::platform::spawn_local(async move {
+            if job.save_to_store().await.is_err() {
+                web_sys::console::error_1(&"Failed to save import job".into());
+            }
+            prune_history().await;
+        });
+        self.job
+    }
+}
+
+#[function_component(ImportJobsProvider)]
+pub fn import_jobs_provider(props: &yew::html::ChildrenProps) -> HtmlResult {
+    let history = yew::suspense::use_future_with((), |_| async move {
+        prune_history().await;
+        keep_recent(
+            ImportJob::retrieve_all_from_store()
+                .await
+                .unwrap_or_default(),
+        )
+    })?;
+    let ctx = use_reducer(|| ImportJobs {
+        jobs: (*history).clone(),
+        tokens: HashMap::new(),
+        cancelling: HashSet::new(),
+    });
+
+    Ok(html! {
+        <ContextProvider<ImportJobsStore> context={ctx}>
+            {props.children.clone()}
+        </ContextProvider<ImportJobsStore>>
+    })
+}
+
+/// Removes finished jobs from the history, keeping the games they imported.
+pub fn clear_import_history(ctx: &ImportJobsStore) {
+    let finished = ctx
+        .jobs()
+        .into_iter()
+        .filter(|job| job.status != ImportStatus::Running)
+        .collect::<Vec<_>>();
+    ctx.dispatch(ImportJobsAction::ClearHistory);
+    yew::platform::spawn_local(async move {
+        for job in finished {
+            if job.delete_from_store().await.is_err() {
+                web_sys::console::error_1(&"Failed to clear import job".into());
+            }
+        }
+    });
+}
+
+#[hook]
+pub fn use_import_jobs() -> ImportJobsStore {
+    use_context::<ImportJobsStore>().expect("ImportJobsStore context not set")
+}
@@ -0,0 +1,331 @@
+use std::rc::Rc;
+
+use nostr_minions::browser_api::IdbStoreManager;
+use nostr_minions::key_manager::{NostrIdAction, NostrIdStore, UserIdentity};
+use web_sys::js_sys;
+use yew::prelude::*;
+
+/// Minutes without input before an unlocked passphrase-protected key is locked again.
+pub const AUTO_LOCK_MINUTES: u32 = 15;
+const ACTIVITY_CHECK_MS: u32 = 30_000;
+const ACTIVITY_EVENTS: [&str; 4] = ["pointerdown", "keydown", "wheel", "touchstart"];
+
+/// A secret key kept on disk only as a NIP-49 `ncryptsec`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct EncryptedKey {
+    pub pubkey: String,
+    pub ncryptsec: String,
+}
+
+impl IdbStoreManager for EncryptedKey {
+    fn config() -> nostr_minions::browser_api::IdbStoreConfig {
+        crate::idb::store_config("encrypted_keys", "pubkey")
+    }
+    fn key(&self) -> web_sys::wasm_bindgen::JsValue {
+        web_sys::wasm_bindgen::JsValue::from_str(&self.pubkey)
+    }
+}
+
+impl From<EncryptedKey> for web_sys::wasm_bindgen::JsValue {
+    fn from(value: EncryptedKey) -> Self {
+        serde_wasm_bindgen::to_value(&value).unwrap_or_default()
+    }
+}
+
+impl TryFrom<web_sys::wasm_bindgen::JsValue> for EncryptedKey {
+    type Error = web_sys::wasm_bindgen::JsValue;
+    fn try_from(value: web_sys::wasm_bindgen::JsValue) -> Result<Self, Self::Error> {
+        Ok(serde_wasm_bindgen::from_value(value)?)
+    }
+}
+
+#[derive(Clone, PartialEq, Default)]
+pub struct KeyLockState {
+    encrypted: Option<EncryptedKey>,
+}
+
+impl KeyLockState {
+    #[must_use]
+    pub const fn encrypted(&self) -> Option<&EncryptedKey> {
+        self.encrypted.as_ref()
+    }
+    /// Whether the stored key needs a passphrase to unlock.
+    #[must_use]
+    pub const fn is_protected(&self) -> bool {
+        self.encrypted.is_some()
+    }
+}
+
+pub enum KeyLockAction {
+    Protected(EncryptedKey),
+    Forgotten,
+}
+
+impl Reducible for KeyLockState {
+    type Action = KeyLockAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        Rc::new(match action {
+            KeyLockAction::Protected(encrypted) => Self {
+                encrypted: Some(encrypted),
+            },
+            KeyLockAction::Forgotten => Self { encrypted: None },
+        })
+    }
+}
+
+pub type KeyLockStore = UseReducerHandle<KeyLockState>;
+
+/// An identity holding `keypair` for this session only. `UserIdentity::from_new_keys` always
+/// writes the raw key to IndexedDB, so protected keys are loaded through this instead.
+///
+/// # Errors
+/// Returns an error if the browser refuses to import the key.
+pub(crate) async fn session_identity(
+    keypair: &nostr_minions::nostro2_signer::keypair::NostrKeypair,
+) -> Result<UserIdentity, String> {
+    let secret = js_sys::Uint8Array::from(keypair.secret_key().as_slice());
+    let crypto_key = nostr_minions::browser_api::BrowserCrypto::default()
+        .import_key_array(secret.into())
+        .await
+        .map_err(|_| "Failed to load the key")?;
+    local_identity(&crypto_key)
+}
+
+/// The active identity slot holding `crypto_key`, in the layout `UserIdentity` reads from disk.
+///
+/// # Errors
+/// Returns an error if `crypto_key` is not a usable key.
+pub(crate) fn local_identity(crypto_key: &web_sys::CryptoKey) -> Result<UserIdentity, String> {
+    let object = js_sys::Object::new();
+    let _ = js_sys::Reflect::set(&object, &"pubkey".into(), &"privateKey".into());
+    let _ = js_sys::Reflect::set(&object, &"crypto_key".into(), crypto_key);
+    let _ = js_sys::Reflect::set(&object, &"default".into(), &true.into());
+    UserIdentity::try_from(web_sys::wasm_bindgen::JsValue::from(object))
+        .map_err(|_| "Invalid key".to_string())
+}
+
+/// Encrypts `keypair` when a passphrase is given and builds its identity. Only an
+/// unprotected key is written to IndexedDB raw.
+async fn prepare_identity(
+    keypair: nostr_minions::nostro2_signer::keypair::NostrKeypair,
+    passphrase: Option<String>,
+) -> Result<(UserIdentity, Option<EncryptedKey>), String> {
+    match passphrase.filter(|passphrase| !passphrase.is_empty()) {
+        Some(passphrase) => {
+            let encrypted = EncryptedKey {
+                pubkey: keypair.public_key(),
+                ncryptsec: crate::nip49::encrypt_keypair(&keypair, &passphrase)?,
+            };
+            Ok((session_identity(&keypair).await?, Some(encrypted)))
+        }
+        None => {
+            let identity = UserIdentity::from_new_keys(keypair)
+                .await
+                .map_err(|_| "Failed to save the key")?;
+            Ok((identity, None))
+        }
+    }
+}
+
+/// Signs in with `keypair`, storing it raw or, when a passphrase is given, as an `ncryptsec`
+/// so only the encrypted form ever reaches IndexedDB.
+///
+/// # Errors
+/// Returns an error if the key cannot be encrypted or saved.
+pub async fn store_identity(
+    key_ctx: &NostrIdStore,
+    lock_ctx: &KeyLockStore,
+    keypair: nostr_minions::nostro2_signer::keypair::NostrKeypair,
+    passphrase: Option<String>,
+) -> Result<(), String> {
+    let pubkey = keypair.public_key();
+    let (identity, encrypted) = prepare_identity(keypair, passphrase).await?;
+    if let Some(previous) = lock_ctx.encrypted.clone() {
+        if previous.delete_from_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to remove previous encrypted key".into());
+        }
+        lock_ctx.dispatch(KeyLockAction::Forgotten);
+    }
+    if let Some(encrypted) = encrypted {
+        encrypted
+            .clone()
+            .save_to_store()
+            .await
+            .map_err(|_| "Failed to save the encrypted key")?;
+        lock_ctx.dispatch(KeyLockAction::Protected(encrypted));
+    }
+    key_ctx.dispatch(NostrIdAction::LoadIdentity(pubkey, identity));
+    Ok(())
+}
+
+/// Replaces the raw key on disk with its `ncryptsec`, keeping the session signed in.
+///
+/// # Errors
+/// Returns an error if there is no extractable key or it cannot be encrypted.
+pub async fn protect_stored_key(
+    key_ctx: &NostrIdStore,
+    lock_ctx: &KeyLockStore,
+    passphrase: &str,
+) -> Result<(), String> {
+    let mut keypair = key_ctx.get_nostr_key().await.ok_or("No key to protect")?;
+    keypair.set_extractable(true);
+    let encrypted = EncryptedKey {
+        pubkey: keypair.public_key(),
+        ncryptsec: crate::nip49::encrypt_keypair(&keypair, passphrase)?,
+    };
+    encrypted
+        .clone()
+        .save_to_store()
+        .await
+        .map_err(|_| "Failed to save the encrypted key")?;
+    if let Some(identity) = key_ctx.get_identity().cloned() {
+        if identity.delete_from_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to remove unencrypted key".into());
+        }
+    }
+    lock_ctx.dispatch(KeyLockAction::Protected(encrypted));
+    Ok(())
+}
+
+/// Decrypts the stored key and signs in for this session only.
+///
+/// # Errors
+/// Returns an error if the passphrase is wrong.
+pub async fn unlock(
+    key_ctx: &NostrIdStore,
+    encrypted: &EncryptedKey,
+    passphrase: &str,
+) -> Result<(), String> {
+    let keypair = crate::nip49::decrypt_keypair(&encrypted.ncryptsec, passphrase)?;
+    let pubkey = keypair.public_key();
+    let identity = session_identity(&keypair).await?;
+    key_ctx.dispatch(NostrIdAction::LoadIdentity(pubkey, identity));
+    Ok(())
+}
+
+/// Drops the encrypted key from disk; whoever forgets it needs the key again to sign in.
+pub fn forget_encrypted_key(lock_ctx: &KeyLockStore) {
+    let Some(encrypted) = lock_ctx.encrypted.clone() else {
+        return;
+    };
+    lock_ctx.dispatch(KeyLockAction::Forgotten);
+    yew::platform::spawn_local(async move {
+        if encrypted.delete_from_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to forget encrypted key".into());
+        }
+    });
+}
+
+#[function_component(KeyLockProvider)]
+pub fn key_lock_provider(props: &yew::html::ChildrenProps) -> HtmlResult {
+    let key_ctx = nostr_minions::key_manager::use_nostr_id_ctx();
+    let language_ctx = crate::language::use_language_ctx();
+    let stored = yew::suspense::use_future_with((), |_| async move {
+        EncryptedKey::retrieve_all_from_store()
+            .await
+            .ok()
+            .and_then(|keys| keys.into_iter().next())
+    })?;
+    let ctx = use_reducer(|| KeyLockState {
+        encrypted: (*stored).clone(),
+    });
+    let last_activity = use_mut_ref(js_sys::Date::now);
+
+    {
+        let last_activity = last_activity.clone();
+        use_effect_with((), move |()| {
+            let listeners = web_sys::window()
+                .map(|window| {
+                    ACTIVITY_EVENTS
+                        .iter()
+                        .map(|event| {
+                            let last_activity = last_activity.clone();
+                            gloo::events::EventListener::new(&window, *event, move |_| {
+                                *last_activity.borrow_mut() = js_sys::Date::now();
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            move || drop(listeners)
+        });
+    }
+    {
+        let unlocked = ctx.is_protected() && key_ctx.get_identity().is_some();
+        let key_ctx = key_ctx.clone();
+        let locked_message = language_ctx.t("key_lock_auto_locked");
+        use_effect_with(unlocked, move |unlocked| {
+            *last_activity.borrow_mut() = js_sys::Date::now();
+            let interval = unlocked.then(|| {
+                gloo::timers::callback::Interval::new(ACTIVITY_CHECK_MS, move || {
+                    let idle_ms = js_sys::Date::now() - *last_activity.borrow();
+                    if idle_ms > f64::from(AUTO_LOCK_MINUTES * 60_000) {
+                        key_ctx.dispatch(NostrIdAction::DeleteIdentity);
+                        crate::toast_success(&locked_message);
+                    }
+                })
+            });
+            move || drop(interval)
+        });
+    }
+
+    Ok(html! {
+        <ContextProvider<KeyLockStore> context={ctx}>
+            {props.children.clone()}
+        </ContextProvider<KeyLockStore>>
+    })
+}
+
+#[hook]
+pub fn use_key_lock_ctx() -> KeyLockStore {
+    use_context::<KeyLockStore>().expect("KeyLockStore context not set")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr_minions::nostro2::NostrSigner;
+    use nostr_minions::nostro2_signer::keypair::NostrKeypair;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    async fn clear_stored_identity() {
+        if let Ok(identity) = UserIdentity::find_identity().await {
+            identity
+                .delete_from_store()
+                .await
+                .expect("stored identity is removed");
+        }
+    }
+
+    #[wasm_bindgen_test]
+    async fn protected_keys_never_reach_the_identity_store() {
+        clear_stored_identity().await;
+        let keypair = NostrKeypair::generate(true);
+        let (identity, encrypted) = prepare_identity(keypair.clone(), Some("hunter2".into()))
+            .await
+            .expect("key is prepared");
+        let encrypted = encrypted.expect("a passphrase encrypts the key");
+        assert_eq!(identity.get_pubkey().await, Some(keypair.public_key()));
+        assert!(UserIdentity::find_identity().await.is_err());
+
+        let unlocked = crate::nip49::decrypt_keypair(&encrypted.ncryptsec, "hunter2")
+            .expect("passphrase decrypts");
+        let identity = session_identity(&unlocked).await.expect("key unlocks");
+        assert_eq!(identity.get_pubkey().await, Some(keypair.public_key()));
+        assert!(UserIdentity::find_identity().await.is_err());
+    }
+
+    #[wasm_bindgen_test]
+    async fn unprotected_keys_are_stored_for_the_next_visit() {
+        clear_stored_identity().await;
+        let (_, encrypted) = prepare_identity(NostrKeypair::generate(true), None)
+            .await
+            .expect("key is prepared");
+        assert!(encrypted.is_none());
+        assert!(UserIdentity::find_identity().await.is_ok());
+        clear_stored_identity().await;
+    }
+}
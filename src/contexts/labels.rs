@@ -0,0 +1,324 @@
+use std::collections::{BTreeSet, HashMap};
+use std::rc::Rc;
+
+use nostr_minions::browser_api::IdbStoreManager;
+use yew::prelude::*;
+
+/// User-defined tags and collection memberships of one game, stored apart from the signed note.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct GameLabels {
+    pub game_id: String,
+    pub tags: BTreeSet<String>,
+    pub collections: BTreeSet<String>,
+}
+
+impl GameLabels {
+    #[must_use]
+    pub fn new(game_id: &str) -> Self {
+        Self {
+            game_id: game_id.to_string(),
+            ..Default::default()
+        }
+    }
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.collections.is_empty()
+    }
+    /// Combines labels from another device with the local ones.
+    #[must_use]
+    pub fn merged(&self, other: &Self) -> Self {
+        Self {
+            game_id: self.game_id.clone(),
+            tags: self.tags.union(&other.tags).cloned().collect(),
+            collections: self
+                .collections
+                .union(&other.collections)
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+impl IdbStoreManager for GameLabels {
+    fn config() -> nostr_minions::browser_api::IdbStoreConfig {
+        crate::idb::store_config("game_labels", "game_id")
+    }
+    fn key(&self) -> web_sys::wasm_bindgen::JsValue {
+        web_sys::wasm_bindgen::JsValue::from_str(&self.game_id)
+    }
+}
+
+impl From<GameLabels> for web_sys::wasm_bindgen::JsValue {
+    fn from(value: GameLabels) -> Self {
+        serde_wasm_bindgen::to_value(&value).unwrap_or_default()
+    }
+}
+
+impl TryFrom<web_sys::wasm_bindgen::JsValue> for GameLabels {
+    type Error = web_sys::wasm_bindgen::JsValue;
+    fn try_from(value: web_sys::wasm_bindgen::JsValue) -> Result<Self, Self::Error> {
+        Ok(serde_wasm_bindgen::from_value(value)?)
+    }
+}
+
+/// A named group of games, such as a tournament or a student's games.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct GameCollection {
+    pub name: String,
+    pub created_at: u64,
+}
+
+impl IdbStoreManager for GameCollection {
+    fn config() -> nostr_minions::browser_api::IdbStoreConfig {
+        crate::idb::store_config("game_collections", "name")
+    }
+    fn key(&self) -> web_sys::wasm_bindgen::JsValue {
+        web_sys::wasm_bindgen::JsValue::from_str(&self.name)
+    }
+}
+
+impl From<GameCollection> for web_sys::wasm_bindgen::JsValue {
+    fn from(value: GameCollection) -> Self {
+        serde_wasm_bindgen::to_value(&value).unwrap_or_default()
+    }
+}
+
+impl TryFrom<web_sys::wasm_bindgen::JsValue> for GameCollection {
+    type Error = web_sys::wasm_bindgen::JsValue;
+    fn try_from(value: web_sys::wasm_bindgen::JsValue) -> Result<Self, Self::Error> {
+        Ok(serde_wasm_bindgen::from_value(value)?)
+    }
+}
+
+/// What the games list can be narrowed down to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LabelFilter {
+    Tag(String),
+    Collection(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct GameLabelsState {
+    labels: HashMap<String, GameLabels>,
+    collections: Vec<GameCollection>,
+}
+
+impl GameLabelsState {
+    #[must_use]
+    pub fn labels(&self, game_id: &str) -> GameLabels {
+        self.labels
+            .get(game_id)
+            .cloned()
+            .unwrap_or_else(|| GameLabels::new(game_id))
+    }
+    /// Every tag in use, sorted.
+    #[must_use]
+    pub fn tags(&self) -> Vec<String> {
+        self.labels
+            .values()
+            .flat_map(|labels| labels.tags.iter().cloned())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+    #[must_use]
+    pub fn collections(&self) -> Vec<GameCollection> {
+        self.collections.clone()
+    }
+    #[must_use]
+    pub fn matches(&self, game_id: &str, filter: &LabelFilter) -> bool {
+        self.labels.get(game_id).is_some_and(|labels| match filter {
+            LabelFilter::Tag(tag) => labels.tags.contains(tag),
+            LabelFilter::Collection(name) => labels.collections.contains(name),
+        })
+    }
+}
+
+pub enum GameLabelsAction {
+    SetLabels(GameLabels),
+    AddCollection(GameCollection),
+    RemoveCollection(String),
+    RemoveGames(Vec<String>),
+}
+
+impl Reducible for GameLabelsState {
+    type Action = GameLabelsAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut labels = self.labels.clone();
+        let mut collections = self.collections.clone();
+        match action {
+            GameLabelsAction::SetLabels(game_labels) => {
+                if game_labels.is_empty() {
+                    labels.remove(&game_labels.game_id);
+                } else {
+                    labels.insert(game_labels.game_id.clone(), game_labels);
+                }
+            }
+            GameLabelsAction::AddCollection(collection) => {
+                if !collections
+                    .iter()
+                    .any(|known| known.name == collection.name)
+                {
+                    collections.push(collection);
+                    collections.sort_by(|a, b| a.name.cmp(&b.name));
+                }
+            }
+            GameLabelsAction::RemoveCollection(name) => {
+                collections.retain(|collection| collection.name != name);
+                for game_labels in labels.values_mut() {
+                    game_labels.collections.remove(&name);
+                }
+                labels.retain(|_, game_labels| !game_labels.is_empty());
+            }
+            GameLabelsAction::RemoveGames(ids) => {
+                for id in ids {
+                    labels.remove(&id);
+                }
+            }
+        }
+        Rc::new(Self {
+            labels,
+            collections,
+        })
+    }
+}
+
+pub type GameLabelsStore = UseReducerHandle<GameLabelsState>;
+
+/// Stores new labels for a game and marks its backup as outdated so the change syncs.
+pub fn save_labels(
+    ctx: &GameLabelsStore,
+    backups: &crate::backup::GameBackupsStore,
+    game_labels: GameLabels,
+) {
+    ctx.dispatch(GameLabelsAction::SetLabels(game_labels.clone()));
+    crate::backup::mark_outdated(backups, vec![game_labels.game_id.clone()]);
+    yew::platform::spawn_local(async move {
+        let saved = if game_labels.is_empty() {
+            game_labels.delete_from_store().await
+        } else {
+            game_labels.save_to_store().await
+        };
+        if saved.is_err() {
+            web_sys::console::error_1(&"Failed to save game labels".into());
+        }
+    });
+}
+
+pub fn create_collection(ctx: &GameLabelsStore, name: &str) -> Option<GameCollection> {
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let collection = GameCollection {
+        name: name.to_string(),
+        created_at: web_sys::js_sys::Date::now() as u64,
+    };
+    ctx.dispatch(GameLabelsAction::AddCollection(collection.clone()));
+    let stored = collection.clone();
+    yew::platform::spawn_local(async move {
+        if stored.save_to_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to save collection".into());
+        }
+    });
+    Some(collection)
+}
+
+/// Deletes a collection; its games stay in the database.
+pub fn delete_collection(ctx: &GameLabelsStore, name: &str) {
+    let members = ctx
+        .labels
+        .values()
+        .filter(|labels| labels.collections.contains(name))
+        .map(|labels| {
+            let mut labels = labels.clone();
+            labels.collections.remove(name);
+            labels
+        })
+        .collect::<Vec<_>>();
+    let collection = ctx
+        .collections
+        .iter()
+        .find(|collection| collection.name == name)
+        .cloned();
+    ctx.dispatch(GameLabelsAction::RemoveCollection(name.to_string()));
+    yew::platform::spawn_local(async move {
+        if let Some(collection) = collection {
+            if collection.delete_from_store().await.is_err() {
+                web_sys::console::error_1(&"Failed to delete collection".into());
+            }
+        }
+        for labels in members {
+            let saved = if labels.is_empty() {
+                labels.delete_from_store().await
+            } else {
+                labels.save_to_store().await
+            };
+            if saved.is_err() {
+                web_sys::console::error_1(&"Failed to save game labels".into());
+            }
+        }
+    });
+}
+
+#[function_component(GameLabelsProvider)]
+pub fn game_labels_provider(props: &yew::html::ChildrenProps) -> HtmlResult {
+    let game_ctx = crate::live_game::use_game_history();
+    let stored = yew::suspense::use_future_with((), |_| async move {
+        let labels = GameLabels::retrieve_all_from_store()
+            .await
+            .unwrap_or_default();
+        let mut collections = GameCollection::retrieve_all_from_store()
+            .await
+            .unwrap_or_default();
+        collections.sort_by(|a, b| a.name.cmp(&b.name));
+        (labels, collections)
+    })?;
+    let ctx = use_reducer(|| GameLabelsState {
+        labels: stored
+            .0
+            .iter()
+            .map(|labels| (labels.game_id.clone(), labels.clone()))
+            .collect(),
+        collections: stored.1.clone(),
+    });
+
+    {
+        // Labels outlive their game only until the games list no longer has it.
+        let ctx = ctx.clone();
+        let entries = game_ctx.rooky_game_entries();
+        use_effect_with((game_ctx.synced, entries.len()), move |(synced, _)| {
+            if *synced {
+                let orphaned = ctx
+                    .labels
+                    .keys()
+                    .filter(|id| !entries.iter().any(|entry| &entry.id == *id))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if !orphaned.is_empty() {
+                    ctx.dispatch(GameLabelsAction::RemoveGames(orphaned.clone()));
+                    yew::platform::spawn_local(async move {
+                        for id in orphaned {
+                            if GameLabels::new(&id).delete_from_store().await.is_err() {
+                                web_sys::console::error_1(&"Failed to delete game labels".into());
+                            }
+                        }
+                    });
+                }
+            }
+            || {}
+        });
+    }
+
+    Ok(html! {
+        <ContextProvider<GameLabelsStore> context={ctx}>
+            {props.children.clone()}
+        </ContextProvider<GameLabelsStore>>
+    })
+}
+
+#[hook]
+pub fn use_game_labels() -> GameLabelsStore {
+    use_context::<GameLabelsStore>().expect("GameLabelsStore context not set")
+}
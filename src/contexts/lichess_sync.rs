@@ -0,0 +1,488 @@
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use futures_util::StreamExt;
+use nostr_minions::browser_api::IdbStoreManager;
+use yew::prelude::*;
+
+/// A Lichess account whose new games are imported on startup and on demand.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TrackedLichessAccount {
+    pub username: String,
+    /// When the newest imported game started, in milliseconds; the next sync resumes here.
+    pub last_game_at: Option<u64>,
+    pub last_synced_at: Option<u64>,
+}
+
+impl TrackedLichessAccount {
+    #[must_use]
+    pub fn new(username: &str) -> Self {
+        Self {
+            username: username.trim().to_lowercase(),
+            last_game_at: None,
+            last_synced_at: None,
+        }
+    }
+}
+
+impl IdbStoreManager for TrackedLichessAccount {
+    fn config() -> nostr_minions::browser_api::IdbStoreConfig {
+        crate::idb::store_config("lichess_accounts", "username")
+    }
+    fn key(&self) -> web_sys::wasm_bindgen::JsValue {
+        web_sys::wasm_bindgen::JsValue::from_str(&self.username)
+    }
+}
+
+impl From<TrackedLichessAccount> for web_sys::wasm_bindgen::JsValue {
+    fn from(value: TrackedLichessAccount) -> Self {
+        serde_wasm_bindgen::to_value(&value).unwrap_or_default()
+    }
+}
+
+impl TryFrom<web_sys::wasm_bindgen::JsValue> for TrackedLichessAccount {
+    type Error = web_sys::wasm_bindgen::JsValue;
+    fn try_from(value: web_sys::wasm_bindgen::JsValue) -> Result<Self, Self::Error> {
+        Ok(serde_wasm_bindgen::from_value(value)?)
+    }
+}
+
+fn pgn_header<'a>(pgn: &'a str, name: &str) -> Option<&'a str> {
+    pgn.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix('[')?
+            .strip_prefix(name)?
+            .trim()
+            .strip_suffix(']')?
+            .trim()
+            .strip_prefix('"')?
+            .strip_suffix('"')
+    })
+}
+
+/// When a game started, in milliseconds, read from the `UTCDate` and `UTCTime` headers
+/// Lichess writes, or from `Date` at midnight when they are missing.
+fn game_started_at_ms(pgn: &str) -> Option<u64> {
+    let started = pgn_header(pgn, "UTCDate")
+        .zip(pgn_header(pgn, "UTCTime"))
+        .and_then(|(date, time)| {
+            chrono::NaiveDateTime::parse_from_str(&format!("{date} {time}"), "%Y.%m.%d %H:%M:%S")
+                .ok()
+        })
+        .or_else(|| {
+            chrono::NaiveDate::parse_from_str(pgn_header(pgn, "Date")?, "%Y.%m.%d")
+                .ok()?
+                .and_hms_opt(0, 0, 0)
+        })?;
+    u64::try_from(started.and_utc().timestamp_millis()).ok()
+}
+
+/// Imports the games `account` played since its last sync, skipping any already in `known`.
+///
+/// The client is passed in so a sync can be pointed at another server than lichess.org.
+/// A cancelled sync keeps the previous resume point, since Lichess streams newest games first.
+///
+/// # Errors
+/// Returns a message if the game history request fails.
+pub async fn sync_account(
+    client: &external::LichessClient,
+    keypair: &crate::signer::BunkerSigner,
+    account: TrackedLichessAccount,
+    known: &mut HashSet<String>,
+    game_ctx: &UseReducerDispatcher<crate::live_game::AnnotatedGameHistory>,
+    job: &mut crate::imports::ImportJobHandle,
+) -> Result<TrackedLichessAccount, String> {
+    let query = external::LichessGameQuery {
+        username: account.username.clone(),
+        since: account.last_game_at,
+        ..Default::default()
+    };
+    let mut games = client
+        .stream_game_history(query)
+        .await
+        .map_err(|_| format!("Failed to fetch games for {}", account.username))?;
+    let mut last_game_at = account.last_game_at;
+    while let Some(game) = games.next().await {
+        if job.is_cancelled() {
+            return Ok(account);
+        }
+        let note: nostr_minions::nostro2::NostrNote = game.into();
+        // `since` is inclusive, so the newest game is fetched again next time and skipped
+        // as known rather than risking a game started in the same second being missed.
+        last_game_at = last_game_at.max(game_started_at_ms(&note.content));
+        job.import_note(keypair, known, game_ctx, note).await;
+    }
+    let account = TrackedLichessAccount {
+        last_game_at,
+        last_synced_at: Some(web_sys::js_sys::Date::now() as u64),
+        ..account
+    };
+    // Saving an account untracked while it synced would track it again.
+    let still_tracked = TrackedLichessAccount::retrieve_all_from_store()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .any(|tracked| tracked.username == account.username);
+    if !still_tracked {
+        return Ok(account);
+    }
+    if account.clone().save_to_store().await.is_err() {
+        web_sys::console::error_1(&"Failed to save Lichess account".into());
+    }
+    Ok(account)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct LichessAccounts {
+    accounts: Vec<TrackedLichessAccount>,
+    syncing: HashSet<String>,
+}
+
+impl LichessAccounts {
+    #[must_use]
+    pub fn accounts(&self) -> Vec<TrackedLichessAccount> {
+        self.accounts.clone()
+    }
+    #[must_use]
+    pub fn is_syncing(&self, username: &str) -> bool {
+        self.syncing.contains(username)
+    }
+    #[must_use]
+    pub fn is_tracked(&self, username: &str) -> bool {
+        self.accounts
+            .iter()
+            .any(|account| account.username == username)
+    }
+}
+
+pub enum LichessAccountsAction {
+    Track(TrackedLichessAccount),
+    Untrack(String),
+    SyncStarted(String),
+    Synced(TrackedLichessAccount),
+    SyncFailed(String),
+}
+
+impl Reducible for LichessAccounts {
+    type Action = LichessAccountsAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut accounts = self.accounts.clone();
+        let mut syncing = self.syncing.clone();
+        match action {
+            LichessAccountsAction::Track(account) => {
+                accounts.retain(|tracked| tracked.username != account.username);
+                accounts.push(account);
+                accounts.sort_by(|a, b| a.username.cmp(&b.username));
+            }
+            LichessAccountsAction::Synced(account) => {
+                syncing.remove(&account.username);
+                // An account untracked mid-sync stays untracked.
+                if let Some(tracked) = accounts
+                    .iter_mut()
+                    .find(|tracked| tracked.username == account.username)
+                {
+                    *tracked = account;
+                }
+            }
+            LichessAccountsAction::Untrack(username) => {
+                syncing.remove(&username);
+                accounts.retain(|tracked| tracked.username != username);
+            }
+            LichessAccountsAction::SyncStarted(username) => {
+                syncing.insert(username);
+            }
+            LichessAccountsAction::SyncFailed(username) => {
+                syncing.remove(&username);
+            }
+        }
+        Rc::new(Self { accounts, syncing })
+    }
+}
+
+pub type LichessAccountsStore = UseReducerHandle<LichessAccounts>;
+
+/// Syncs each account in turn, sharing one set of known games so overlapping histories import once.
+pub fn sync_accounts(
+    client: external::LichessClient,
+    ctx: &LichessAccountsStore,
+    jobs: &crate::imports::ImportJobsStore,
+    keypair: &crate::signer::BunkerSigner,
+    game_ctx: &crate::live_game::AnnotatedGameHistoryStore,
+    accounts: Vec<TrackedLichessAccount>,
+) {
+    let accounts = accounts
+        .into_iter()
+        .filter(|account| !ctx.is_syncing(&account.username))
+        .collect::<Vec<_>>();
+    if accounts.is_empty() {
+        return;
+    }
+    for account in &accounts {
+        ctx.dispatch(LichessAccountsAction::SyncStarted(account.username.clone()));
+    }
+    let mut known = crate::known_fingerprints(&game_ctx.rooky_game_entries());
+    let ctx = ctx.dispatcher();
+    let game_ctx = game_ctx.dispatcher();
+    let keypair = keypair.clone();
+    let jobs = jobs.dispatcher();
+    yew::platform::spawn_local(async move {
+        for account in accounts {
+            let username = account.username.clone();
+            let mut job = crate::imports::ImportJobHandle::start(
+                jobs.clone(),
+                crate::imports::ImportSource::LichessSync,
+                &username,
+            );
+            match sync_account(&client, &keypair, account, &mut known, &game_ctx, &mut job).await {
+                Ok(account) => {
+                    ctx.dispatch(LichessAccountsAction::Synced(account));
+                    job.finish(crate::imports::ImportStatus::Completed);
+                }
+                Err(error) => {
+                    ctx.dispatch(LichessAccountsAction::SyncFailed(username));
+                    job.finish(crate::imports::ImportStatus::Failed);
+                    crate::toast_failure(&error);
+                }
+            }
+        }
+    });
+}
+
+#[function_component(LichessSyncProvider)]
+pub fn lichess_sync_provider(props: &yew::html::ChildrenProps) -> HtmlResult {
+    let game_ctx = crate::live_game::use_game_history();
+    let jobs = crate::imports::use_import_jobs();
+    let keypair = crate::signer::use_signer();
+    let tracked = yew::suspense::use_future_with((), |_| async move {
+        TrackedLichessAccount::retrieve_all_from_store()
+            .await
+            .unwrap_or_default()
+    })?;
+    let ctx = use_reducer(|| LichessAccounts {
+        accounts: (*tracked).clone(),
+        syncing: HashSet::new(),
+    });
+
+    {
+        let ctx = ctx.clone();
+        use_effect_with((game_ctx.synced, keypair.is_some()), move |(synced, _)| {
+            if let (true, Some(keypair)) = (*synced, keypair.as_ref()) {
+                sync_accounts(
+                    external::LichessClient::default(),
+                    &ctx,
+                    &jobs,
+                    keypair,
+                    &game_ctx,
+                    ctx.accounts(),
+                );
+            }
+            || {}
+        });
+    }
+
+    Ok(html! {
+        <ContextProvider<LichessAccountsStore> context={ctx}>
+            {props.children.clone()}
+        </ContextProvider<LichessAccountsStore>>
+    })
+}
+
+#[hook]
+pub fn use_lichess_accounts() -> LichessAccountsStore {
+    use_context::<LichessAccountsStore>().expect("LichessAccountsStore context not set")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr_minions::nostro2::NostrSigner;
+    use std::cell::RefCell;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    const LICHESS_PGN: &str = "[Event \"Rated blitz game\"]\n[Date \"2024.03.09\"]\n[UTCDate \"2024.03.09\"]\n[UTCTime \"18:42:07\"]\n\n1. e4 e5 1-0\n";
+
+    #[test]
+    fn started_at_uses_utc_date_and_time() {
+        assert_eq!(game_started_at_ms(LICHESS_PGN), Some(1_710_009_727_000));
+    }
+
+    #[test]
+    fn started_at_falls_back_to_date() {
+        let pgn = "[Date \"2024.03.09\"]\n\n1. e4 *\n";
+        assert_eq!(game_started_at_ms(pgn), Some(1_709_942_400_000));
+        assert_eq!(game_started_at_ms("1. e4 *"), None);
+    }
+
+    #[test]
+    fn synced_account_untracked_mid_sync_stays_untracked() {
+        let accounts = Rc::new(LichessAccounts::default())
+            .reduce(LichessAccountsAction::Track(TrackedLichessAccount::new(
+                "Magnus",
+            )))
+            .reduce(LichessAccountsAction::SyncStarted("magnus".to_string()))
+            .reduce(LichessAccountsAction::Untrack("magnus".to_string()));
+        let synced = accounts.reduce(LichessAccountsAction::Synced(TrackedLichessAccount {
+            last_game_at: Some(1),
+            ..TrackedLichessAccount::new("magnus")
+        }));
+        assert!(!synced.is_tracked("magnus"));
+        assert!(!synced.is_syncing("magnus"));
+    }
+
+    fn lichess_game(white: &str, time: &str, moves: &str) -> String {
+        format!(
+            "[Event \"Rated blitz game\"]\n[Site \"https://lichess.org/abcdefgh\"]\n[Date \"2024.03.09\"]\n[White \"{white}\"]\n[Black \"magnus\"]\n[Result \"0-1\"]\n[UTCDate \"2024.03.09\"]\n[UTCTime \"{time}\"]\n\n{moves} 0-1\n\n"
+        )
+    }
+
+    /// Replaces `window.fetch` with a stand-in answering each request with the next of
+    /// `pages` and recording the requested URLs in `window.lichessRequests`.
+    fn serve(pages: &[String]) {
+        let pages = pages
+            .iter()
+            .map(|page| web_sys::wasm_bindgen::JsValue::from_str(page))
+            .collect::<web_sys::js_sys::Array>();
+        web_sys::js_sys::Function::new_with_args(
+            "pages",
+            "window.lichessRequests = []; \
+            window.fetch = (request) => { \
+                window.lichessRequests.push(typeof request === 'string' ? request : request.url); \
+                return Promise.resolve(new Response(pages.shift() ?? '', { status: 200 })); \
+            };",
+        )
+        .call1(&web_sys::wasm_bindgen::JsValue::NULL, &pages)
+        .expect("fetch stand-in installs");
+    }
+
+    fn requested_urls() -> Vec<String> {
+        web_sys::window()
+            .and_then(|window| {
+                web_sys::js_sys::Reflect::get(&window, &"lichessRequests".into()).ok()
+            })
+            .map(|urls| {
+                web_sys::js_sys::Array::from(&urls)
+                    .iter()
+                    .filter_map(|url| url.as_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    type Dispatchers = (
+        UseReducerDispatcher<crate::live_game::AnnotatedGameHistory>,
+        UseReducerDispatcher<crate::imports::ImportJobs>,
+    );
+
+    #[derive(Properties, PartialEq)]
+    struct HarnessProps {
+        ready: Callback<Dispatchers>,
+    }
+
+    #[function_component(Harness)]
+    fn harness(props: &HarnessProps) -> Html {
+        let games = use_reducer(crate::live_game::AnnotatedGameHistory::default);
+        let jobs = use_reducer(crate::imports::ImportJobs::default);
+        let ready = props.ready.clone();
+        use_effect_with((), move |()| {
+            ready.emit((games.dispatcher(), jobs.dispatcher()));
+            || {}
+        });
+        html! {}
+    }
+
+    /// Renders reducers for the game history and import jobs and hands back their dispatchers.
+    async fn dispatchers() -> Dispatchers {
+        let slot = Rc::new(RefCell::new(None));
+        let ready = {
+            let slot = slot.clone();
+            Callback::from(move |dispatchers| *slot.borrow_mut() = Some(dispatchers))
+        };
+        let root = gloo::utils::document()
+            .create_element("div")
+            .expect("harness root is created");
+        yew::Renderer::<Harness>::with_root_and_props(root, HarnessProps { ready }).render();
+        for _ in 0..10 {
+            if let Some(dispatchers) = slot.borrow_mut().take() {
+                return dispatchers;
+            }
+            gloo::timers::future::TimeoutFuture::new(0).await;
+        }
+        panic!("harness did not render");
+    }
+
+    #[wasm_bindgen_test]
+    async fn sync_resumes_from_the_newest_game_and_skips_it_as_known() {
+        let keypair = crate::signer::BunkerSigner::Local(
+            nostr_minions::nostro2_signer::keypair::NostrKeypair::generate(false),
+        );
+        let (game_ctx, jobs) = dispatchers().await;
+        TrackedLichessAccount::new("magnus")
+            .save_to_store()
+            .await
+            .expect("account is tracked");
+        let client = external::LichessClient::default();
+        let mut known = HashSet::new();
+
+        // Lichess streams newest games first, in a single response per sync.
+        let newest = lichess_game("Hikaru", "18:42:07", "1. e4 e5");
+        let older = lichess_game("Fabiano", "17:05:00", "1. d4 d5");
+        let latest = lichess_game("Alireza", "20:15:30", "1. c4 e5");
+        serve(&[format!("{newest}{older}"), format!("{latest}{newest}")]);
+
+        let mut job = crate::imports::ImportJobHandle::start(
+            jobs.clone(),
+            crate::imports::ImportSource::LichessSync,
+            "magnus",
+        );
+        let account = sync_account(
+            &client,
+            &keypair,
+            TrackedLichessAccount::new("magnus"),
+            &mut known,
+            &game_ctx,
+            &mut job,
+        )
+        .await
+        .expect("stand-in answers");
+        assert_eq!(account.last_game_at, game_started_at_ms(&newest));
+        assert_eq!((job.job().fetched, job.job().saved.len()), (2, 2));
+
+        let mut job = crate::imports::ImportJobHandle::start(
+            jobs,
+            crate::imports::ImportSource::LichessSync,
+            "magnus",
+        );
+        let account = sync_account(&client, &keypair, account, &mut known, &game_ctx, &mut job)
+            .await
+            .expect("stand-in answers");
+        assert_eq!(account.last_game_at, game_started_at_ms(&latest));
+        assert_eq!(
+            (job.job().fetched, job.job().skipped, job.job().saved.len()),
+            (2, 1, 1)
+        );
+
+        let urls = requested_urls();
+        assert_eq!(urls.len(), 2);
+        assert!(urls
+            .iter()
+            .all(|url| url.contains("/api/games/user/magnus")));
+        assert!(!urls[0].contains("since="));
+        let since = game_started_at_ms(&newest).expect("newest game has a start");
+        assert!(urls[1].contains(&format!("since={since}")));
+
+        let stored = TrackedLichessAccount::retrieve_all_from_store()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .find(|tracked| tracked.username == "magnus")
+            .expect("account stays tracked");
+        assert_eq!(stored.last_game_at, account.last_game_at);
+        stored
+            .delete_from_store()
+            .await
+            .expect("account is untracked");
+    }
+}
@@ -29,6 +29,7 @@ pub enum AnnotatedGameHistoryAction {
     LoadGames(Vec<rooky_core::idb::RookyGameEntry>),
     AddGame(rooky_core::idb::RookyGameEntry),
     AddReceivedGame(rooky_core::idb::RookyGameEntry),
+    RemoveGames(Vec<String>),
 }
 
 impl Reducible for AnnotatedGameHistory {
@@ -45,6 +46,9 @@ impl Reducible for AnnotatedGameHistory {
                 ..(*self).clone()
             }),
             AnnotatedGameHistoryAction::AddReceivedGame(game) => {
+                if self.pgn_games.iter().any(|known| known.id == game.id) {
+                    return self;
+                }
                 let mut pgn_games = self.pgn_games.clone();
                 let mut unread = self.unread.clone();
                 unread.push(game.clone());
@@ -55,9 +59,24 @@ impl Reducible for AnnotatedGameHistory {
                     ..(*self).clone()
                 })
             }
+            AnnotatedGameHistoryAction::RemoveGames(ids) => {
+                let mut pgn_games = self.pgn_games.clone();
+                let mut unread = self.unread.clone();
+                pgn_games.retain(|game| !ids.contains(&game.id));
+                unread.retain(|game| !ids.contains(&game.id));
+                Rc::new(Self {
+                    pgn_games,
+                    unread,
+                    ..(*self).clone()
+                })
+            }
             AnnotatedGameHistoryAction::AddGame(game) => {
                 let mut pgn_games = self.pgn_games.clone();
-                pgn_games.push(game);
+                if let Some(known) = pgn_games.iter_mut().find(|known| known.id == game.id) {
+                    *known = game;
+                } else {
+                    pgn_games.push(game);
+                }
                 Rc::new(Self {
                     pgn_games,
                     ..(*self).clone()
@@ -78,12 +97,15 @@ pub struct AnnotatedGameHistoryChildren {
 pub fn key_handler(props: &AnnotatedGameHistoryChildren) -> HtmlResult {
     let relay_ctx = use_context::<nostr_minions::relay_pool::NostrRelayPoolStore>()
         .expect("Relay context not found");
-    let user_id = nostr_minions::key_manager::use_nostr_key();
+    let user_id = crate::signer::use_signer();
     let last_sync = nostr_minions::use_last_sync_time();
     let sub_id = use_state(|| None);
+    let owner = user_id
+        .as_ref()
+        .map(crate::signer::BunkerSigner::public_key);
     let games = yew::suspense::use_future_with((), |_| async move {
         if let Ok(games) = rooky_core::idb::RookyGameEntry::retrieve_all_from_store().await {
-            return games;
+            return GameOwner::owned_games(games, owner.as_deref()).await;
         }
         vec![]
     })?;
@@ -92,6 +114,39 @@ pub fn key_handler(props: &AnnotatedGameHistoryChildren) -> HtmlResult {
         pgn_games: (*games).clone(),
         unread: vec![],
     });
+    let owned = use_mut_ref(|| {
+        games
+            .iter()
+            .map(|game| game.id.clone())
+            .collect::<std::collections::HashSet<_>>()
+    });
+
+    // Games saved while this identity is active belong to it.
+    {
+        let owner = user_id
+            .as_ref()
+            .map(crate::signer::BunkerSigner::public_key);
+        use_effect_with(ctx.pgn_games.clone(), move |games| {
+            if let Some(owner) = owner {
+                let new_owners = games
+                    .iter()
+                    .filter(|game| owned.borrow_mut().insert(game.id.clone()))
+                    .map(|game| GameOwner {
+                        game_id: game.id.clone(),
+                        pubkey: owner.clone(),
+                    })
+                    .collect::<Vec<_>>();
+                yew::platform::spawn_local(async move {
+                    for game_owner in new_owners {
+                        if game_owner.save_to_store().await.is_err() {
+                            web_sys::console::error_1(&"Failed to save game owner".into());
+                        }
+                    }
+                });
+            }
+            || {}
+        });
+    }
 
     // {
     //     let dispatcher = ctx.dispatcher();
@@ -146,47 +201,24 @@ pub fn key_handler(props: &AnnotatedGameHistoryChildren) -> HtmlResult {
     {
         let dispatcher = ctx.dispatcher();
         let set_id = sub_id.clone();
-        use_effect_with(relay_ctx.relay_events.clone(), move |notes| {
-            if let Some(nostr_minions::nostro2::NostrRelayEvent::EndOfSubscription(_, sub_id)) =
-                notes.last()
-            {
-                if Some(sub_id) == set_id.as_ref() {
-                    web_sys::console::log_1(&"Synced".into());
-                    dispatcher.dispatch(AnnotatedGameHistoryAction::Synced);
-                }
-            }
-            || {}
-        });
-    }
-    {
-        let dispatcher = ctx.dispatcher();
-        use_effect_with(relay_ctx.unique_notes.clone(), move |notes| {
-            user_id.as_ref().and_then(|user_id| {
-                let note = notes.last().cloned()?;
-                let dm_note = user_id.extract_rumor(&note).ok()?;
-                rooky_core::RookyGame::try_from(dm_note.clone())
-                    .ok()
-                    .map(|_| {
-                        let entry = rooky_core::idb::RookyGameEntry {
-                            id: dm_note.id.clone().unwrap_or_default(),
-                            note: dm_note,
-                            origin: rooky_core::idb::GameOrigin::Received,
-                        };
-                        yew::platform::spawn_local(async move {
-                            if entry.clone().save_to_store().await.is_err() {
-                                web_sys::console::log_1(&"Failed to save".into());
-                                return;
-                            }
-                            if nostr_minions::LastSyncTime::new_sync_time().await.is_ok() {
-                                dispatcher
-                                    .dispatch(AnnotatedGameHistoryAction::AddReceivedGame(entry));
-                            }
-                        });
-                    })
+        let cursor = use_mut_ref(|| 0usize);
+        use_effect_with(relay_ctx.relay_events.clone(), move |events| {
+            let synced = crate::unseen(&cursor, events).iter().any(|event| {
+                matches!(
+                    event,
+                    nostr_minions::nostro2::NostrRelayEvent::EndOfSubscription(_, sub_id)
+                        if Some(sub_id) == set_id.as_ref()
+                )
             });
+            if synced {
+                web_sys::console::log_1(&"Synced".into());
+                dispatcher.dispatch(AnnotatedGameHistoryAction::Synced);
+            }
             || {}
         });
     }
+    // Incoming gift wraps are unwrapped once by the backup provider, which hands games
+    // someone sent back to `receive_game`.
 
     Ok(html! {
         <ContextProvider<AnnotatedGameHistoryStore> context={ctx}>
@@ -195,6 +227,353 @@ pub fn key_handler(props: &AnnotatedGameHistoryChildren) -> HtmlResult {
     })
 }
 
+/// Saves the game in an unwrapped rumor as received, unless it is already known.
+pub async fn receive_game(
+    known_games: &[rooky_core::idb::RookyGameEntry],
+    dispatcher: &UseReducerDispatcher<AnnotatedGameHistory>,
+    dm_note: nostr_minions::nostro2::NostrNote,
+) {
+    // The same rumor can arrive in several wraps, e.g. during a resync.
+    if known_games
+        .iter()
+        .any(|game| Some(&game.id) == dm_note.id.as_ref())
+        || rooky_core::RookyGame::try_from(dm_note.clone()).is_err()
+    {
+        return;
+    }
+    let entry = rooky_core::idb::RookyGameEntry {
+        id: dm_note.id.clone().unwrap_or_default(),
+        note: dm_note,
+        origin: rooky_core::idb::GameOrigin::Received,
+    };
+    if entry.clone().save_to_store().await.is_err() {
+        web_sys::console::log_1(&"Failed to save".into());
+        return;
+    }
+    if nostr_minions::LastSyncTime::new_sync_time().await.is_ok() {
+        dispatcher.dispatch(AnnotatedGameHistoryAction::AddReceivedGame(entry));
+    }
+}
+
+/// The kind used by rooky game notes, as produced by `RookyGame`'s note conversion.
+#[must_use]
+pub fn rooky_game_kind() -> u32 {
+    nostr_minions::nostro2::NostrNote::from(rooky_core::RookyGame::default()).kind
+}
+
+/// The parameterized replaceable kind used for edited games, addressed by their `d` tag.
+///
+/// It is fixed rather than derived from the game kind: most of the 30000 range is taken by
+/// NIP-51 lists and other addressable events, while 30064 mirrors NIP-64's chess kind and is
+/// not assigned by any NIP.
+#[must_use]
+pub const fn replaceable_game_kind() -> u32 {
+    30_064
+}
+
+/// A previous version of a game, kept locally whenever the game is edited.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct GameRevision {
+    pub id: String,
+    pub game_id: String,
+    pub note: nostr_minions::nostro2::NostrNote,
+    pub revised_at: u64,
+}
+
+impl GameRevision {
+    #[must_use]
+    pub fn from_entry(entry: &rooky_core::idb::RookyGameEntry) -> Self {
+        Self {
+            id: entry.note.id.clone().unwrap_or_default(),
+            game_id: entry.id.clone(),
+            note: entry.note.clone(),
+            revised_at: web_sys::js_sys::Date::now() as u64,
+        }
+    }
+
+    /// # Errors
+    /// Returns the underlying `JsValue` if the revisions cannot be read from IndexedDB.
+    pub async fn for_game(game_id: &str) -> Result<Vec<Self>, web_sys::wasm_bindgen::JsValue> {
+        let mut revisions = Self::retrieve_all_from_store()
+            .await?
+            .into_iter()
+            .filter(|revision| revision.game_id == game_id)
+            .collect::<Vec<_>>();
+        revisions.sort_by(|a, b| b.revised_at.cmp(&a.revised_at));
+        Ok(revisions)
+    }
+}
+
+impl IdbStoreManager for GameRevision {
+    fn config() -> nostr_minions::browser_api::IdbStoreConfig {
+        crate::idb::store_config("game_revisions", "id")
+    }
+    fn key(&self) -> web_sys::wasm_bindgen::JsValue {
+        web_sys::wasm_bindgen::JsValue::from_str(&self.id)
+    }
+}
+
+impl From<GameRevision> for web_sys::wasm_bindgen::JsValue {
+    fn from(value: GameRevision) -> Self {
+        serde_wasm_bindgen::to_value(&value).unwrap_or_default()
+    }
+}
+
+impl TryFrom<web_sys::wasm_bindgen::JsValue> for GameRevision {
+    type Error = web_sys::wasm_bindgen::JsValue;
+    fn try_from(value: web_sys::wasm_bindgen::JsValue) -> Result<Self, Self::Error> {
+        Ok(serde_wasm_bindgen::from_value(value)?)
+    }
+}
+
+/// Who sent or published a saved game, when it was not signed by `own_pubkey`.
+#[must_use]
+pub fn game_author(
+    entry: &rooky_core::idb::RookyGameEntry,
+    own_pubkey: Option<&str>,
+) -> Option<String> {
+    let pubkey = &entry.note.pubkey;
+    (!pubkey.is_empty() && Some(pubkey.as_str()) != own_pubkey).then(|| pubkey.clone())
+}
+
+/// The identity a saved game belongs to, so each account sees only its own games.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct GameOwner {
+    pub game_id: String,
+    pub pubkey: String,
+}
+
+impl GameOwner {
+    /// Keeps the games owned by `owner`. Games saved before accounts existed are
+    /// claimed by whichever identity loads them first.
+    pub async fn owned_games(
+        games: Vec<rooky_core::idb::RookyGameEntry>,
+        owner: Option<&str>,
+    ) -> Vec<rooky_core::idb::RookyGameEntry> {
+        let Some(owner) = owner else {
+            return games;
+        };
+        let owners = Self::retrieve_all_from_store()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|game_owner| (game_owner.game_id, game_owner.pubkey))
+            .collect::<std::collections::HashMap<_, _>>();
+        let mut owned = vec![];
+        for game in games {
+            match owners.get(&game.id) {
+                Some(pubkey) if pubkey != owner => {}
+                Some(_) => owned.push(game),
+                None => {
+                    let game_owner = Self {
+                        game_id: game.id.clone(),
+                        pubkey: owner.to_string(),
+                    };
+                    if game_owner.save_to_store().await.is_err() {
+                        web_sys::console::error_1(&"Failed to save game owner".into());
+                    }
+                    owned.push(game);
+                }
+            }
+        }
+        owned
+    }
+}
+
+impl IdbStoreManager for GameOwner {
+    fn config() -> nostr_minions::browser_api::IdbStoreConfig {
+        crate::idb::store_config("game_owners", "game_id")
+    }
+    fn key(&self) -> web_sys::wasm_bindgen::JsValue {
+        web_sys::wasm_bindgen::JsValue::from_str(&self.game_id)
+    }
+}
+
+impl From<GameOwner> for web_sys::wasm_bindgen::JsValue {
+    fn from(value: GameOwner) -> Self {
+        serde_wasm_bindgen::to_value(&value).unwrap_or_default()
+    }
+}
+
+impl TryFrom<web_sys::wasm_bindgen::JsValue> for GameOwner {
+    type Error = web_sys::wasm_bindgen::JsValue;
+    fn try_from(value: web_sys::wasm_bindgen::JsValue) -> Result<Self, Self::Error> {
+        Ok(serde_wasm_bindgen::from_value(value)?)
+    }
+}
+
+/// Signs `game`, with `comments` on its main line, as the next revision of `entry`,
+/// keeping the entry's id stable.
+///
+/// # Errors
+/// Returns the signer's error if the note cannot be signed.
+pub async fn revise_game(
+    keypair: &crate::signer::BunkerSigner,
+    entry: &rooky_core::idb::RookyGameEntry,
+    game: rooky_core::RookyGame,
+    comments: &[String],
+) -> Result<rooky_core::idb::RookyGameEntry, String> {
+    let content = crate::annotated_pgn(&game, comments);
+    let mut note: nostr_minions::nostro2::NostrNote = game.into();
+    note.kind = replaceable_game_kind();
+    note.content = content;
+    let mut note = crate::with_tag(note, &["d", &entry.id]);
+    keypair.sign_note(&mut note).await?;
+    Ok(rooky_core::idb::RookyGameEntry {
+        id: entry.id.clone(),
+        note,
+        origin: entry.origin.clone(),
+    })
+}
+
+/// Builds a NIP-09 deletion request for the entries the user published themselves.
+///
+/// Returns `None` when none of the entries were authored by `keypair`.
+pub async fn deletion_request(
+    keypair: &crate::signer::BunkerSigner,
+    entries: &[rooky_core::idb::RookyGameEntry],
+) -> Option<nostr_minions::nostro2::NostrNote> {
+    let pubkey = keypair.public_key();
+    let published = entries
+        .iter()
+        .filter(|entry| {
+            entry.origin == rooky_core::idb::GameOrigin::Annotated && entry.note.pubkey == pubkey
+        })
+        .collect::<Vec<_>>();
+    if published.is_empty() {
+        return None;
+    }
+    let mut note = nostr_minions::nostro2::NostrNote {
+        kind: 5,
+        pubkey: pubkey.clone(),
+        content: String::new(),
+        ..Default::default()
+    };
+    for entry in published {
+        if let Some(id) = entry.note.id.as_ref() {
+            note = crate::with_tag(note, &["e", id]);
+        }
+        if entry.note.kind == replaceable_game_kind() {
+            let address = format!("{}:{pubkey}:{}", entry.note.kind, entry.id);
+            note = crate::with_tag(note, &["a", &address]);
+        }
+        // The original, non-replaceable note keeps the entry id.
+        if entry.note.id.as_ref() != Some(&entry.id) {
+            note = crate::with_tag(note, &["e", &entry.id]);
+        }
+    }
+    let kinds = [rooky_game_kind(), replaceable_game_kind()];
+    for kind in kinds {
+        note = crate::with_tag(note, &["k", &kind.to_string()]);
+    }
+    keypair.sign_note(&mut note).await.ok()?;
+    Some(note)
+}
+
+/// Removes games from the local database and, for games the user published,
+/// queues a deletion request for the write relays.
+pub fn delete_games(
+    keypair: Option<&crate::signer::BunkerSigner>,
+    outbox: &crate::outbox::OutboxStore,
+    relay_ctx: &nostr_minions::relay_pool::NostrRelayPoolStore,
+    game_ctx: &AnnotatedGameHistoryStore,
+    entries: Vec<rooky_core::idb::RookyGameEntry>,
+) {
+    remove_games(keypair, outbox, relay_ctx, game_ctx, entries, true);
+}
+
+/// Removes duplicate copies here and, through backup tombstones, on the user's other
+/// devices, leaving the copies published to relays alone.
+pub fn discard_duplicates(
+    keypair: Option<&crate::signer::BunkerSigner>,
+    outbox: &crate::outbox::OutboxStore,
+    relay_ctx: &nostr_minions::relay_pool::NostrRelayPoolStore,
+    game_ctx: &AnnotatedGameHistoryStore,
+    entries: Vec<rooky_core::idb::RookyGameEntry>,
+) {
+    remove_games(keypair, outbox, relay_ctx, game_ctx, entries, false);
+}
+
+fn remove_games(
+    keypair: Option<&crate::signer::BunkerSigner>,
+    outbox: &crate::outbox::OutboxStore,
+    relay_ctx: &nostr_minions::relay_pool::NostrRelayPoolStore,
+    game_ctx: &AnnotatedGameHistoryStore,
+    entries: Vec<rooky_core::idb::RookyGameEntry>,
+    request_deletion: bool,
+) {
+    if entries.is_empty() {
+        return;
+    }
+    game_ctx.dispatch(AnnotatedGameHistoryAction::RemoveGames(
+        entries.iter().map(|entry| entry.id.clone()).collect(),
+    ));
+    let keypair = keypair.cloned();
+    let outbox = outbox.clone();
+    let relay_ctx = relay_ctx.clone();
+    yew::platform::spawn_local(async move {
+        if let Some(keypair) = keypair.as_ref() {
+            if request_deletion {
+                if let Some(request) = deletion_request(keypair, &entries).await {
+                    crate::outbox::publish_note(&outbox, &relay_ctx, request);
+                }
+            }
+            crate::backup::send_tombstones(keypair, &outbox, &relay_ctx, &entries).await;
+        }
+        let failed = remove_local_games(entries).await;
+        if failed > 0 {
+            crate::toast_failure(&format!("Failed to delete {failed} games"));
+        } else {
+            nostr_minions::widgets::toastify::ToastifyOptions::new_success(
+                "Games deleted successfully",
+            )
+            .show();
+        }
+    });
+}
+
+/// Deletes games and their revisions, labels and owners from IndexedDB, returning how many
+/// games could not be deleted.
+pub async fn remove_local_games(entries: Vec<rooky_core::idb::RookyGameEntry>) -> usize {
+    let mut failed = 0;
+    for entry in entries {
+        if let Ok(revisions) = GameRevision::for_game(&entry.id).await {
+            for revision in revisions {
+                if revision.delete_from_store().await.is_err() {
+                    web_sys::console::error_1(&"Failed to delete game revision".into());
+                }
+            }
+        }
+        if crate::labels::GameLabels::new(&entry.id)
+            .delete_from_store()
+            .await
+            .is_err()
+        {
+            web_sys::console::error_1(&"Failed to delete game labels".into());
+        }
+        let game_owner = GameOwner {
+            game_id: entry.id.clone(),
+            pubkey: String::new(),
+        };
+        if game_owner.delete_from_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to delete game owner".into());
+        }
+        // Games are stored under their entry id, while `key()` reads the note id, which
+        // changes with every revision.
+        let stored = rooky_core::idb::RookyGameEntry {
+            note: nostr_minions::nostro2::NostrNote {
+                id: Some(entry.id.clone()),
+                ..Default::default()
+            },
+            ..entry
+        };
+        if stored.delete_from_store().await.is_err() {
+            failed += 1;
+        }
+    }
+    failed
+}
+
 #[hook]
 pub fn use_game_history() -> AnnotatedGameHistoryStore {
     use_context::<AnnotatedGameHistoryStore>().expect("AnnotatedGameHistoryStore context not set")
@@ -1,2 +1,13 @@
+pub mod accounts;
+pub mod backup;
+pub mod imports;
+pub mod key_lock;
+pub mod labels;
 pub mod language;
+pub mod lichess_sync;
 pub mod live_game;
+pub mod outbox;
+pub mod profiles;
+pub mod relay_reconnect;
+pub mod relay_stats;
+pub mod signer;
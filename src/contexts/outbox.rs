@@ -0,0 +1,365 @@
+use std::rc::Rc;
+
+use nostr_minions::browser_api::IdbStoreManager;
+use yew::prelude::*;
+
+const BASE_RETRY_DELAY_MS: u64 = 5_000;
+const MAX_RETRY_DELAY_MS: u64 = 600_000;
+const RETRY_TICK_MS: u32 = 5_000;
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct OutboxEntry {
+    pub id: String,
+    pub note: nostr_minions::nostro2::NostrNote,
+    pub queued_at: u64,
+    pub attempts: u32,
+    pub next_attempt_at: u64,
+}
+
+impl OutboxEntry {
+    /// Queues a signed note; unsigned notes have no id to be acknowledged by and are refused.
+    #[must_use]
+    pub fn new(note: nostr_minions::nostro2::NostrNote) -> Option<Self> {
+        let id = note
+            .id
+            .clone()
+            .filter(|id| !id.is_empty() && note.sig.is_some())?;
+        let now = now_ms();
+        Some(Self {
+            id,
+            note,
+            queued_at: now,
+            attempts: 0,
+            next_attempt_at: now,
+        })
+    }
+
+    #[must_use]
+    pub fn is_due(&self, now: u64) -> bool {
+        self.next_attempt_at <= now
+    }
+
+    #[must_use]
+    fn attempted(&self, now: u64) -> Self {
+        let attempts = self.attempts.saturating_add(1);
+        let delay = BASE_RETRY_DELAY_MS
+            .saturating_mul(1 << attempts.min(16))
+            .min(MAX_RETRY_DELAY_MS);
+        Self {
+            attempts,
+            next_attempt_at: now + delay,
+            ..self.clone()
+        }
+    }
+}
+
+impl IdbStoreManager for OutboxEntry {
+    fn config() -> nostr_minions::browser_api::IdbStoreConfig {
+        crate::idb::store_config("outbox", "id")
+    }
+    fn key(&self) -> web_sys::wasm_bindgen::JsValue {
+        web_sys::wasm_bindgen::JsValue::from_str(&self.id)
+    }
+}
+
+impl From<OutboxEntry> for web_sys::wasm_bindgen::JsValue {
+    fn from(value: OutboxEntry) -> Self {
+        serde_wasm_bindgen::to_value(&value).unwrap_or_default()
+    }
+}
+
+impl TryFrom<web_sys::wasm_bindgen::JsValue> for OutboxEntry {
+    type Error = web_sys::wasm_bindgen::JsValue;
+    fn try_from(value: web_sys::wasm_bindgen::JsValue) -> Result<Self, Self::Error> {
+        Ok(serde_wasm_bindgen::from_value(value)?)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Outbox {
+    entries: Vec<OutboxEntry>,
+    /// Counts each note in the session stats when it first goes out; `None` outside the provider.
+    stats: Option<UseReducerDispatcher<crate::relay_stats::RelayStats>>,
+}
+
+impl PartialEq for Outbox {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl Outbox {
+    #[must_use]
+    pub fn queued(&self) -> Vec<OutboxEntry> {
+        self.entries.clone()
+    }
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    #[must_use]
+    pub fn contains(&self, id: &str) -> bool {
+        self.entries.iter().any(|entry| entry.id == id)
+    }
+}
+
+pub enum OutboxAction {
+    Queue(OutboxEntry),
+    Attempted(String),
+    Acknowledged(String),
+}
+
+impl Reducible for Outbox {
+    type Action = OutboxAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        match action {
+            OutboxAction::Queue(entry) => {
+                let mut entries = self.entries.clone();
+                entries.retain(|queued| queued.id != entry.id);
+                entries.push(entry);
+                Rc::new(Self {
+                    entries,
+                    stats: self.stats.clone(),
+                })
+            }
+            OutboxAction::Attempted(id) => {
+                let now = now_ms();
+                let entries = self
+                    .entries
+                    .iter()
+                    .map(|entry| {
+                        if entry.id == id {
+                            entry.attempted(now)
+                        } else {
+                            entry.clone()
+                        }
+                    })
+                    .collect();
+                Rc::new(Self {
+                    entries,
+                    stats: self.stats.clone(),
+                })
+            }
+            OutboxAction::Acknowledged(id) => {
+                let mut entries = self.entries.clone();
+                entries.retain(|entry| entry.id != id);
+                Rc::new(Self {
+                    entries,
+                    stats: self.stats.clone(),
+                })
+            }
+        }
+    }
+}
+
+pub type OutboxStore = UseReducerHandle<Outbox>;
+
+#[function_component(OutboxProvider)]
+pub fn outbox_provider(props: &yew::html::ChildrenProps) -> HtmlResult {
+    let relay_ctx = use_context::<nostr_minions::relay_pool::NostrRelayPoolStore>()
+        .expect("Relay context not found");
+    let queued = yew::suspense::use_future_with((), |_| async move {
+        OutboxEntry::retrieve_all_from_store()
+            .await
+            .unwrap_or_default()
+    })?;
+    let stats = crate::relay_stats::use_relay_stats().dispatcher();
+    let ctx = use_reducer(|| Outbox {
+        entries: (*queued).clone(),
+        stats: Some(stats),
+    });
+    let tick = use_state(|| 0u32);
+
+    {
+        let tick = tick.clone();
+        use_effect_with((), move |()| {
+            let interval = gloo::timers::callback::Interval::new(RETRY_TICK_MS, move || {
+                tick.set((*tick).wrapping_add(1));
+            });
+            move || drop(interval)
+        });
+    }
+    {
+        let ctx = ctx.clone();
+        let open_relays = relay_ctx
+            .relay_health()
+            .values()
+            .filter(|state| **state == nostr_minions::relay_pool::ReadyState::OPEN)
+            .count();
+        let relay_ctx = relay_ctx.clone();
+        use_effect_with((open_relays, *tick), move |(open_relays, _)| {
+            if *open_relays > 0 {
+                let now = now_ms();
+                for entry in ctx.queued().into_iter().filter(|entry| entry.is_due(now)) {
+                    retry_entry(&ctx, &relay_ctx, &entry);
+                }
+            }
+            || {}
+        });
+    }
+    {
+        let ctx = ctx.clone();
+        let cursor = use_mut_ref(|| 0usize);
+        use_effect_with(relay_ctx.relay_events.clone(), move |events| {
+            for event in crate::unseen(&cursor, events) {
+                let nostr_minions::nostro2::NostrRelayEvent::SentOk(_, id, true, _) = event else {
+                    continue;
+                };
+                if let Some(entry) = ctx.queued().into_iter().find(|entry| &entry.id == id) {
+                    ctx.dispatch(OutboxAction::Acknowledged(entry.id.clone()));
+                    yew::platform::spawn_local(async move {
+                        if entry.delete_from_store().await.is_err() {
+                            web_sys::console::error_1(&"Failed to clear outbox entry".into());
+                        }
+                    });
+                }
+            }
+            || {}
+        });
+    }
+
+    Ok(html! {
+        <ContextProvider<OutboxStore> context={ctx}>
+            {props.children.clone()}
+        </ContextProvider<OutboxStore>>
+    })
+}
+
+#[hook]
+pub fn use_outbox() -> OutboxStore {
+    use_context::<OutboxStore>().expect("OutboxStore context not set")
+}
+
+/// Queues a signed note in the persistent outbox and sends it if any relay is open.
+///
+/// The entry stays queued until a relay acknowledges it with an `OK` message. Unsigned
+/// notes are refused.
+pub fn publish_note(
+    outbox: &OutboxStore,
+    relay_ctx: &nostr_minions::relay_pool::NostrRelayPoolStore,
+    note: nostr_minions::nostro2::NostrNote,
+) {
+    let Some(entry) = OutboxEntry::new(note) else {
+        web_sys::console::error_1(&"Refusing to queue an unsigned note".into());
+        return;
+    };
+    let is_online = relay_ctx
+        .relay_health()
+        .values()
+        .any(|state| *state == nostr_minions::relay_pool::ReadyState::OPEN);
+    let entry = if is_online {
+        send_entry(outbox, relay_ctx, &entry);
+        entry.attempted(now_ms())
+    } else {
+        entry
+    };
+    outbox.dispatch(OutboxAction::Queue(entry.clone()));
+    yew::platform::spawn_local(async move {
+        if entry.save_to_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to persist outbox entry".into());
+        }
+    });
+}
+
+/// Sends a queued entry again and persists its updated attempt count and backoff.
+pub fn retry_entry(
+    outbox: &OutboxStore,
+    relay_ctx: &nostr_minions::relay_pool::NostrRelayPoolStore,
+    entry: &OutboxEntry,
+) {
+    send_entry(outbox, relay_ctx, entry);
+    outbox.dispatch(OutboxAction::Attempted(entry.id.clone()));
+    let attempted = entry.attempted(now_ms());
+    yew::platform::spawn_local(async move {
+        if attempted.save_to_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to update outbox entry".into());
+        }
+    });
+}
+
+/// Sends an entry's note, counting it in the session stats on its first attempt only.
+fn send_entry(
+    outbox: &OutboxStore,
+    relay_ctx: &nostr_minions::relay_pool::NostrRelayPoolStore,
+    entry: &OutboxEntry,
+) {
+    relay_ctx.send(entry.note.clone());
+    if let (0, Some(stats)) = (entry.attempts, outbox.stats.as_ref()) {
+        stats.dispatch(crate::relay_stats::RelayStatsAction::Sent(entry.note.kind));
+    }
+}
+
+fn now_ms() -> u64 {
+    web_sys::js_sys::Date::now() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr_minions::nostro2::NostrSigner;
+    use nostr_minions::nostro2_signer::keypair::NostrKeypair;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    fn signed_note(content: &str) -> nostr_minions::nostro2::NostrNote {
+        let mut note = nostr_minions::nostro2::NostrNote {
+            kind: 1,
+            content: content.to_string(),
+            ..Default::default()
+        };
+        NostrKeypair::generate(false)
+            .sign_note(&mut note)
+            .expect("note is signed");
+        note
+    }
+
+    #[wasm_bindgen_test]
+    fn refuses_unsigned_notes() {
+        assert!(OutboxEntry::new(nostr_minions::nostro2::NostrNote::default()).is_none());
+        let entry = OutboxEntry::new(signed_note("1. d4")).expect("signed notes are queued");
+        assert_eq!(Some(&entry.id), entry.note.id.as_ref());
+        assert!(entry.is_due(entry.queued_at));
+    }
+
+    #[wasm_bindgen_test]
+    fn doubles_the_retry_delay_up_to_the_cap() {
+        let entry = OutboxEntry::new(signed_note("1. e4")).expect("signed notes are queued");
+        let first = entry.attempted(0);
+        assert_eq!(
+            (first.attempts, first.next_attempt_at),
+            (1, 2 * BASE_RETRY_DELAY_MS)
+        );
+        let second = first.attempted(0);
+        assert_eq!(second.next_attempt_at, 4 * BASE_RETRY_DELAY_MS);
+        let capped = (0..20).fold(second, |entry, _| entry.attempted(0));
+        assert_eq!(capped.next_attempt_at, MAX_RETRY_DELAY_MS);
+        assert!(!capped.is_due(MAX_RETRY_DELAY_MS - 1));
+        assert!(capped.is_due(MAX_RETRY_DELAY_MS));
+    }
+
+    #[wasm_bindgen_test]
+    fn queues_retries_and_acknowledges_entries() {
+        let first = OutboxEntry::new(signed_note("1. c4")).expect("signed notes are queued");
+        let second = OutboxEntry::new(signed_note("1. Nf3")).expect("signed notes are queued");
+        let outbox = Rc::new(Outbox::default())
+            .reduce(OutboxAction::Queue(first.clone()))
+            .reduce(OutboxAction::Queue(second.clone()))
+            .reduce(OutboxAction::Queue(first.clone()));
+        assert_eq!(outbox.queued().len(), 2);
+
+        let outbox = outbox.reduce(OutboxAction::Attempted(first.id.clone()));
+        let attempted = outbox
+            .queued()
+            .into_iter()
+            .find(|entry| entry.id == first.id)
+            .expect("entry stays queued");
+        assert_eq!(attempted.attempts, 1);
+        assert!(!attempted.is_due(now_ms()));
+
+        let outbox = outbox.reduce(OutboxAction::Acknowledged(first.id.clone()));
+        assert!(!outbox.contains(&first.id));
+        assert!(outbox.contains(&second.id));
+    }
+}
@@ -0,0 +1,302 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use nostr_minions::browser_api::IdbStoreManager;
+use serde::Serialize;
+use yew::prelude::*;
+
+use crate::components::NostrMetadata;
+
+/// How long a cached profile is trusted before it is requested again.
+pub const PROFILE_TTL_MS: u64 = 6 * 60 * 60 * 1000;
+/// Requests made within this window are sent to relays as one subscription.
+const BATCH_DELAY_MS: u32 = 300;
+/// Authors per kind-0 subscription, to keep filters small for relays.
+const MAX_BATCH_AUTHORS: usize = 100;
+
+/// The newest kind-0 seen for a pubkey, and when it was last asked for.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CachedProfile {
+    pub pubkey: String,
+    pub metadata: NostrMetadata,
+    /// `created_at` of the kind-0 note, so older notes never replace newer ones.
+    pub created_at: i64,
+    pub fetched_at: u64,
+}
+
+impl CachedProfile {
+    #[must_use]
+    pub fn is_fresh(&self) -> bool {
+        now_ms().saturating_sub(self.fetched_at) < PROFILE_TTL_MS
+    }
+}
+
+impl IdbStoreManager for CachedProfile {
+    fn config() -> nostr_minions::browser_api::IdbStoreConfig {
+        crate::idb::store_config("profiles", "pubkey")
+    }
+    fn key(&self) -> web_sys::wasm_bindgen::JsValue {
+        web_sys::wasm_bindgen::JsValue::from_str(&self.pubkey)
+    }
+}
+
+impl From<CachedProfile> for web_sys::wasm_bindgen::JsValue {
+    fn from(value: CachedProfile) -> Self {
+        // Unknown metadata fields are a map; store them as a plain object, not a JS `Map`.
+        value
+            .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+            .unwrap_or_default()
+    }
+}
+
+impl TryFrom<web_sys::wasm_bindgen::JsValue> for CachedProfile {
+    type Error = web_sys::wasm_bindgen::JsValue;
+    fn try_from(value: web_sys::wasm_bindgen::JsValue) -> Result<Self, Self::Error> {
+        Ok(serde_wasm_bindgen::from_value(value)?)
+    }
+}
+
+fn now_ms() -> u64 {
+    web_sys::js_sys::Date::now() as u64
+}
+
+#[derive(Clone, Default)]
+pub struct ProfileCache {
+    profiles: HashMap<String, CachedProfile>,
+    /// Pubkeys waiting for the next batched request.
+    queue: Rc<RefCell<HashSet<String>>>,
+    /// Pubkeys already asked for this session, so missing profiles are not re-requested.
+    requested: Rc<RefCell<HashSet<String>>>,
+    /// Authors of each batch subscription still waiting for its EOSE.
+    pending: Rc<RefCell<HashMap<String, Vec<String>>>>,
+    /// Pubkeys whose request reached EOSE, so a missing profile is known not to exist.
+    settled: HashSet<String>,
+}
+
+impl PartialEq for ProfileCache {
+    fn eq(&self, other: &Self) -> bool {
+        self.profiles == other.profiles && self.settled == other.settled
+    }
+}
+
+impl ProfileCache {
+    #[must_use]
+    pub fn get(&self, pubkey: &str) -> Option<&CachedProfile> {
+        self.profiles.get(pubkey)
+    }
+    #[must_use]
+    pub fn metadata(&self, pubkey: &str) -> Option<NostrMetadata> {
+        self.profiles
+            .get(pubkey)
+            .map(|profile| profile.metadata.clone())
+    }
+    /// Queues a kind-0 request for `pubkey` unless its cached profile is still fresh.
+    pub fn request(&self, pubkey: &str) {
+        if pubkey.is_empty()
+            || self
+                .profiles
+                .get(pubkey)
+                .is_some_and(CachedProfile::is_fresh)
+            || self.requested.borrow().contains(pubkey)
+        {
+            return;
+        }
+        self.queue.borrow_mut().insert(pubkey.to_string());
+    }
+    /// Whether the profile shown for `pubkey` is the newest known: it was fetched recently,
+    /// or relays answered this session's request.
+    #[must_use]
+    pub fn is_settled(&self, pubkey: &str) -> bool {
+        self.settled.contains(pubkey)
+            || self
+                .profiles
+                .get(pubkey)
+                .is_some_and(CachedProfile::is_fresh)
+    }
+    fn take_batch(&self) -> Vec<String> {
+        let batch = self.queue.borrow_mut().drain().collect::<Vec<_>>();
+        self.requested.borrow_mut().extend(batch.iter().cloned());
+        batch
+    }
+}
+
+pub enum ProfileCacheAction {
+    Loaded(Vec<CachedProfile>),
+    Updated(CachedProfile),
+    /// A batch subscription reached EOSE for these authors.
+    Settled(Vec<String>),
+}
+
+impl Reducible for ProfileCache {
+    type Action = ProfileCacheAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut profiles = self.profiles.clone();
+        let mut settled = self.settled.clone();
+        match action {
+            ProfileCacheAction::Loaded(loaded) => {
+                for profile in loaded {
+                    profiles.insert(profile.pubkey.clone(), profile);
+                }
+            }
+            ProfileCacheAction::Updated(profile) => {
+                if profiles
+                    .get(&profile.pubkey)
+                    .is_some_and(|cached| cached.created_at > profile.created_at)
+                {
+                    return self;
+                }
+                profiles.insert(profile.pubkey.clone(), profile);
+            }
+            ProfileCacheAction::Settled(authors) => settled.extend(authors),
+        }
+        Rc::new(Self {
+            profiles,
+            queue: self.queue.clone(),
+            requested: self.requested.clone(),
+            pending: self.pending.clone(),
+            settled,
+        })
+    }
+}
+
+pub type ProfileCacheStore = UseReducerHandle<ProfileCache>;
+
+/// Caches and persists `note` if it is a kind-0 newer than what is already known.
+pub fn cache_profile_note(ctx: &ProfileCacheStore, note: &nostr_minions::nostro2::NostrNote) {
+    if note.kind != 0
+        || ctx
+            .get(&note.pubkey)
+            .is_some_and(|cached| cached.created_at >= note.created_at)
+    {
+        return;
+    }
+    let Ok(metadata) = NostrMetadata::try_from(note) else {
+        return;
+    };
+    let profile = CachedProfile {
+        pubkey: note.pubkey.clone(),
+        metadata,
+        created_at: note.created_at,
+        fetched_at: now_ms(),
+    };
+    ctx.dispatch(ProfileCacheAction::Updated(profile.clone()));
+    yew::platform::spawn_local(async move {
+        if profile.save_to_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to cache profile".into());
+        }
+    });
+}
+
+#[function_component(ProfileCacheProvider)]
+pub fn profile_cache_provider(props: &yew::html::ChildrenProps) -> Html {
+    let relay_ctx = nostr_minions::relay_pool::use_nostr_relay_pool();
+    let ctx = use_reducer(ProfileCache::default);
+
+    {
+        let dispatcher = ctx.dispatcher();
+        use_effect_with((), move |()| {
+            yew::platform::spawn_local(async move {
+                match CachedProfile::retrieve_all_from_store().await {
+                    Ok(profiles) => dispatcher.dispatch(ProfileCacheAction::Loaded(profiles)),
+                    Err(_) => {
+                        web_sys::console::error_1(&"Failed to load cached profiles".into());
+                    }
+                }
+            });
+            || {}
+        });
+    }
+    {
+        let ctx = ctx.clone();
+        let relay_ctx = relay_ctx.clone();
+        use_effect_with((), move |()| {
+            let interval = gloo::timers::callback::Interval::new(BATCH_DELAY_MS, move || {
+                let batch = ctx.take_batch();
+                for authors in batch.chunks(MAX_BATCH_AUTHORS) {
+                    let filter = nostr_minions::nostro2::NostrSubscription {
+                        kinds: vec![0].into(),
+                        authors: authors.to_vec().into(),
+                        ..Default::default()
+                    };
+                    if let nostr_minions::nostro2::NostrClientEvent::Subscribe(_, id, _) =
+                        relay_ctx.send(filter)
+                    {
+                        ctx.pending.borrow_mut().insert(id, authors.to_vec());
+                    }
+                }
+            });
+            move || drop(interval)
+        });
+    }
+    {
+        let ctx = ctx.clone();
+        let relay_ctx = relay_ctx.clone();
+        let cursor = use_mut_ref(|| 0usize);
+        use_effect_with(relay_ctx.relay_events.clone(), move |events| {
+            for event in crate::unseen(&cursor, events) {
+                let nostr_minions::nostro2::NostrRelayEvent::EndOfSubscription(_, sub_id) = event
+                else {
+                    continue;
+                };
+                let authors = ctx.pending.borrow_mut().remove(sub_id);
+                if let Some(authors) = authors {
+                    // Kind-0 is replaceable and stored, so nothing new comes after EOSE.
+                    crate::close_subscription(&relay_ctx, sub_id);
+                    ctx.dispatch(ProfileCacheAction::Settled(authors));
+                }
+            }
+            || {}
+        });
+    }
+    {
+        let ctx = ctx.clone();
+        let cursor = use_mut_ref(|| 0usize);
+        use_effect_with(relay_ctx.unique_notes.clone(), move |notes| {
+            // The snapshot in `ctx` does not see earlier notes of the same burst, so only
+            // the newest metadata of each author is cached.
+            let mut newest = HashMap::<&str, &nostr_minions::nostro2::NostrNote>::new();
+            for note in crate::unseen(&cursor, notes)
+                .iter()
+                .filter(|note| note.kind == 0)
+            {
+                let entry = newest.entry(note.pubkey.as_str()).or_insert(note);
+                if note.created_at > entry.created_at {
+                    *entry = note;
+                }
+            }
+            for note in newest.into_values() {
+                cache_profile_note(&ctx, note);
+            }
+            || {}
+        });
+    }
+
+    html! {
+        <ContextProvider<ProfileCacheStore> context={ctx}>
+            {props.children.clone()}
+        </ContextProvider<ProfileCacheStore>>
+    }
+}
+
+#[hook]
+pub fn use_profile_cache() -> ProfileCacheStore {
+    use_context::<ProfileCacheStore>().expect("ProfileCacheStore context not set")
+}
+
+/// Cached metadata for `pubkey`, fetching it in the next batch when missing or stale.
+#[hook]
+pub fn use_profile(pubkey: Option<String>) -> Option<NostrMetadata> {
+    let ctx = use_profile_cache();
+    {
+        let ctx = ctx.clone();
+        use_effect_with(pubkey.clone(), move |pubkey| {
+            if let Some(pubkey) = pubkey {
+                ctx.request(pubkey);
+            }
+            || {}
+        });
+    }
+    pubkey.and_then(|pubkey| ctx.metadata(&pubkey))
+}
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use nostr_minions::browser_api::IdbStoreManager;
+use nostr_minions::relay_pool::ReadyState;
+use yew::prelude::*;
+
+const BASE_RECONNECT_DELAY_MS: f64 = 2_000.0;
+const MAX_RECONNECT_DELAY_MS: f64 = 300_000.0;
+/// How long a relay may stay connecting before the attempt is abandoned and retried.
+const CONNECT_TIMEOUT_MS: f64 = 15_000.0;
+
+fn now_ms() -> f64 {
+    web_sys::js_sys::Date::now()
+}
+
+/// When a closed relay is next retried, doubling the wait after every failed attempt.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Backoff {
+    pub attempts: u32,
+    pub next_attempt_at: f64,
+}
+
+impl Backoff {
+    fn after(attempts: u32) -> Self {
+        let delay = (BASE_RECONNECT_DELAY_MS * f64::from(1u32 << attempts.min(16)))
+            .min(MAX_RECONNECT_DELAY_MS);
+        Self {
+            attempts,
+            next_attempt_at: now_ms() + delay,
+        }
+    }
+    /// A first retry for a relay that is still connecting, due once the connect timeout ends.
+    fn connecting() -> Self {
+        Self {
+            attempts: 0,
+            next_attempt_at: now_ms() + CONNECT_TIMEOUT_MS,
+        }
+    }
+    #[must_use]
+    pub fn is_due(&self, now: f64) -> bool {
+        self.next_attempt_at <= now
+    }
+    /// Whole seconds until the next attempt.
+    #[must_use]
+    pub fn seconds_left(&self) -> u64 {
+        ((self.next_attempt_at - now_ms()).max(0.0) / 1000.0).ceil() as u64
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct RelayReconnect {
+    backoff: HashMap<String, Backoff>,
+}
+
+impl RelayReconnect {
+    #[must_use]
+    pub fn backoff(&self, url: &str) -> Option<&Backoff> {
+        self.backoff.get(url)
+    }
+}
+
+pub enum RelayReconnectAction {
+    /// Starts a backoff for newly closed or connecting relays and clears it for relays that
+    /// connected.
+    Health(HashMap<String, ReadyState>),
+    Attempted(String),
+    Reset(String),
+}
+
+impl Reducible for RelayReconnect {
+    type Action = RelayReconnectAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut backoff = self.backoff.clone();
+        match action {
+            RelayReconnectAction::Health(health) => {
+                backoff.retain(|url, _| {
+                    health
+                        .get(url)
+                        .is_some_and(|state| *state != ReadyState::OPEN)
+                });
+                for (url, state) in health {
+                    match state {
+                        ReadyState::CLOSED => {
+                            backoff.entry(url).or_insert_with(|| Backoff::after(0));
+                        }
+                        ReadyState::CONNECTING => {
+                            backoff.entry(url).or_insert_with(Backoff::connecting);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            RelayReconnectAction::Attempted(url) => {
+                let attempts = backoff.get(&url).map_or(0, |backoff| backoff.attempts) + 1;
+                backoff.insert(url, Backoff::after(attempts));
+            }
+            RelayReconnectAction::Reset(url) => {
+                backoff.remove(&url);
+            }
+        }
+        if backoff == self.backoff {
+            return self;
+        }
+        Rc::new(Self { backoff })
+    }
+}
+
+pub type RelayReconnectStore = UseReducerHandle<RelayReconnect>;
+
+/// Drops and re-adds `url` in the pool, keeping its saved read and write markers.
+pub fn reconnect_relay(relay_ctx: &nostr_minions::relay_pool::NostrRelayPoolStore, url: &str) {
+    let relay_ctx = relay_ctx.clone();
+    let url = url.to_string();
+    yew::platform::spawn_local(async move {
+        let relay = nostr_minions::relay_pool::UserRelay::retrieve_all_from_store()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .find(|relay| relay.url == url)
+            .unwrap_or(nostr_minions::relay_pool::UserRelay {
+                url,
+                read: true,
+                write: true,
+            });
+        relay_ctx
+            .dispatch(nostr_minions::relay_pool::NostrRelayPoolAction::RemoveRelay(relay.clone()));
+        relay_ctx.dispatch(nostr_minions::relay_pool::NostrRelayPoolAction::AddRelay(
+            relay,
+        ));
+    });
+}
+
+/// Retries closed relays, and relays stuck connecting, with exponential backoff instead of
+/// leaving them until reload.
+#[function_component(RelayReconnectProvider)]
+pub fn relay_reconnect_provider(props: &yew::html::ChildrenProps) -> Html {
+    let relay_ctx = nostr_minions::relay_pool::use_nostr_relay_pool();
+    let ctx = use_reducer(RelayReconnect::default);
+
+    {
+        let dispatcher = ctx.dispatcher();
+        use_effect_with(relay_ctx.relay_health(), move |health| {
+            dispatcher.dispatch(RelayReconnectAction::Health(health.clone()));
+            || {}
+        });
+    }
+    {
+        let ctx = ctx.clone();
+        let relay_ctx = relay_ctx.clone();
+        // One timer for the earliest backoff; every attempt changes the state and reschedules it.
+        use_effect_with((*ctx).clone(), move |reconnect| {
+            let next = reconnect
+                .backoff
+                .values()
+                .map(|backoff| backoff.next_attempt_at)
+                .min_by(f64::total_cmp);
+            let timeout = next.map(|next| {
+                let delay = (next - now_ms()).clamp(0.0, MAX_RECONNECT_DELAY_MS);
+                gloo::timers::callback::Timeout::new(delay.ceil() as u32, move || {
+                    let now = now_ms();
+                    let due = ctx
+                        .backoff
+                        .iter()
+                        .filter(|(_, backoff)| backoff.is_due(now))
+                        .map(|(url, _)| url.clone())
+                        .collect::<Vec<_>>();
+                    for url in due {
+                        reconnect_relay(&relay_ctx, &url);
+                        ctx.dispatch(RelayReconnectAction::Attempted(url));
+                    }
+                })
+            });
+            move || drop(timeout)
+        });
+    }
+
+    html! {
+        <ContextProvider<RelayReconnectStore> context={ctx}>
+            {props.children.clone()}
+        </ContextProvider<RelayReconnectStore>>
+    }
+}
+
+#[hook]
+pub fn use_relay_reconnect() -> RelayReconnectStore {
+    use_context::<RelayReconnectStore>().expect("RelayReconnectStore context not set")
+}
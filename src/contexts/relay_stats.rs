@@ -0,0 +1,127 @@
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use nostr_minions::relay_pool::ReadyState;
+use yew::prelude::*;
+
+/// How one relay's connection has behaved this session.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct RelayConnection {
+    /// When the current connection opened, if the relay is connected.
+    pub connected_since: Option<f64>,
+    /// How many times the relay reached the open state.
+    pub connections: u32,
+}
+
+impl RelayConnection {
+    #[must_use]
+    pub const fn reconnects(&self) -> u32 {
+        self.connections.saturating_sub(1)
+    }
+    #[must_use]
+    pub fn uptime_ms(&self) -> Option<f64> {
+        self.connected_since
+            .map(|since| web_sys::js_sys::Date::now() - since)
+    }
+}
+
+/// Connection history per relay and note counts per kind, for the current session.
+///
+/// The pool does not say which relay delivered a note, so the counts are session totals
+/// across all relays, each unique note counted once.
+#[derive(Clone, PartialEq, Default)]
+pub struct RelayStats {
+    connections: HashMap<String, RelayConnection>,
+    received: BTreeMap<u32, u32>,
+    /// Counted by the outbox when a note is first sent, not on retries.
+    sent: BTreeMap<u32, u32>,
+}
+
+impl RelayStats {
+    #[must_use]
+    pub fn connection(&self, url: &str) -> RelayConnection {
+        self.connections.get(url).cloned().unwrap_or_default()
+    }
+    #[must_use]
+    pub const fn received(&self) -> &BTreeMap<u32, u32> {
+        &self.received
+    }
+    #[must_use]
+    pub const fn sent(&self) -> &BTreeMap<u32, u32> {
+        &self.sent
+    }
+}
+
+pub enum RelayStatsAction {
+    Health(HashMap<String, ReadyState>),
+    Received(u32),
+    Sent(u32),
+}
+
+impl Reducible for RelayStats {
+    type Action = RelayStatsAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut stats = (*self).clone();
+        match action {
+            RelayStatsAction::Health(health) => {
+                let now = web_sys::js_sys::Date::now();
+                for (url, state) in health {
+                    let connection = stats.connections.entry(url).or_default();
+                    match (state == ReadyState::OPEN, connection.connected_since) {
+                        (true, None) => {
+                            connection.connected_since = Some(now);
+                            connection.connections += 1;
+                        }
+                        (false, Some(_)) => connection.connected_since = None,
+                        _ => {}
+                    }
+                }
+            }
+            RelayStatsAction::Received(kind) => {
+                *stats.received.entry(kind).or_default() += 1;
+            }
+            RelayStatsAction::Sent(kind) => {
+                *stats.sent.entry(kind).or_default() += 1;
+            }
+        }
+        Rc::new(stats)
+    }
+}
+
+pub type RelayStatsStore = UseReducerHandle<RelayStats>;
+
+#[function_component(RelayStatsProvider)]
+pub fn relay_stats_provider(props: &yew::html::ChildrenProps) -> Html {
+    let relay_ctx = nostr_minions::relay_pool::use_nostr_relay_pool();
+    let ctx = use_reducer(RelayStats::default);
+
+    {
+        let dispatcher = ctx.dispatcher();
+        use_effect_with(relay_ctx.relay_health(), move |health| {
+            dispatcher.dispatch(RelayStatsAction::Health(health.clone()));
+            || {}
+        });
+    }
+    {
+        let dispatcher = ctx.dispatcher();
+        let cursor = use_mut_ref(|| 0usize);
+        use_effect_with(relay_ctx.unique_notes.clone(), move |notes| {
+            for note in crate::unseen(&cursor, notes) {
+                dispatcher.dispatch(RelayStatsAction::Received(note.kind));
+            }
+            || {}
+        });
+    }
+
+    html! {
+        <ContextProvider<RelayStatsStore> context={ctx}>
+            {props.children.clone()}
+        </ContextProvider<RelayStatsStore>>
+    }
+}
+
+#[hook]
+pub fn use_relay_stats() -> RelayStatsStore {
+    use_context::<RelayStatsStore>().expect("RelayStatsStore context not set")
+}
@@ -0,0 +1,784 @@
+use std::rc::Rc;
+
+use nostr_minions::browser_api::IdbStoreManager;
+use nostr_minions::nostro2::NostrSigner;
+use nostr_minions::nostro2_signer::nostro2_nips::{Nip17, Nip44};
+use sha2::Digest;
+use web_sys::js_sys;
+use web_sys::wasm_bindgen::{JsCast, JsValue};
+use yew::prelude::*;
+
+const SEAL_KIND: u32 = 13;
+const PRIVATE_DM_KIND: u32 = 14;
+const GIFT_WRAP_KIND: u32 = 1059;
+/// How far back seals and wraps are dated, so their timestamps do not tell when a
+/// message was sent (NIP-59).
+const WRAP_TIME_WINDOW_SECS: u32 = 2 * 24 * 60 * 60;
+
+fn js_error(error: JsValue) -> String {
+    error
+        .as_string()
+        .or_else(|| {
+            js_sys::Reflect::get(&error, &"message".into())
+                .ok()
+                .and_then(|message| message.as_string())
+        })
+        .unwrap_or_else(|| "Signer extension error".to_string())
+}
+
+/// The `window.nostr` object injected by a NIP-07 extension.
+fn window_nostr() -> Result<JsValue, String> {
+    let window = web_sys::window().ok_or("No window found")?;
+    js_sys::Reflect::get(&window, &"nostr".into())
+        .ok()
+        .filter(|nostr| nostr.is_object())
+        .ok_or_else(|| "No signer extension found".to_string())
+}
+
+/// Calls `target.method(...args)` and awaits the result, whether or not it is a promise.
+async fn call_method(target: &JsValue, method: &str, args: &[JsValue]) -> Result<JsValue, String> {
+    let function = js_sys::Reflect::get(target, &method.into())
+        .map_err(js_error)?
+        .dyn_into::<js_sys::Function>()
+        .map_err(|_| format!("Signer extension does not support {method}"))?;
+    let result = function
+        .apply(target, &args.iter().collect::<js_sys::Array>())
+        .map_err(js_error)?;
+    wasm_bindgen_futures::JsFuture::from(js_sys::Promise::resolve(&result))
+        .await
+        .map_err(js_error)
+}
+
+/// Computes the NIP-01 id of a note that is not going to be signed, such as a rumor.
+fn note_id(note: &nostr_minions::nostro2::NostrNote) -> Result<String, String> {
+    let value = serde_json::to_value(note).map_err(|e| e.to_string())?;
+    let serialized = serde_json::json!([
+        0,
+        value["pubkey"],
+        value["created_at"],
+        value["kind"],
+        value
+            .get("tags")
+            .cloned()
+            .unwrap_or_else(|| serde_json::json!([])),
+        value["content"],
+    ]);
+    Ok(sha2::Sha256::digest(serialized.to_string().as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+fn parse_note(json: &str) -> Result<nostr_minions::nostro2::NostrNote, String> {
+    serde_json::from_str(json).map_err(|e| e.to_string())
+}
+
+/// A NIP-07 browser extension, reached through `window.nostr`.
+///
+/// The public key is asked for once when connecting; every signature and
+/// NIP-44 operation goes through the extension, which may prompt the user.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ExtensionSigner {
+    pub pubkey: String,
+}
+
+impl ExtensionSigner {
+    #[must_use]
+    pub fn is_available() -> bool {
+        window_nostr().is_ok()
+    }
+
+    /// Asks the extension for the user's public key.
+    ///
+    /// # Errors
+    /// Returns an error if no extension is installed or the user refuses.
+    pub async fn connect() -> Result<Self, String> {
+        let nostr = window_nostr()?;
+        let pubkey = call_method(&nostr, "getPublicKey", &[])
+            .await?
+            .as_string()
+            .ok_or("Signer extension returned no public key")?;
+        Ok(Self { pubkey })
+    }
+
+    /// # Errors
+    /// Returns an error if the extension refuses to sign or returns an invalid event.
+    pub async fn sign_note(
+        &self,
+        note: &mut nostr_minions::nostro2::NostrNote,
+    ) -> Result<(), String> {
+        let mut unsigned = serde_json::to_value(&*note).map_err(|e| e.to_string())?;
+        if let Some(fields) = unsigned.as_object_mut() {
+            fields.remove("id");
+            fields.remove("sig");
+            fields.insert("pubkey".to_string(), self.pubkey.clone().into());
+        }
+        let event = js_sys::JSON::parse(&unsigned.to_string()).map_err(js_error)?;
+        let signed = call_method(&window_nostr()?, "signEvent", &[event]).await?;
+        let json = js_sys::JSON::stringify(&signed)
+            .map_err(js_error)?
+            .as_string()
+            .unwrap_or_default();
+        let signed = parse_note(&json)?;
+        if signed.pubkey != self.pubkey {
+            return Err("Signer extension signed with a different key".to_string());
+        }
+        *note = signed;
+        Ok(())
+    }
+
+    async fn nip44(&self, method: &str, peer_pubkey: &str, text: &str) -> Result<String, String> {
+        let nip44 = js_sys::Reflect::get(&window_nostr()?, &"nip44".into())
+            .ok()
+            .filter(JsValue::is_object)
+            .ok_or("Signer extension does not support NIP-44")?;
+        call_method(&nip44, method, &[peer_pubkey.into(), text.into()])
+            .await?
+            .as_string()
+            .ok_or_else(|| format!("Signer extension returned no {method} result"))
+    }
+
+    /// # Errors
+    /// Returns an error if the extension does not support NIP-44 or refuses.
+    pub async fn nip44_encrypt(
+        &self,
+        peer_pubkey: &str,
+        plaintext: &str,
+    ) -> Result<String, String> {
+        self.nip44("encrypt", peer_pubkey, plaintext).await
+    }
+
+    /// # Errors
+    /// Returns an error if the extension does not support NIP-44 or cannot decrypt.
+    pub async fn nip44_decrypt(
+        &self,
+        peer_pubkey: &str,
+        ciphertext: &str,
+    ) -> Result<String, String> {
+        self.nip44("decrypt", peer_pubkey, ciphertext).await
+    }
+}
+
+/// Whatever signs on the user's behalf: a key kept in IndexedDB, a NIP-07
+/// extension or a NIP-46 remote signer.
+#[derive(Clone, PartialEq)]
+pub enum BunkerSigner {
+    Local(nostr_minions::nostro2_signer::keypair::NostrKeypair),
+    Extension(ExtensionSigner),
+    Remote(crate::nip46::RemoteSigner),
+}
+
+impl BunkerSigner {
+    #[must_use]
+    pub fn public_key(&self) -> String {
+        match self {
+            Self::Local(keypair) => keypair.public_key(),
+            Self::Extension(extension) => extension.pubkey.clone(),
+            Self::Remote(remote) => remote.session.user_pubkey.clone(),
+        }
+    }
+
+    /// Whether the private key lives outside this app.
+    #[must_use]
+    pub const fn is_external(&self) -> bool {
+        !matches!(self, Self::Local(_))
+    }
+
+    /// # Errors
+    /// Returns an error if the note cannot be signed.
+    pub async fn sign_note(
+        &self,
+        note: &mut nostr_minions::nostro2::NostrNote,
+    ) -> Result<(), String> {
+        match self {
+            Self::Local(keypair) => keypair
+                .sign_note(note)
+                .map_err(|_| "Failed to sign note".to_string()),
+            Self::Extension(extension) => extension.sign_note(note).await,
+            Self::Remote(remote) => remote.sign_note(note).await,
+        }
+    }
+
+    /// # Errors
+    /// Returns an error if `plaintext` cannot be encrypted for `peer_pubkey`.
+    pub async fn nip44_encrypt(
+        &self,
+        peer_pubkey: &str,
+        plaintext: &str,
+    ) -> Result<String, String> {
+        match self {
+            Self::Local(keypair) => keypair
+                .nip_44_encrypt(plaintext, peer_pubkey)
+                .map(|ciphertext| ciphertext.to_string())
+                .map_err(|_| "Failed to encrypt".to_string()),
+            Self::Extension(extension) => extension.nip44_encrypt(peer_pubkey, plaintext).await,
+            Self::Remote(remote) => remote.nip44_encrypt(peer_pubkey, plaintext).await,
+        }
+    }
+
+    /// # Errors
+    /// Returns an error if `ciphertext` was not encrypted for this user by `peer_pubkey`.
+    pub async fn nip44_decrypt(
+        &self,
+        peer_pubkey: &str,
+        ciphertext: &str,
+    ) -> Result<String, String> {
+        match self {
+            Self::Local(keypair) => keypair
+                .nip_44_decrypt(ciphertext, peer_pubkey)
+                .map(|plaintext| plaintext.to_string())
+                .map_err(|_| "Failed to decrypt".to_string()),
+            Self::Extension(extension) => extension.nip44_decrypt(peer_pubkey, ciphertext).await,
+            Self::Remote(remote) => remote.nip44_decrypt(peer_pubkey, ciphertext).await,
+        }
+    }
+
+    /// Gift-wraps `content` for `recipient` as a NIP-17 private message.
+    ///
+    /// # Errors
+    /// Returns an error if the message cannot be encrypted or signed.
+    pub async fn private_dm(
+        &self,
+        content: &str,
+        recipient: &str,
+    ) -> Result<nostr_minions::nostro2::NostrNote, String> {
+        match self {
+            Self::Local(keypair) => keypair
+                .private_dm(content, recipient)
+                .map_err(|_| "Failed to wrap private message".to_string()),
+            Self::Extension(_) | Self::Remote(_) => self.gift_wrap(content, recipient).await,
+        }
+    }
+
+    /// Opens a gift wrap addressed to the user.
+    ///
+    /// # Errors
+    /// Returns an error if the note is not a gift wrap for this user.
+    pub async fn extract_rumor(
+        &self,
+        wrap: &nostr_minions::nostro2::NostrNote,
+    ) -> Result<nostr_minions::nostro2::NostrNote, String> {
+        match self {
+            Self::Local(keypair) => keypair
+                .extract_rumor(wrap)
+                .map_err(|_| "Failed to open gift wrap".to_string()),
+            Self::Extension(_) | Self::Remote(_) => self.unwrap_gift(wrap).await,
+        }
+    }
+
+    /// Builds a NIP-17 gift wrap without access to the private key: the seal is
+    /// signed by this signer and the outer wrap by a throwaway key.
+    async fn gift_wrap(
+        &self,
+        content: &str,
+        recipient: &str,
+    ) -> Result<nostr_minions::nostro2::NostrNote, String> {
+        let pubkey = self.public_key();
+        let mut rumor = crate::with_tag(
+            nostr_minions::nostro2::NostrNote {
+                kind: PRIVATE_DM_KIND,
+                pubkey: pubkey.clone(),
+                content: content.to_string(),
+                ..Default::default()
+            },
+            &["p", recipient],
+        );
+        rumor.id = Some(note_id(&rumor)?);
+        let rumor_json = serde_json::to_string(&rumor).map_err(|e| e.to_string())?;
+        let mut seal = nostr_minions::nostro2::NostrNote {
+            kind: SEAL_KIND,
+            pubkey,
+            content: self.nip44_encrypt(recipient, &rumor_json).await?,
+            created_at: Self::tweaked_created_at()?,
+            ..Default::default()
+        };
+        self.sign_note(&mut seal).await?;
+        let seal_json = serde_json::to_string(&seal).map_err(|e| e.to_string())?;
+
+        let ephemeral = nostr_minions::nostro2_signer::keypair::NostrKeypair::generate(false);
+        let mut wrap = crate::with_tag(
+            nostr_minions::nostro2::NostrNote {
+                kind: GIFT_WRAP_KIND,
+                pubkey: ephemeral.public_key(),
+                content: ephemeral
+                    .nip_44_encrypt(&seal_json, recipient)
+                    .map_err(|_| "Failed to encrypt gift wrap".to_string())?
+                    .to_string(),
+                created_at: Self::tweaked_created_at()?,
+                ..Default::default()
+            },
+            &["p", recipient],
+        );
+        ephemeral
+            .sign_note(&mut wrap)
+            .map_err(|_| "Failed to sign gift wrap".to_string())?;
+        Ok(wrap)
+    }
+
+    /// A random time within the last two days, in seconds.
+    fn tweaked_created_at() -> Result<i64, String> {
+        let mut bytes = [0u8; 4];
+        getrandom::getrandom(&mut bytes).map_err(|e| e.to_string())?;
+        let now = (js_sys::Date::now() / 1000.0) as i64;
+        Ok(now - i64::from(u32::from_le_bytes(bytes) % WRAP_TIME_WINDOW_SECS))
+    }
+
+    async fn unwrap_gift(
+        &self,
+        wrap: &nostr_minions::nostro2::NostrNote,
+    ) -> Result<nostr_minions::nostro2::NostrNote, String> {
+        if wrap.kind != GIFT_WRAP_KIND {
+            return Err("Not a gift wrap".to_string());
+        }
+        let seal = parse_note(&self.nip44_decrypt(&wrap.pubkey, &wrap.content).await?)?;
+        let rumor = parse_note(&self.nip44_decrypt(&seal.pubkey, &seal.content).await?)?;
+        // A rumor is only as trustworthy as the seal that carries it.
+        if seal.kind != SEAL_KIND || !seal.verify() || rumor.pubkey != seal.pubkey {
+            return Err("Gift wrap seal does not match its rumor".to_string());
+        }
+        Ok(rumor)
+    }
+}
+
+impl IdbStoreManager for ExtensionSigner {
+    fn config() -> nostr_minions::browser_api::IdbStoreConfig {
+        crate::idb::store_config("extension_signer", "pubkey")
+    }
+    fn key(&self) -> web_sys::wasm_bindgen::JsValue {
+        web_sys::wasm_bindgen::JsValue::from_str(&self.pubkey)
+    }
+}
+
+impl From<ExtensionSigner> for web_sys::wasm_bindgen::JsValue {
+    fn from(value: ExtensionSigner) -> Self {
+        serde_wasm_bindgen::to_value(&value).unwrap_or_default()
+    }
+}
+
+impl TryFrom<web_sys::wasm_bindgen::JsValue> for ExtensionSigner {
+    type Error = web_sys::wasm_bindgen::JsValue;
+    fn try_from(value: web_sys::wasm_bindgen::JsValue) -> Result<Self, Self::Error> {
+        Ok(serde_wasm_bindgen::from_value(value)?)
+    }
+}
+
+#[derive(Clone, PartialEq, Default)]
+pub struct SignerState {
+    extension: Option<ExtensionSigner>,
+    remote: Option<crate::nip46::RemoteSignerSession>,
+    inbox: crate::nip46::Nip46Inbox,
+}
+
+impl SignerState {
+    #[must_use]
+    pub const fn extension(&self) -> Option<&ExtensionSigner> {
+        self.extension.as_ref()
+    }
+    #[must_use]
+    pub const fn remote(&self) -> Option<&crate::nip46::RemoteSignerSession> {
+        self.remote.as_ref()
+    }
+    /// Whether the user signed in through an extension or a remote signer.
+    #[must_use]
+    pub const fn is_connected(&self) -> bool {
+        self.extension.is_some() || self.remote.is_some()
+    }
+    #[must_use]
+    pub fn inbox(&self) -> crate::nip46::Nip46Inbox {
+        self.inbox.clone()
+    }
+}
+
+pub enum SignerAction {
+    ExtensionConnected(ExtensionSigner),
+    RemoteConnected(crate::nip46::RemoteSignerSession),
+    Disconnected,
+}
+
+impl Reducible for SignerState {
+    type Action = SignerAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let inbox = self.inbox.clone();
+        Rc::new(match action {
+            SignerAction::ExtensionConnected(extension) => Self {
+                extension: Some(extension),
+                remote: None,
+                inbox,
+            },
+            SignerAction::RemoteConnected(session) => Self {
+                extension: None,
+                remote: Some(session),
+                inbox,
+            },
+            SignerAction::Disconnected => Self {
+                extension: None,
+                remote: None,
+                inbox,
+            },
+        })
+    }
+}
+
+pub type SignerStore = UseReducerHandle<SignerState>;
+
+/// Connects the browser extension and remembers it for the next visit.
+pub fn connect_extension(ctx: &SignerStore) {
+    let dispatcher = ctx.dispatcher();
+    yew::platform::spawn_local(async move {
+        match ExtensionSigner::connect().await {
+            Ok(extension) => {
+                if extension.clone().save_to_store().await.is_err() {
+                    web_sys::console::error_1(&"Failed to save extension login".into());
+                }
+                dispatcher.dispatch(SignerAction::ExtensionConnected(extension));
+            }
+            Err(error) => {
+                crate::toast_failure(&error);
+            }
+        }
+    });
+}
+
+/// Keeps a remote signer session for the next visit and signs in with it.
+pub fn connect_remote(
+    ctx: &UseReducerDispatcher<SignerState>,
+    session: crate::nip46::RemoteSignerSession,
+) {
+    let ctx = ctx.clone();
+    yew::platform::spawn_local(async move {
+        if session.clone().save_to_store().await.is_err() {
+            web_sys::console::error_1(&"Failed to save remote signer session".into());
+        }
+        ctx.dispatch(SignerAction::RemoteConnected(session));
+    });
+}
+
+/// Signs out of the extension or remote signer; their keys are left untouched.
+pub fn disconnect_signer(ctx: &SignerStore) {
+    let extension = ctx.extension.clone();
+    let remote = ctx.remote.clone();
+    ctx.inbox.close();
+    ctx.dispatch(SignerAction::Disconnected);
+    yew::platform::spawn_local(async move {
+        if let Some(extension) = extension {
+            if extension.delete_from_store().await.is_err() {
+                web_sys::console::error_1(&"Failed to forget extension login".into());
+            }
+        }
+        if let Some(remote) = remote {
+            if remote.delete_from_store().await.is_err() {
+                web_sys::console::error_1(&"Failed to forget remote signer".into());
+            }
+        }
+    });
+}
+
+#[function_component(SignerProvider)]
+pub fn signer_provider(props: &yew::html::ChildrenProps) -> HtmlResult {
+    let stored = yew::suspense::use_future_with((), |_| async move {
+        let extension = ExtensionSigner::retrieve_all_from_store()
+            .await
+            .ok()
+            .and_then(|logins| logins.into_iter().next());
+        let remote = crate::nip46::RemoteSignerSession::retrieve_all_from_store()
+            .await
+            .ok()
+            .and_then(|sessions| sessions.into_iter().next());
+        (extension, remote)
+    })?;
+    let ctx = use_reducer(|| SignerState {
+        extension: stored.0.clone(),
+        remote: stored.1.clone(),
+        inbox: crate::nip46::Nip46Inbox::default(),
+    });
+
+    {
+        let inbox = ctx.inbox.clone();
+        use_effect_with(ctx.remote.clone(), move |remote| {
+            if let Some((session, client)) = remote
+                .as_ref()
+                .and_then(|session| Some((session, session.client_keypair()?)))
+            {
+                inbox.listen(&session.relays, &client);
+            }
+            || {}
+        });
+    }
+
+    Ok(html! {
+        <ContextProvider<SignerStore> context={ctx}>
+            {props.children.clone()}
+        </ContextProvider<SignerStore>>
+    })
+}
+
+#[hook]
+pub fn use_signer_ctx() -> SignerStore {
+    use_context::<SignerStore>().expect("SignerStore context not set")
+}
+
+/// The user's signer, preferring a key stored in IndexedDB over an extension or remote signer.
+#[hook]
+pub fn use_signer() -> Option<BunkerSigner> {
+    let keypair = nostr_minions::key_manager::use_nostr_key();
+    let ctx = use_signer_ctx();
+    keypair
+        .map(BunkerSigner::Local)
+        .or_else(|| ctx.extension.clone().map(BunkerSigner::Extension))
+        .or_else(|| {
+            let session = ctx.remote.clone()?;
+            crate::nip46::RemoteSigner::new(session, ctx.inbox()).map(BunkerSigner::Remote)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr_minions::nostro2_signer::keypair::NostrKeypair;
+    use wasm_bindgen_test::wasm_bindgen_test;
+    use web_sys::wasm_bindgen::closure::Closure;
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    fn set(target: &JsValue, name: &str, value: &JsValue) {
+        js_sys::Reflect::set(target, &name.into(), value).expect("stub property is set");
+    }
+
+    /// Installs a `window.nostr` stand-in that signs and encrypts with `keypair`.
+    fn install_extension(keypair: &NostrKeypair) {
+        let nostr = JsValue::from(js_sys::Object::new());
+        let pubkey = keypair.public_key();
+        let get_public_key = Closure::<dyn Fn() -> JsValue>::new(move || pubkey.clone().into());
+        let signer = keypair.clone();
+        let sign_event = Closure::<dyn Fn(JsValue) -> JsValue>::new(move |event: JsValue| {
+            let json = js_sys::JSON::stringify(&event)
+                .ok()
+                .and_then(|json| json.as_string())
+                .unwrap_or_default();
+            let mut note = parse_note(&json).expect("stub receives an event");
+            signer.sign_note(&mut note).expect("stub signs");
+            js_sys::JSON::parse(&serde_json::to_string(&note).unwrap_or_default())
+                .unwrap_or_default()
+        });
+        let signer = keypair.clone();
+        let encrypt = Closure::<dyn Fn(String, String) -> JsValue>::new(
+            move |peer: String, plaintext: String| {
+                signer
+                    .nip_44_encrypt(&plaintext, &peer)
+                    .map(|ciphertext| ciphertext.to_string())
+                    .unwrap_or_default()
+                    .into()
+            },
+        );
+        let signer = keypair.clone();
+        let decrypt = Closure::<dyn Fn(String, String) -> JsValue>::new(
+            move |peer: String, ciphertext: String| {
+                signer
+                    .nip_44_decrypt(&ciphertext, &peer)
+                    .map(|plaintext| plaintext.to_string())
+                    .unwrap_or_default()
+                    .into()
+            },
+        );
+        let nip44 = JsValue::from(js_sys::Object::new());
+        set(&nip44, "encrypt", encrypt.as_ref());
+        set(&nip44, "decrypt", decrypt.as_ref());
+        set(&nostr, "getPublicKey", get_public_key.as_ref());
+        set(&nostr, "signEvent", sign_event.as_ref());
+        set(&nostr, "nip44", &nip44);
+        set(
+            &web_sys::window().expect("tests run in a browser").into(),
+            "nostr",
+            &nostr,
+        );
+        get_public_key.forget();
+        sign_event.forget();
+        encrypt.forget();
+        decrypt.forget();
+    }
+
+    #[wasm_bindgen_test]
+    async fn extension_signs_notes_with_its_key() {
+        let keypair = NostrKeypair::generate(false);
+        install_extension(&keypair);
+        let extension = ExtensionSigner::connect().await.expect("stub connects");
+        assert_eq!(extension.pubkey, keypair.public_key());
+
+        let mut note = nostr_minions::nostro2::NostrNote {
+            kind: 1,
+            content: "1. e4 e5".to_string(),
+            ..Default::default()
+        };
+        extension.sign_note(&mut note).await.expect("stub signs");
+        assert_eq!(note.pubkey, keypair.public_key());
+        assert!(note.verify());
+    }
+
+    #[wasm_bindgen_test]
+    async fn extension_rejects_notes_signed_by_another_key() {
+        install_extension(&NostrKeypair::generate(false));
+        let extension = ExtensionSigner {
+            pubkey: NostrKeypair::generate(false).public_key(),
+        };
+        let mut note = nostr_minions::nostro2::NostrNote {
+            kind: 1,
+            ..Default::default()
+        };
+        assert!(extension.sign_note(&mut note).await.is_err());
+        assert!(note.sig.is_none());
+    }
+
+    #[wasm_bindgen_test]
+    async fn extension_round_trips_nip44() {
+        let keypair = NostrKeypair::generate(false);
+        let peer = NostrKeypair::generate(false);
+        install_extension(&keypair);
+        let extension = ExtensionSigner::connect().await.expect("stub connects");
+
+        let ciphertext = extension
+            .nip44_encrypt(&peer.public_key(), "Nf3 Nc6")
+            .await
+            .expect("stub encrypts");
+        assert_eq!(
+            peer.nip_44_decrypt(&ciphertext, &keypair.public_key())
+                .map(|plaintext| plaintext.to_string())
+                .ok()
+                .as_deref(),
+            Some("Nf3 Nc6")
+        );
+        let reply = peer
+            .nip_44_encrypt("Bb5", &keypair.public_key())
+            .expect("peer encrypts")
+            .to_string();
+        assert_eq!(
+            extension
+                .nip44_decrypt(&peer.public_key(), &reply)
+                .await
+                .as_deref(),
+            Ok("Bb5")
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn gift_wraps_through_the_extension_open_on_both_sides() {
+        let keypair = NostrKeypair::generate(false);
+        let peer = NostrKeypair::generate(false);
+        install_extension(&keypair);
+        let signer = BunkerSigner::Extension(ExtensionSigner::connect().await.expect("connects"));
+
+        let wrap = signer
+            .private_dm("1. d4", &peer.public_key())
+            .await
+            .expect("stub wraps");
+        assert_eq!(wrap.kind, GIFT_WRAP_KIND);
+        assert_ne!(wrap.pubkey, keypair.public_key());
+        let seal = parse_note(
+            &peer
+                .nip_44_decrypt(&wrap.content, &wrap.pubkey)
+                .expect("peer opens the wrap")
+                .to_string(),
+        )
+        .expect("wrap holds a seal");
+        let now = (js_sys::Date::now() / 1000.0) as i64;
+        let window = now - i64::from(WRAP_TIME_WINDOW_SECS)..=now;
+        assert!(window.contains(&wrap.created_at));
+        assert!(window.contains(&seal.created_at));
+        let rumor = BunkerSigner::Local(peer.clone())
+            .extract_rumor(&wrap)
+            .await
+            .expect("peer opens the wrap");
+        assert_eq!(rumor.content, "1. d4");
+        assert_eq!(rumor.pubkey, keypair.public_key());
+
+        let reply = peer
+            .private_dm("1... d5", &keypair.public_key())
+            .expect("peer wraps");
+        let rumor = signer.extract_rumor(&reply).await.expect("stub unwraps");
+        assert_eq!(rumor.content, "1... d5");
+        assert_eq!(rumor.pubkey, peer.public_key());
+    }
+
+    #[wasm_bindgen_test]
+    async fn unwrap_rejects_seals_from_another_author() {
+        let keypair = NostrKeypair::generate(false);
+        let forger = NostrKeypair::generate(false);
+        install_extension(&keypair);
+        let signer = BunkerSigner::Extension(ExtensionSigner::connect().await.expect("connects"));
+
+        // A rumor claiming a different author than the key that sealed it.
+        let rumor = nostr_minions::nostro2::NostrNote {
+            kind: PRIVATE_DM_KIND,
+            pubkey: NostrKeypair::generate(false).public_key(),
+            content: "resign".to_string(),
+            ..Default::default()
+        };
+        let mut seal = nostr_minions::nostro2::NostrNote {
+            kind: SEAL_KIND,
+            content: forger
+                .nip_44_encrypt(
+                    &serde_json::to_string(&rumor).unwrap_or_default(),
+                    &keypair.public_key(),
+                )
+                .expect("forger encrypts")
+                .to_string(),
+            ..Default::default()
+        };
+        forger.sign_note(&mut seal).expect("forger signs");
+        let ephemeral = NostrKeypair::generate(false);
+        let mut wrap = nostr_minions::nostro2::NostrNote {
+            kind: GIFT_WRAP_KIND,
+            content: ephemeral
+                .nip_44_encrypt(
+                    &serde_json::to_string(&seal).unwrap_or_default(),
+                    &keypair.public_key(),
+                )
+                .expect("ephemeral encrypts")
+                .to_string(),
+            ..Default::default()
+        };
+        ephemeral.sign_note(&mut wrap).expect("ephemeral signs");
+        assert!(signer.extract_rumor(&wrap).await.is_err());
+    }
+
+    #[wasm_bindgen_test]
+    async fn unwrap_rejects_unsigned_seals() {
+        let keypair = NostrKeypair::generate(false);
+        let author = NostrKeypair::generate(false);
+        install_extension(&keypair);
+        let signer = BunkerSigner::Extension(ExtensionSigner::connect().await.expect("connects"));
+
+        let rumor = nostr_minions::nostro2::NostrNote {
+            kind: PRIVATE_DM_KIND,
+            pubkey: author.public_key(),
+            content: "1. e4".to_string(),
+            ..Default::default()
+        };
+        let seal = nostr_minions::nostro2::NostrNote {
+            kind: SEAL_KIND,
+            pubkey: author.public_key(),
+            content: author
+                .nip_44_encrypt(
+                    &serde_json::to_string(&rumor).unwrap_or_default(),
+                    &keypair.public_key(),
+                )
+                .expect("author encrypts")
+                .to_string(),
+            ..Default::default()
+        };
+        let ephemeral = NostrKeypair::generate(false);
+        let mut wrap = nostr_minions::nostro2::NostrNote {
+            kind: GIFT_WRAP_KIND,
+            content: ephemeral
+                .nip_44_encrypt(
+                    &serde_json::to_string(&seal).unwrap_or_default(),
+                    &keypair.public_key(),
+                )
+                .expect("ephemeral encrypts")
+                .to_string(),
+            ..Default::default()
+        };
+        ephemeral.sign_note(&mut wrap).expect("ephemeral signs");
+        assert!(signer.extract_rumor(&wrap).await.is_err());
+    }
+}
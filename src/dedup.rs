@@ -0,0 +1,192 @@
+//! Content-based duplicate detection for games, independent of note ids and signatures.
+
+use std::collections::{HashMap, HashSet};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+fn normalize(value: &str) -> String {
+    value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// A stable hash of a game's normalized headers and move list.
+///
+/// Two games fetched or signed separately share a fingerprint when they describe the same game.
+#[must_use]
+pub fn game_fingerprint(game: &rooky_core::RookyGame) -> String {
+    let moves = game
+        .moves
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ");
+    let canonical = [
+        normalize(&game.white),
+        normalize(&game.black),
+        game.date.format("%Y-%m-%d").to_string(),
+        normalize(&game.event.to_string()),
+        normalize(&game.site.to_string()),
+        normalize(&game.round.to_string()),
+        game.outcome.to_string(),
+        moves,
+    ]
+    .join("\n");
+    format!("{:016x}", fnv1a(canonical.as_bytes()))
+}
+
+#[must_use]
+pub fn note_fingerprint(note: &nostr_minions::nostro2::NostrNote) -> Option<String> {
+    rooky_core::RookyGame::try_from(note.clone())
+        .ok()
+        .map(|game| game_fingerprint(&game))
+}
+
+/// Fingerprints of the stored games, skipping entries whose PGN no longer parses.
+#[must_use]
+pub fn known_fingerprints(entries: &[rooky_core::idb::RookyGameEntry]) -> HashSet<String> {
+    entries
+        .iter()
+        .filter_map(|entry| note_fingerprint(&entry.note))
+        .collect()
+}
+
+/// Groups entries sharing a fingerprint, keeping only groups with more than one entry.
+///
+/// Within a group the entry to keep comes first: annotated games, then received, then public.
+/// Entries whose PGN does not parse are never grouped.
+#[must_use]
+pub fn duplicate_groups(
+    entries: &[rooky_core::idb::RookyGameEntry],
+) -> Vec<Vec<rooky_core::idb::RookyGameEntry>> {
+    let mut groups: HashMap<String, Vec<rooky_core::idb::RookyGameEntry>> = HashMap::new();
+    for entry in entries {
+        let Some(fingerprint) = note_fingerprint(&entry.note) else {
+            continue;
+        };
+        groups.entry(fingerprint).or_default().push(entry.clone());
+    }
+    let mut duplicates = groups
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .map(|mut group| {
+            group.sort_by_key(|entry| (origin_rank(&entry.origin), entry.note.created_at));
+            group
+        })
+        .collect::<Vec<_>>();
+    duplicates.sort_by(|a, b| a[0].id.cmp(&b[0].id));
+    duplicates
+}
+
+const fn origin_rank(origin: &rooky_core::idb::GameOrigin) -> u8 {
+    match origin {
+        rooky_core::idb::GameOrigin::Annotated => 0,
+        rooky_core::idb::GameOrigin::Received => 1,
+        rooky_core::idb::GameOrigin::Public => 2,
+        rooky_core::idb::GameOrigin::Unknown => 3,
+    }
+}
+
+/// The move text of a PGN with whitespace collapsed, comments, variations and NAGs included.
+fn movetext(pgn: &str) -> String {
+    pgn.split_once("\n\n")
+        .map_or("", |(_, movetext)| movetext)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether removing `copy` would lose comments, variations or NAGs that `kept` does not carry.
+///
+/// Copies share a fingerprint whatever their annotations, so this is checked before one is
+/// discarded.
+#[must_use]
+pub fn loses_annotations(
+    kept: &rooky_core::idb::RookyGameEntry,
+    copy: &rooky_core::idb::RookyGameEntry,
+) -> bool {
+    let copy_moves = movetext(&copy.note.content);
+    copy_moves.contains(['{', '(', '$', ';']) && copy_moves != movetext(&kept.note.content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAME: &str = "[Event \"Casual\"]\n[Date \"2024.05.01\"]\n[White \"Carlsen\"]\n[Black \"Nepo\"]\n[Result \"1-0\"]\n\n1. e4 e5 2. Nf3 1-0\n";
+
+    fn entry(
+        id: &str,
+        pgn: &str,
+        origin: rooky_core::idb::GameOrigin,
+    ) -> rooky_core::idb::RookyGameEntry {
+        rooky_core::idb::RookyGameEntry {
+            id: id.to_string(),
+            note: nostr_minions::nostro2::NostrNote {
+                id: Some(id.to_string()),
+                content: pgn.to_string(),
+                kind: crate::live_game::rooky_game_kind(),
+                ..Default::default()
+            },
+            origin,
+        }
+    }
+
+    #[test]
+    fn groups_copies_keeping_the_annotated_one_first() {
+        let spaced = GAME.replace("Carlsen", "  carlsen ");
+        let entries = [
+            entry("public", GAME, rooky_core::idb::GameOrigin::Public),
+            entry("annotated", &spaced, rooky_core::idb::GameOrigin::Annotated),
+            entry(
+                "other",
+                &GAME.replace("Nf3", "Nc3"),
+                rooky_core::idb::GameOrigin::Annotated,
+            ),
+        ];
+        let groups = duplicate_groups(&entries);
+        assert_eq!(groups.len(), 1);
+        let ids = groups[0]
+            .iter()
+            .map(|entry| entry.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["annotated", "public"]);
+    }
+
+    #[test]
+    fn skips_entries_that_do_not_parse() {
+        let broken = "[White \"Carlsen\"]\n\n1. e4 e4 e4 *\n";
+        let entries = [
+            entry("a", GAME, rooky_core::idb::GameOrigin::Annotated),
+            entry("b", broken, rooky_core::idb::GameOrigin::Public),
+            entry("c", broken, rooky_core::idb::GameOrigin::Public),
+        ];
+        assert!(duplicate_groups(&entries).is_empty());
+        assert_eq!(
+            known_fingerprints(&entries),
+            HashSet::from([note_fingerprint(&entries[0].note).unwrap()])
+        );
+    }
+
+    #[test]
+    fn annotations_missing_from_the_kept_copy_are_reported() {
+        let kept = entry("a", GAME, rooky_core::idb::GameOrigin::Annotated);
+        let commented = entry(
+            "b",
+            &GAME.replace("2. Nf3", "{ Solid } 2. Nf3"),
+            rooky_core::idb::GameOrigin::Public,
+        );
+        assert!(loses_annotations(&kept, &commented));
+        assert!(!loses_annotations(&commented, &kept));
+        assert!(!loses_annotations(&commented, &commented.clone()));
+    }
+}
@@ -0,0 +1,90 @@
+use web_sys::wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+pub const BUNKER_DB_NAME: &str = "bunker";
+pub const BUNKER_DB_VERSION: u32 = 12;
+
+/// Object stores owned by the bunker database, as `(store_name, key_path)`.
+const BUNKER_STORES: &[(&str, &str)] = &[
+    ("outbox", "id"),
+    ("game_backups", "id"),
+    ("game_revisions", "id"),
+    ("lichess_accounts", "username"),
+    ("import_jobs", "id"),
+    ("game_labels", "game_id"),
+    ("game_collections", "name"),
+    ("extension_signer", "pubkey"),
+    ("remote_signer", "user_pubkey"),
+    ("encrypted_keys", "pubkey"),
+    ("accounts", "pubkey"),
+    ("game_owners", "game_id"),
+    ("profiles", "pubkey"),
+    ("account_keys", "pubkey"),
+];
+
+#[must_use]
+pub const fn store_config(
+    store_name: &'static str,
+    document_key: &'static str,
+) -> nostr_minions::browser_api::IdbStoreConfig {
+    nostr_minions::browser_api::IdbStoreConfig {
+        db_name: BUNKER_DB_NAME,
+        db_version: BUNKER_DB_VERSION,
+        store_name,
+        document_key,
+    }
+}
+
+/// Opens the bunker database, creating any missing object stores.
+///
+/// # Errors
+/// Returns the underlying `JsValue` if IndexedDB is unavailable or the open request fails.
+pub async fn init_bunker_db() -> Result<(), JsValue> {
+    let factory = web_sys::window()
+        .ok_or_else(|| JsValue::from_str("No window found"))?
+        .indexed_db()?
+        .ok_or_else(|| JsValue::from_str("IndexedDB not supported"))?;
+    let request = factory.open_with_u32(BUNKER_DB_NAME, BUNKER_DB_VERSION)?;
+
+    let upgrade_request = request.clone();
+    let on_upgrade = Closure::<dyn FnMut(web_sys::IdbVersionChangeEvent)>::new(
+        move |_: web_sys::IdbVersionChangeEvent| {
+            let Ok(db) = upgrade_request
+                .result()
+                .map(JsCast::unchecked_into::<web_sys::IdbDatabase>)
+            else {
+                web_sys::console::error_1(&"Failed to upgrade bunker database".into());
+                return;
+            };
+            let existing = db.object_store_names();
+            for (store_name, key_path) in BUNKER_STORES {
+                if existing.contains(store_name) {
+                    continue;
+                }
+                let params = web_sys::IdbObjectStoreParameters::new();
+                params.set_key_path(&JsValue::from_str(key_path));
+                if db
+                    .create_object_store_with_optional_parameters(store_name, &params)
+                    .is_err()
+                {
+                    web_sys::console::error_1(
+                        &format!("Failed to create store {store_name}").into(),
+                    );
+                }
+            }
+        },
+    );
+    request.set_onupgradeneeded(Some(on_upgrade.as_ref().unchecked_ref()));
+
+    let opened = web_sys::js_sys::Promise::new(&mut |resolve, reject| {
+        request.set_onsuccess(Some(&resolve));
+        request.set_onerror(Some(&reject));
+    });
+    wasm_bindgen_futures::JsFuture::from(opened).await?;
+    drop(on_upgrade);
+
+    request
+        .result()?
+        .unchecked_into::<web_sys::IdbDatabase>()
+        .close();
+    Ok(())
+}
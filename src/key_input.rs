@@ -0,0 +1,344 @@
+//! Parsing of user-typed recovery phrases and secret keys, with errors that can be shown inline.
+
+/// BIP-39 phrase lengths accepted when recovering a key.
+pub const MNEMONIC_WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+
+/// Phrase length this app writes, whose words encode the secret key itself.
+const BACKUP_WORD_COUNT: usize = 24;
+
+/// How a key was read from a recovery phrase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MnemonicDerivation {
+    /// This app's own backup, whose words are the secret key itself.
+    Backup,
+    /// Derived from the phrase's seed as NIP-06 describes, as other clients do.
+    Nip06,
+}
+
+const HARDENED: u32 = 0x8000_0000;
+/// NIP-06 derivation path, `m/44'/1237'/0'/0/0`.
+const NIP06_PATH: [u32; 5] = [44 | HARDENED, 1237 | HARDENED, HARDENED, 0, 0];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeyInputError {
+    WordCount(usize),
+    UnknownWord(usize, String),
+    Checksum,
+    InvalidNsec,
+    HexLength(usize),
+    NotAKey,
+}
+
+impl KeyInputError {
+    #[must_use]
+    pub fn message(&self, language_ctx: &crate::contexts::language::LanguageConfigs) -> String {
+        match self {
+            Self::WordCount(count) => language_ctx
+                .t("key_input_word_count")
+                .replace("{0}", &count.to_string()),
+            Self::UnknownWord(position, word) => language_ctx
+                .t("key_input_unknown_word")
+                .replace("{0}", &position.to_string())
+                .replace("{1}", word),
+            Self::Checksum => language_ctx.t("key_input_checksum"),
+            Self::InvalidNsec => language_ctx.t("key_input_invalid_nsec"),
+            Self::HexLength(length) => language_ctx
+                .t("key_input_hex_length")
+                .replace("{0}", &length.to_string()),
+            Self::NotAKey => language_ctx.t("key_input_not_a_key"),
+        }
+    }
+}
+
+/// Splits pasted text into lowercase words, dropping list numbering such as `1.` or `12)`.
+#[must_use]
+pub fn split_phrase(text: &str) -> Vec<String> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .map(|word| {
+            word.trim_start_matches(|c: char| c.is_ascii_digit())
+                .trim_start_matches(['.', ')'])
+                .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// The smallest accepted phrase length that fits `words` words.
+#[must_use]
+pub fn word_count_for(words: usize) -> usize {
+    MNEMONIC_WORD_COUNTS
+        .into_iter()
+        .find(|count| *count >= words)
+        .unwrap_or(MNEMONIC_WORD_COUNTS[MNEMONIC_WORD_COUNTS.len() - 1])
+}
+
+#[must_use]
+pub fn is_mnemonic_word(word: &str) -> bool {
+    bip39::Language::English.find_word(word).is_some()
+}
+
+/// Word list entries starting with `prefix`, for autocompleting a phrase word.
+#[must_use]
+pub fn mnemonic_suggestions(prefix: &str, limit: usize) -> Vec<&'static str> {
+    let prefix = prefix.trim().to_lowercase();
+    if prefix.is_empty() || is_mnemonic_word(&prefix) {
+        return vec![];
+    }
+    bip39::Language::English
+        .words_by_prefix(&prefix)
+        .iter()
+        .take(limit)
+        .copied()
+        .collect()
+}
+
+fn hmac_sha512(key: &[u8], data: &[u8]) -> Option<([u8; 32], [u8; 32])> {
+    use hmac::Mac;
+    let mut mac = hmac::Hmac::<sha2::Sha512>::new_from_slice(key).ok()?;
+    mac.update(data);
+    let output = mac.finalize().into_bytes();
+    Some((output[..32].try_into().ok()?, output[32..].try_into().ok()?))
+}
+
+/// The secret key NIP-06 derives from the phrase's BIP-39 seed through BIP-32.
+fn nip06_secret(mnemonic: &bip39::Mnemonic) -> Option<[u8; 32]> {
+    let secp = secp256k1::Secp256k1::signing_only();
+    let (key, mut chain_code) = hmac_sha512(b"Bitcoin seed", &mnemonic.to_seed(""))?;
+    let mut key = secp256k1::SecretKey::from_byte_array(&key).ok()?;
+    for index in NIP06_PATH {
+        let mut data = Vec::with_capacity(37);
+        if index & HARDENED == 0 {
+            data.extend_from_slice(&secp256k1::PublicKey::from_secret_key(&secp, &key).serialize());
+        } else {
+            data.push(0);
+            data.extend_from_slice(&key.secret_bytes());
+        }
+        data.extend_from_slice(&index.to_be_bytes());
+        let (tweak, child_chain_code) = hmac_sha512(&chain_code, &data)?;
+        key = secp256k1::SecretKey::from_byte_array(&tweak)
+            .ok()?
+            .add_tweak(&key.into())
+            .ok()?;
+        chain_code = child_chain_code;
+    }
+    Some(key.secret_bytes())
+}
+
+fn keypair_from_secret(
+    secret: &[u8],
+) -> Result<nostr_minions::nostro2_signer::keypair::NostrKeypair, KeyInputError> {
+    let mut keypair = crate::nip49::secret_to_nsec(secret)
+        .map_err(|_| KeyInputError::NotAKey)?
+        .parse::<nostr_minions::nostro2_signer::keypair::NostrKeypair>()
+        .map_err(|_| KeyInputError::NotAKey)?;
+    keypair.set_extractable(true);
+    Ok(keypair)
+}
+
+/// Checks the phrase against the English word list and its checksum, then derives the keys
+/// it can stand for.
+///
+/// 24 words may be this app's own backup, which encodes the key directly, or a NIP-06 phrase
+/// from another client, so both keys are returned with the backup first for the user to
+/// choose from; shorter phrases only come from other clients.
+///
+/// # Errors
+/// Returns the first problem found, positions counting from one.
+pub fn parse_mnemonic(
+    words: &[String],
+) -> Result<
+    Vec<(
+        MnemonicDerivation,
+        nostr_minions::nostro2_signer::keypair::NostrKeypair,
+    )>,
+    KeyInputError,
+> {
+    let words = words
+        .iter()
+        .map(|word| word.trim().to_lowercase())
+        .collect::<Vec<_>>();
+    if !MNEMONIC_WORD_COUNTS.contains(&words.len()) {
+        return Err(KeyInputError::WordCount(words.len()));
+    }
+    if let Some((index, word)) = words
+        .iter()
+        .enumerate()
+        .find(|(_, word)| !is_mnemonic_word(word))
+    {
+        return Err(KeyInputError::UnknownWord(index + 1, word.clone()));
+    }
+    let phrase = words.join(" ");
+    let mnemonic = bip39::Mnemonic::parse_in_normalized(bip39::Language::English, &phrase)
+        .map_err(|error| match error {
+            bip39::Error::BadWordCount(count) => KeyInputError::WordCount(count),
+            bip39::Error::UnknownWord(index) => {
+                KeyInputError::UnknownWord(index + 1, words[index].clone())
+            }
+            _ => KeyInputError::Checksum,
+        })?;
+    let mut keys = vec![];
+    if words.len() == BACKUP_WORD_COUNT {
+        let backup = nostr_minions::nostro2_signer::keypair::NostrKeypair::parse_mnemonic(
+            &phrase,
+            nostr_minions::nostro2_signer::Language::English,
+            true,
+        )
+        .map_err(|_| KeyInputError::Checksum)?;
+        keys.push((MnemonicDerivation::Backup, backup));
+    }
+    let nip06 = keypair_from_secret(&nip06_secret(&mnemonic).ok_or(KeyInputError::NotAKey)?)?;
+    keys.push((MnemonicDerivation::Nip06, nip06));
+    Ok(keys)
+}
+
+/// Accepts an `nsec` or a 64 character hex secret key.
+///
+/// # Errors
+/// Returns why the input is not a usable secret key.
+pub fn parse_secret_key(
+    input: &str,
+) -> Result<nostr_minions::nostro2_signer::keypair::NostrKeypair, KeyInputError> {
+    let input = input.trim();
+    let secret = if input.to_lowercase().starts_with("nsec1") {
+        let (hrp, data) = bech32::decode(input).map_err(|_| KeyInputError::InvalidNsec)?;
+        if hrp.as_str() != "nsec" || data.len() != 32 {
+            return Err(KeyInputError::InvalidNsec);
+        }
+        data
+    } else if input.chars().all(|c| c.is_ascii_hexdigit()) && !input.is_empty() {
+        if input.len() != 64 {
+            return Err(KeyInputError::HexLength(input.len()));
+        }
+        (0..64)
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&input[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| KeyInputError::NotAKey)?
+    } else {
+        return Err(KeyInputError::NotAKey);
+    };
+    keypair_from_secret(&secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The first test vector from NIP-06.
+    const NIP06_PHRASE: &str =
+        "leader monkey parrot ring guide accident before fence cannon height naive bean";
+    const NIP06_SECRET: &str = "7f7ff03d123792d6ac594bfa67bf6d0c0ab55b6b1fdb6249303fe861f1ccba9a";
+    const NIP06_PUBKEY: &str = "17162c921dc4d2518f9a101db33695df1afb56ab82f5ff3e5da6eec3ca5cd917";
+    /// The second test vector from NIP-06, a 24 word phrase.
+    const NIP06_PHRASE_24: &str = "what bleak badge arrange retreat wolf trade produce cricket blur garlic valid proud rude strong choose busy staff weather area salt hollow arm fade";
+    const NIP06_24_PUBKEY: &str =
+        "d41b22899549e1f3d335a31002cfd382174006e166d3e658e3a5eecdb6463573";
+    const NIP06_24_BACKUP_PUBKEY: &str =
+        "c34167c5a18ff38cf56a266e85b98ac9c0424c22e8c95f7230815434454255fd";
+
+    fn words(phrase: &str) -> Vec<String> {
+        phrase.split_whitespace().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn splits_numbered_and_comma_separated_phrases() {
+        assert_eq!(
+            split_phrase("1. Leader 2) monkey,parrot\n12.bean"),
+            vec!["leader", "monkey", "parrot", "bean"]
+        );
+        assert!(split_phrase(" \n, ").is_empty());
+    }
+
+    fn public_keys(phrase: &str) -> Vec<(MnemonicDerivation, String)> {
+        parse_mnemonic(&words(phrase))
+            .expect("phrase parses")
+            .into_iter()
+            .map(|(derivation, keypair)| (derivation, keypair.public_key()))
+            .collect()
+    }
+
+    #[test]
+    fn derives_short_phrases_as_nip06() {
+        assert_eq!(
+            public_keys(NIP06_PHRASE),
+            vec![(MnemonicDerivation::Nip06, NIP06_PUBKEY.to_string())]
+        );
+    }
+
+    #[test]
+    fn offers_both_keys_for_24_words() {
+        let keypair = parse_secret_key(NIP06_SECRET).expect("hex key parses");
+        let phrase = keypair
+            .mnemonic(nostr_minions::nostro2_signer::Language::English)
+            .expect("key is extractable");
+        let keys = public_keys(&phrase);
+        assert_eq!(
+            keys[0],
+            (MnemonicDerivation::Backup, NIP06_PUBKEY.to_string())
+        );
+        assert_eq!(keys[1].0, MnemonicDerivation::Nip06);
+        assert_ne!(keys[1].1, NIP06_PUBKEY);
+    }
+
+    #[test]
+    fn derives_24_word_phrases_from_other_clients_as_nip06() {
+        assert_eq!(
+            public_keys(NIP06_PHRASE_24),
+            vec![
+                (
+                    MnemonicDerivation::Backup,
+                    NIP06_24_BACKUP_PUBKEY.to_string()
+                ),
+                (MnemonicDerivation::Nip06, NIP06_24_PUBKEY.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_phrase_errors() {
+        let mut phrase = words(NIP06_PHRASE);
+        assert_eq!(
+            parse_mnemonic(&phrase[..11]).err(),
+            Some(KeyInputError::WordCount(11))
+        );
+        phrase[3] = "rnig".to_string();
+        assert_eq!(
+            parse_mnemonic(&phrase).err(),
+            Some(KeyInputError::UnknownWord(4, "rnig".to_string()))
+        );
+        phrase[3] = "ring".to_string();
+        phrase.swap(0, 1);
+        assert_eq!(parse_mnemonic(&phrase).err(), Some(KeyInputError::Checksum));
+    }
+
+    #[test]
+    fn parses_nsec_and_hex_keys() {
+        let nsec = crate::nip49::secret_to_nsec(
+            parse_secret_key(NIP06_SECRET)
+                .expect("hex key parses")
+                .secret_key()
+                .as_slice(),
+        )
+        .expect("nsec encodes");
+        assert_eq!(
+            parse_secret_key(&format!("  {nsec}\n")).map(|keypair| keypair.public_key()),
+            Ok(NIP06_PUBKEY.to_string())
+        );
+        assert_eq!(
+            parse_secret_key(&NIP06_SECRET.to_uppercase()).map(|keypair| keypair.public_key()),
+            Ok(NIP06_PUBKEY.to_string())
+        );
+        assert_eq!(
+            parse_secret_key(&NIP06_SECRET[..63]).err(),
+            Some(KeyInputError::HexLength(63))
+        );
+        assert_eq!(
+            parse_secret_key(&format!("{}x", &nsec[..nsec.len() - 1])).err(),
+            Some(KeyInputError::InvalidNsec)
+        );
+        assert_eq!(
+            parse_secret_key("npub1abc").err(),
+            Some(KeyInputError::NotAKey)
+        );
+    }
+}
@@ -1,10 +1,37 @@
+mod chesscom_archives;
 mod components;
 mod contexts;
+mod dedup;
+pub mod idb;
+mod key_input;
+mod lichess_export;
 mod login;
+mod nip46;
+mod nip49;
+mod note_tags;
 pub mod pages;
+mod pgn_import;
+mod relay_diagnostics;
+mod relay_list;
 mod router;
+mod search_index;
+mod stats;
+mod toast;
 
+pub use chesscom_archives::*;
 pub use components::*;
 pub use contexts::*;
+pub use dedup::*;
+pub use key_input::*;
+pub use lichess_export::*;
 pub use login::*;
+pub use nip46::*;
+pub use nip49::*;
+pub use note_tags::*;
+pub use pgn_import::*;
+pub use relay_diagnostics::*;
+pub use relay_list::*;
 pub use router::*;
+pub use search_index::*;
+pub use stats::*;
+pub use toast::*;
@@ -0,0 +1,231 @@
+//! PGN exports of Lichess studies and broadcasts.
+
+const LICHESS_BASE_URL: &str = "https://lichess.org";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LichessExport {
+    Study {
+        id: String,
+    },
+    StudyChapter {
+        id: String,
+        chapter: String,
+    },
+    /// Every round of a broadcast tournament.
+    Broadcast {
+        id: String,
+    },
+    BroadcastRound {
+        id: String,
+    },
+}
+
+impl LichessExport {
+    #[must_use]
+    pub const fn import_source(&self) -> crate::imports::ImportSource {
+        match self {
+            Self::Study { .. } | Self::StudyChapter { .. } => {
+                crate::imports::ImportSource::LichessStudy
+            }
+            Self::Broadcast { .. } | Self::BroadcastRound { .. } => {
+                crate::imports::ImportSource::LichessBroadcast
+            }
+        }
+    }
+
+    #[must_use]
+    pub const fn is_broadcast(&self) -> bool {
+        matches!(self, Self::Broadcast { .. } | Self::BroadcastRound { .. })
+    }
+}
+
+/// Builds export URLs against a Lichess server, lichess.org unless told otherwise.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LichessExporter {
+    pub base_url: String,
+}
+
+impl Default for LichessExporter {
+    fn default() -> Self {
+        Self {
+            base_url: LICHESS_BASE_URL.to_string(),
+        }
+    }
+}
+
+impl LichessExporter {
+    #[must_use]
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Reads a lichess.org study or broadcast page URL.
+    ///
+    /// Returns `None` for URLs that are not study or broadcast pages on lichess.org.
+    #[must_use]
+    pub fn from_page_url(url: &str) -> Option<(Self, LichessExport)> {
+        let (scheme, rest) = url.trim().split_once("://")?;
+        let (host, path) = rest.split_once('/')?;
+        let host = host.to_lowercase();
+        if !matches!(scheme.to_lowercase().as_str(), "https" | "http")
+            || (host != "lichess.org" && host != "www.lichess.org")
+        {
+            return None;
+        }
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        let segments = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+        let export = match segments.as_slice() {
+            ["study", id] => LichessExport::Study {
+                id: (*id).to_string(),
+            },
+            ["study", id, chapter, ..] => LichessExport::StudyChapter {
+                id: (*id).to_string(),
+                chapter: (*chapter).to_string(),
+            },
+            ["broadcast", _, id] => LichessExport::Broadcast {
+                id: (*id).to_string(),
+            },
+            ["broadcast", _, _, round, ..] => LichessExport::BroadcastRound {
+                id: (*round).to_string(),
+            },
+            _ => return None,
+        };
+        Some((Self::default(), export))
+    }
+
+    #[must_use]
+    pub fn export_url(&self, export: &LichessExport) -> String {
+        let base = &self.base_url;
+        match export {
+            LichessExport::Study { id } => {
+                format!("{base}/api/study/{id}.pgn?comments=true&variations=true")
+            }
+            LichessExport::StudyChapter { id, chapter } => {
+                format!("{base}/api/study/{id}/{chapter}.pgn?comments=true&variations=true")
+            }
+            LichessExport::Broadcast { id } => format!("{base}/api/broadcast/{id}.pgn"),
+            LichessExport::BroadcastRound { id } => {
+                format!("{base}/api/broadcast/round/{id}.pgn")
+            }
+        }
+    }
+
+    /// # Errors
+    /// Returns the request error if the export cannot be fetched.
+    pub async fn fetch(&self, export: &LichessExport) -> Result<String, gloo::net::Error> {
+        crate::fetch_pgn(&self.export_url(export)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    const BROADCAST_PGN: &str = "[Event \"?\"]\n[BroadcastName \"Tata Steel 2024\"]\n[Round \"5.3\"]\n[Date \"2024.01.17\"]\n[White \"Giri, Anish\"]\n[Black \"Firouzja, Alireza\"]\n[Result \"1/2-1/2\"]\n\n1. d4 { Solid } Nf6 (1... d5 2. c4) 2. c4 1/2-1/2\n\n[Event \"?\"]\n[BroadcastName \"Tata Steel 2024\"]\n[Round \"5.4\"]\n[Date \"2024.01.17\"]\n[White \"Gukesh D\"]\n[Black \"Wei, Yi\"]\n[Result \"1-0\"]\n\n1. e4 e5 1-0\n";
+
+    /// Replaces `window.fetch` with a stand-in serving `body` and recording the requested URL.
+    fn serve(body: &str) {
+        web_sys::js_sys::Function::new_with_args(
+            "body",
+            "window.fetch = (request) => { \
+                window.lastFetchUrl = typeof request === 'string' ? request : request.url; \
+                return Promise.resolve(new Response(body, { status: 200 })); \
+            };",
+        )
+        .call1(&web_sys::wasm_bindgen::JsValue::NULL, &body.into())
+        .expect("fetch stand-in installs");
+    }
+
+    fn last_fetch_url() -> Option<String> {
+        web_sys::js_sys::Reflect::get(&web_sys::window()?.into(), &"lastFetchUrl".into())
+            .ok()?
+            .as_string()
+    }
+
+    #[test]
+    fn reads_lichess_page_urls() {
+        assert_eq!(
+            LichessExporter::from_page_url("https://lichess.org/study/abc123/ch4pter#3"),
+            Some((
+                LichessExporter::default(),
+                LichessExport::StudyChapter {
+                    id: "abc123".to_string(),
+                    chapter: "ch4pter".to_string(),
+                }
+            ))
+        );
+        assert_eq!(
+            LichessExporter::from_page_url(
+                "https://lichess.org/broadcast/tata-steel/round-5/r0und5"
+            ),
+            Some((
+                LichessExporter::default(),
+                LichessExport::BroadcastRound {
+                    id: "r0und5".to_string(),
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn rejects_other_hosts() {
+        assert_eq!(
+            LichessExporter::from_page_url("https://evil.example/study/abc123"),
+            None
+        );
+        assert_eq!(
+            LichessExporter::from_page_url("https://lichess.org.evil.example/study/abc123"),
+            None
+        );
+        assert_eq!(
+            LichessExporter::from_page_url("ftp://lichess.org/study/abc123"),
+            None
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn fetches_broadcast_round_from_stand_in() {
+        serve(BROADCAST_PGN);
+        let exporter = LichessExporter::new("http://localhost:8080/");
+        let export = LichessExport::BroadcastRound {
+            id: "r0und5".to_string(),
+        };
+        let text = exporter.fetch(&export).await.expect("stand-in answers");
+        assert_eq!(
+            last_fetch_url().as_deref(),
+            Some("http://localhost:8080/api/broadcast/round/r0und5.pgn")
+        );
+
+        let games = crate::split_pgn(&text, export.is_broadcast());
+        assert_eq!(games.len(), 2);
+        assert!(games[0].contains("[Event \"Tata Steel 2024\"]"));
+        assert!(games[0].contains("[Round \"5\"]"));
+        assert!(games[0].contains("[Board \"3\"]"));
+        assert!(games[0].contains("{Solid}"));
+        assert!(games[0].contains("(1... d5 2. c4)"));
+    }
+
+    #[wasm_bindgen_test]
+    async fn keeps_study_headers_as_exported() {
+        serve("[Event \"Endgames: Lucena\"]\n[Round \"1.2\"]\n\n1. e4 *\n");
+        let export = LichessExport::Study {
+            id: "abc123".to_string(),
+        };
+        let text = LichessExporter::new("http://localhost:8080")
+            .fetch(&export)
+            .await
+            .expect("stand-in answers");
+        let games = crate::split_pgn(&text, export.is_broadcast());
+        assert_eq!(games.len(), 1);
+        assert!(games[0].contains("[Round \"1.2\"]"));
+        assert!(!games[0].contains("[Board "));
+    }
+}
@@ -1,4 +1,3 @@
-use nostr_minions::browser_api::IdbStoreManager;
 use nostr_minions::nostro2::NostrSigner;
 use shady_minions::ui::{
     Button, Card, CardContent, CardDescription, CardHeader, CardTitle, Form, Input, Modal, Tabs,
@@ -11,6 +10,7 @@ pub fn login_form() -> Html {
     let language_ctx = crate::language::use_language_ctx();
     let open_modal = use_state(|| false);
     let login_modal = use_state(|| false);
+    let remote_modal = use_state(|| false);
     let onclick = {
         let modal = open_modal.clone();
         Callback::from(move |_| {
@@ -23,6 +23,16 @@ pub fn login_form() -> Html {
             modal.set(!(*modal));
         })
     };
+    let remote_onclick = {
+        let modal = remote_modal.clone();
+        Callback::from(move |_| {
+            modal.set(!(*modal));
+        })
+    };
+    let signer_ctx = crate::signer::use_signer_ctx();
+    let extension_onclick = Callback::from(move |_| {
+        crate::signer::connect_extension(&signer_ctx);
+    });
     html! {
         <>
         <img
@@ -64,8 +74,34 @@ pub fn login_form() -> Html {
                         </Button>
                     </TabsContent>
                 </Tabs>
+                {if crate::signer::ExtensionSigner::is_available() {
+                    html! {
+                        <Button
+                            onclick={extension_onclick}
+                            r#type={shady_minions::ui::ButtonType::Button}
+                            variant={shady_minions::ui::ButtonVariant::Outline}
+                            class={classes!("mt-4", "w-full")}>
+                            <lucide_yew::Puzzle class={classes!("size-4", "mr-2")} />
+                            { language_ctx.t("login_with_extension") }
+                        </Button>
+                    }
+                } else {
+                    html! {}
+                }}
+                <Button
+                    onclick={remote_onclick}
+                    r#type={shady_minions::ui::ButtonType::Button}
+                    variant={shady_minions::ui::ButtonVariant::Outline}
+                    class={classes!("mt-4", "w-full")}>
+                    <lucide_yew::KeyRound class={classes!("size-4", "mr-2")} />
+                    { language_ctx.t("login_with_remote_signer") }
+                </Button>
+                <crate::SavedAccounts />
             </CardContent>
         </Card>
+        <Modal is_open={remote_modal} >
+            <RemoteSignerLogin />
+        </Modal>
         <Modal is_open={open_modal} >
             <NewKeyForm />
         </Modal>
@@ -82,48 +118,66 @@ pub fn new_key_form() -> Html {
     let key_ctx = use_context::<nostr_minions::key_manager::NostrIdStore>()
         .expect("KeyManagerProvider not found");
 
+    let lock_ctx = crate::key_lock::use_key_lock_ctx();
+
+    let words = use_state(|| vec![String::new(); 24]);
+    let mnemonic_error = use_state(|| None::<crate::KeyInputError>);
+    let nsec_error = use_state(|| None::<crate::KeyInputError>);
+
+    // A 24 word phrase may be this app's backup or a NIP-06 phrase from another client, so
+    // the user picks the key whose npub they recognise.
+    let key_choices = use_state(|| None::<KeyChoices>);
+
+    let import_key = {
+        let key_ctx = key_ctx.clone();
+        let lock_ctx = lock_ctx.clone();
+        let key_choices = key_choices.clone();
+        Callback::from(
+            move |(new_key, passphrase): (
+                nostr_minions::nostro2_signer::keypair::NostrKeypair,
+                Option<String>,
+            )| {
+                key_choices.set(None);
+                let key_ctx = key_ctx.clone();
+                let lock_ctx = lock_ctx.clone();
+                yew::platform::spawn_local(async move {
+                    if let Err(error) =
+                        crate::key_lock::store_identity(&key_ctx, &lock_ctx, new_key, passphrase)
+                            .await
+                    {
+                        crate::toast_failure(&error);
+                    }
+                });
+            },
+        )
+    };
+
     let mnemonic_submit = {
-        let key_ctx = key_ctx.dispatcher();
+        let words = words.clone();
+        let mnemonic_error = mnemonic_error.clone();
+        let key_choices = key_choices.clone();
+        let import_key = import_key.clone();
         Callback::from(move |form: web_sys::HtmlFormElement| {
-            let mut mnemonic = vec![];
-            for i in 1..=24 {
-                let Some(word) = form
-                    .get_with_name(&format!("word-{i}"))
-                    .map(|input| input.unchecked_into::<web_sys::HtmlInputElement>().value())
-                else {
-                    web_sys::console::log_1(&"Error: Input not found".into());
+            let mut keys = match crate::parse_mnemonic(&words) {
+                Ok(keys) => keys,
+                Err(error) => {
+                    mnemonic_error.set(Some(error));
                     return;
-                };
-                mnemonic.push(word);
+                }
+            };
+            mnemonic_error.set(None);
+            let passphrase = passphrase_value(&form);
+            if keys.len() > 1 {
+                key_choices.set(Some(KeyChoices { keys, passphrase }));
+            } else if let Some((_, new_key)) = keys.pop() {
+                import_key.emit((new_key, passphrase));
             }
-            let mnemonic = mnemonic.join(" ");
-            let new_key = nostr_minions::nostro2_signer::keypair::NostrKeypair::parse_mnemonic(
-                &mnemonic,
-                nostr_minions::nostro2_signer::Language::English,
-                true,
-            )
-            .expect("Failed to create new key");
-            let key_ctx = key_ctx.clone();
-            yew::platform::spawn_local(async move {
-                let pubkey = new_key.public_key();
-                let new_id =
-                    nostr_minions::key_manager::UserIdentity::from_new_keys(new_key.clone())
-                        .await
-                        .expect("Failed to create new key");
-                new_id
-                    .clone()
-                    .save_to_store()
-                    .await
-                    .expect("Failed to save new key");
-                key_ctx.dispatch(nostr_minions::key_manager::NostrIdAction::LoadIdentity(
-                    pubkey, new_id,
-                ));
-            });
         })
     };
 
     let nsec_submit = {
-        let key_ctx = key_ctx.dispatcher();
+        let key_ctx = key_ctx.clone();
+        let nsec_error = nsec_error.clone();
         Callback::from(move |form: web_sys::HtmlFormElement| {
             let Some(input) = form
                 .get_with_name("hex-key")
@@ -132,29 +186,23 @@ pub fn new_key_form() -> Html {
                 web_sys::console::log_1(&"Error: Input not found".into());
                 return;
             };
-            let Ok(mut new_key) =
-                input.parse::<nostr_minions::nostro2_signer::keypair::NostrKeypair>()
-            else {
-                // TODO
-                web_sys::console::log_1(&"Error: Invalid NSEC key".into());
-                return;
+            let new_key = match crate::parse_secret_key(&input) {
+                Ok(new_key) => new_key,
+                Err(error) => {
+                    nsec_error.set(Some(error));
+                    return;
+                }
             };
-            new_key.set_extractable(true);
+            nsec_error.set(None);
+            let passphrase = passphrase_value(&form);
             let key_ctx = key_ctx.clone();
+            let lock_ctx = lock_ctx.clone();
             yew::platform::spawn_local(async move {
-                let pubkey = new_key.public_key();
-                let new_id =
-                    nostr_minions::key_manager::UserIdentity::from_new_keys(new_key.clone())
-                        .await
-                        .expect("Failed to create new key");
-                new_id
-                    .clone()
-                    .save_to_store()
-                    .await
-                    .expect("Failed to save new key");
-                key_ctx.dispatch(nostr_minions::key_manager::NostrIdAction::LoadIdentity(
-                    pubkey, new_id,
-                ));
+                if let Err(error) =
+                    crate::key_lock::store_identity(&key_ctx, &lock_ctx, new_key, passphrase).await
+                {
+                    crate::toast_failure(&error);
+                }
             });
         })
     };
@@ -177,26 +225,63 @@ pub fn new_key_form() -> Html {
                             <p class={classes!("font-bold", "text-muted-foreground", "select-none", "pointer-events-none")}>
                                 { language_ctx.t("login_keep_order") }
                             </p>
-                            <div class={classes!("font-bold", "text-sm", "grid", "grid-cols-3", "gap-2")}>
-                                { (1..24).map(|i| {
-                                    html! {
-                                        <div class={classes!("text-center", "text-sm", "font-bold", "flex", "gap-1")}>
-                                            <Input
-                                                id={format!("word-{i}")}
-                                                placeholder={language_ctx.t("login_word_format").replace("{0}", &i.to_string())}
-                                                required={true}
-                                                r#type={shady_minions::ui::InputType::Text}
-                                                class={classes!("text-sm", "font-bold", "text-center")}/>
-                                        </div>
-                                    }
-                                }).collect::<Html>() }
-                            </div>
+                            <MnemonicWords
+                                words={(*words).clone()}
+                                onchange={
+                                    let words = words.clone();
+                                    let mnemonic_error = mnemonic_error.clone();
+                                    Callback::from(move |new_words| {
+                                        mnemonic_error.set(None);
+                                        words.set(new_words);
+                                    })
+                                } />
+                            <InputError error={(*mnemonic_error).clone()} />
+                            <PassphraseInput />
                             <Button
                                 r#type={shady_minions::ui::ButtonType::Submit}
                                 class={classes!("mt-4", "mr-4")}>
                                 { language_ctx.t("common_save") }
                             </Button>
                         </Form>
+                        {if let Some(choices) = (*key_choices).clone() {
+                            html! {
+                                <div class={classes!("space-y-2")}>
+                                    <p class={classes!("text-sm", "font-bold")}>
+                                        { language_ctx.t("login_choose_key") }
+                                    </p>
+                                    {for choices.keys.into_iter().map(|(derivation, keypair)| {
+                                        let label = match derivation {
+                                            crate::MnemonicDerivation::Backup => language_ctx.t("login_key_backup"),
+                                            crate::MnemonicDerivation::Nip06 => language_ctx.t("login_key_nip06"),
+                                        };
+                                        let npub = crate::accounts::npub(&keypair.public_key());
+                                        let onclick = {
+                                            let import_key = import_key.clone();
+                                            let passphrase = choices.passphrase.clone();
+                                            Callback::from(move |_| {
+                                                import_key.emit((keypair.clone(), passphrase.clone()));
+                                            })
+                                        };
+                                        html! {
+                                            <div class={classes!("flex", "items-center", "justify-between", "gap-4", "p-3", "border", "border-border", "rounded-lg")}>
+                                                <div class={classes!("min-w-0")}>
+                                                    <p class={classes!("text-sm")}>{ label }</p>
+                                                    <p class={classes!("text-xs", "font-mono", "text-muted-foreground", "break-all")}>{ npub }</p>
+                                                </div>
+                                                <Button
+                                                    {onclick}
+                                                    r#type={shady_minions::ui::ButtonType::Button}
+                                                    variant={shady_minions::ui::ButtonVariant::Outline}>
+                                                    { language_ctx.t("login_use_key") }
+                                                </Button>
+                                            </div>
+                                        }
+                                    })}
+                                </div>
+                            }
+                        } else {
+                            html! {}
+                        }}
                     </TabsContent>
                     <TabsContent value="hex-key" class={classes!("space-y-4")}>
                         <Form onsubmit={nsec_submit}>
@@ -205,10 +290,12 @@ pub fn new_key_form() -> Html {
                         </p>
                         <Input
                             id="hex-key"
-                            placeholder="nsec_1234567890abcdef"
+                            placeholder="nsec1… / hex"
                             required={true}
                             r#type={shady_minions::ui::InputType::Password}
                             class={classes!("text-sm", "font-bold", "text-center")}/>
+                        <InputError error={(*nsec_error).clone()} />
+                        <PassphraseInput />
                         <Button
                             r#type={shady_minions::ui::ButtonType::Submit}
                             class={classes!("mt-4", "mr-4")}>
@@ -233,25 +320,22 @@ pub fn new_key_form() -> Html {
         .mnemonic(nostr_minions::nostro2_signer::Language::English)
         .unwrap_or_default();
     let hex_key = new_key.nsec().unwrap_or_default();
+    let lock_ctx = crate::key_lock::use_key_lock_ctx();
+    let passphrase = use_state(String::new);
     let onclick = {
         let keys = new_key.clone();
+        let passphrase = passphrase.clone();
         Callback::from(move |_| {
-            let keys = keys.clone();
-            let key_ctx = key_ctx.dispatcher();
+            let keys = (*keys).clone();
+            let passphrase = Some((*passphrase).clone());
+            let key_ctx = key_ctx.clone();
+            let lock_ctx = lock_ctx.clone();
             yew::platform::spawn_local(async move {
-                let pubkey = keys.public_key();
-                let new_id =
-                    nostr_minions::key_manager::UserIdentity::from_new_keys((*keys).clone())
-                        .await
-                        .expect("Failed to create new key");
-                new_id
-                    .clone()
-                    .save_to_store()
-                    .await
-                    .expect("Failed to save new key");
-                key_ctx.dispatch(nostr_minions::key_manager::NostrIdAction::LoadIdentity(
-                    pubkey, new_id,
-                ));
+                if let Err(error) =
+                    crate::key_lock::store_identity(&key_ctx, &lock_ctx, keys, passphrase).await
+                {
+                    crate::toast_failure(&error);
+                }
             });
         })
     };
@@ -316,6 +400,7 @@ pub fn new_key_form() -> Html {
                         </div>
                     </TabsContent>
                 </Tabs>
+                <PassphraseInput onchange={Callback::from(move |value| passphrase.set(value))} />
                 <Button
                     r#type={shady_minions::ui::ButtonType::Button}
                     {onclick}
@@ -333,3 +418,475 @@ pub fn new_key_form() -> Html {
         </Card>
     }
 }
+
+#[derive(Properties, PartialEq)]
+struct InputErrorProps {
+    error: Option<crate::KeyInputError>,
+}
+
+#[function_component(InputError)]
+fn input_error(props: &InputErrorProps) -> Html {
+    let language_ctx = crate::language::use_language_ctx();
+    let Some(error) = props.error.as_ref() else {
+        return html! {};
+    };
+    html! {
+        <p class={classes!("flex", "items-center", "gap-2", "text-sm", "text-red-500", "mt-2")}>
+            <lucide_yew::TriangleAlert class={classes!("size-4", "flex-shrink-0")} />
+            { error.message(&language_ctx) }
+        </p>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct MnemonicWordsProps {
+    pub words: Vec<String>,
+    pub onchange: Callback<Vec<String>>,
+}
+
+/// Recovery phrase entry: one field per word, wordlist suggestions, and whole-phrase paste.
+#[function_component(MnemonicWords)]
+pub fn mnemonic_words(props: &MnemonicWordsProps) -> Html {
+    let language_ctx = crate::language::use_language_ctx();
+    let focused = use_state(|| None::<usize>);
+    let words = props.words.clone();
+    let suggestions = (*focused)
+        .and_then(|index| words.get(index))
+        .map(|word| crate::mnemonic_suggestions(word, 6))
+        .unwrap_or_default();
+
+    let set_word_count = |count: usize| {
+        let words = words.clone();
+        let onchange = props.onchange.clone();
+        Callback::from(move |_| {
+            let mut words = words.clone();
+            words.resize(count, String::new());
+            onchange.emit(words);
+        })
+    };
+    let pick_suggestion = |suggestion: &'static str| {
+        let words = words.clone();
+        let onchange = props.onchange.clone();
+        let focused = focused.clone();
+        Callback::from(move |_| {
+            let Some(index) = *focused else {
+                return;
+            };
+            let mut words = words.clone();
+            words[index] = suggestion.to_string();
+            onchange.emit(words);
+            if let Some(next) = web_sys::window()
+                .and_then(|window| window.document())
+                .and_then(|document| document.get_element_by_id(&format!("word-{}", index + 2)))
+            {
+                let _ = next.unchecked_into::<web_sys::HtmlElement>().focus();
+            }
+        })
+    };
+
+    html! {
+        <>
+        <div class={classes!("flex", "gap-1", "mb-2")}>
+            { crate::MNEMONIC_WORD_COUNTS.iter().map(|count| {
+                let selected = words.len() == *count;
+                html! {
+                    <button
+                        type="button"
+                        onclick={set_word_count(*count)}
+                        class={classes!(
+                            "px-3", "py-1", "rounded-full", "text-sm",
+                            if selected { "bg-primary" } else { "bg-zinc-800" },
+                        )}>
+                        { language_ctx.t("login_word_count").replace("{0}", &count.to_string()) }
+                    </button>
+                }
+            }).collect::<Html>() }
+        </div>
+        <div class={classes!("font-bold", "text-sm", "grid", "grid-cols-3", "gap-2")}>
+            { words.iter().enumerate().map(|(index, word)| {
+                let invalid = !word.is_empty()
+                    && *focused != Some(index)
+                    && !crate::is_mnemonic_word(word.trim());
+                let oninput = {
+                    let words = words.clone();
+                    let onchange = props.onchange.clone();
+                    Callback::from(move |e: InputEvent| {
+                        let value = e.target_unchecked_into::<web_sys::HtmlInputElement>().value();
+                        let mut words = words.clone();
+                        words[index] = value.trim().to_lowercase();
+                        onchange.emit(words);
+                    })
+                };
+                let onpaste = {
+                    let words = words.clone();
+                    let onchange = props.onchange.clone();
+                    Callback::from(move |e: Event| {
+                        let Some(text) = e
+                            .dyn_ref::<web_sys::ClipboardEvent>()
+                            .and_then(web_sys::ClipboardEvent::clipboard_data)
+                            .and_then(|data| data.get_data("text").ok())
+                        else {
+                            return;
+                        };
+                        let pasted = crate::split_phrase(&text);
+                        if pasted.len() < 2 {
+                            return;
+                        }
+                        e.prevent_default();
+                        let mut new_words = words[..index].to_vec();
+                        new_words.extend(pasted);
+                        let count = crate::word_count_for(new_words.len());
+                        new_words.resize(count, String::new());
+                        onchange.emit(new_words);
+                    })
+                };
+                let onfocus = {
+                    let focused = focused.clone();
+                    Callback::from(move |_| focused.set(Some(index)))
+                };
+                html! {
+                    <div class={classes!("text-center", "text-sm", "font-bold", "flex", "gap-1")}>
+                        <input
+                            id={format!("word-{}", index + 1)}
+                            name={format!("word-{}", index + 1)}
+                            value={word.clone()}
+                            placeholder={language_ctx.t("login_word_format").replace("{0}", &(index + 1).to_string())}
+                            required={true}
+                            autocomplete="off"
+                            autocapitalize="none"
+                            spellcheck="false"
+                            {oninput}
+                            {onpaste}
+                            {onfocus}
+                            class={classes!(
+                                "w-full", "rounded-md", "border", "bg-transparent", "px-2", "py-1",
+                                "text-sm", "font-bold", "text-center",
+                                if invalid { "border-red-500" } else { "border-input" },
+                            )} />
+                    </div>
+                }
+            }).collect::<Html>() }
+        </div>
+        {if suggestions.is_empty() {
+            html! {}
+        } else {
+            html! {
+                <div class={classes!("flex", "flex-wrap", "gap-1", "mt-2")}>
+                    { suggestions.into_iter().map(|suggestion| html! {
+                        <button
+                            type="button"
+                            onclick={pick_suggestion(suggestion)}
+                            class={classes!("px-2", "py-1", "rounded-md", "text-xs", "bg-muted")}>
+                            { suggestion }
+                        </button>
+                    }).collect::<Html>() }
+                </div>
+            }
+        }}
+        </>
+    }
+}
+
+/// Reads the optional passphrase from a login form; an empty field keeps the key unencrypted.
+/// The keys a recovery phrase can stand for, kept until the user picks one.
+#[derive(Clone)]
+struct KeyChoices {
+    keys: Vec<(
+        crate::MnemonicDerivation,
+        nostr_minions::nostro2_signer::keypair::NostrKeypair,
+    )>,
+    passphrase: Option<String>,
+}
+
+fn passphrase_value(form: &web_sys::HtmlFormElement) -> Option<String> {
+    form.get_with_name("passphrase")
+        .map(|input| input.unchecked_into::<web_sys::HtmlInputElement>().value())
+        .filter(|passphrase| !passphrase.is_empty())
+}
+
+#[derive(Properties, PartialEq)]
+pub struct PassphraseInputProps {
+    #[prop_or_default]
+    pub onchange: Callback<String>,
+}
+
+#[function_component(PassphraseInput)]
+pub fn passphrase_input(props: &PassphraseInputProps) -> Html {
+    let language_ctx = crate::language::use_language_ctx();
+    html! {
+        <div class={classes!("space-y-2", "mt-4")}>
+            <p class={classes!("text-sm", "text-muted-foreground")}>
+                { language_ctx.t("login_passphrase_message") }
+            </p>
+            <Input
+                id="passphrase"
+                name="passphrase"
+                placeholder={language_ctx.t("login_passphrase")}
+                required={false}
+                onchange={props.onchange.clone()}
+                r#type={shady_minions::ui::InputType::Password}
+                class={classes!("text-sm", "text-center")}/>
+        </div>
+    }
+}
+
+#[function_component(UnlockKey)]
+pub fn unlock_key() -> Html {
+    let language_ctx = crate::language::use_language_ctx();
+    let key_ctx = nostr_minions::key_manager::use_nostr_id_ctx();
+    let lock_ctx = crate::key_lock::use_key_lock_ctx();
+    let switch = crate::accounts::use_account_switch();
+    let unlocking = use_state(|| false);
+    let Some(encrypted) = lock_ctx.encrypted().cloned() else {
+        return html! {};
+    };
+    let npub = crate::accounts::npub(&encrypted.pubkey);
+
+    let onsubmit = {
+        let unlocking = unlocking.clone();
+        let wrong_passphrase = language_ctx.t("key_lock_wrong_passphrase");
+        Callback::from(move |form: web_sys::HtmlFormElement| {
+            let Some(passphrase) = passphrase_value(&form) else {
+                return;
+            };
+            unlocking.set(true);
+            let key_ctx = key_ctx.clone();
+            let encrypted = encrypted.clone();
+            let unlocking = unlocking.clone();
+            let wrong_passphrase = wrong_passphrase.clone();
+            yew::platform::spawn_local(async move {
+                // Let the unlocking state paint before scrypt blocks the thread.
+                gloo::timers::future::TimeoutFuture::new(0).await;
+                if crate::key_lock::unlock(&key_ctx, &encrypted, &passphrase)
+                    .await
+                    .is_err()
+                {
+                    crate::toast_failure(&wrong_passphrase);
+                }
+                unlocking.set(false);
+            });
+        })
+    };
+    let use_other_key = {
+        let switch = switch.clone();
+        Callback::from(move |_| switch.sign_out())
+    };
+
+    html! {
+        <>
+        <img
+            class={classes!("mb-4",  "mx-auto")}
+            src="public/img/splashscreen.svg"
+            alt={language_ctx.t("login_logo_alt")}
+        />
+        <Card class={classes!("max-w-sm", "min-w-sm")}>
+            <CardHeader>
+                <CardTitle>{ language_ctx.t("key_lock_title") }</CardTitle>
+                <CardDescription class={classes!("flex-1", "break-all")}>
+                    { language_ctx.t("key_lock_message") }
+                    <br />
+                    { npub }
+                </CardDescription>
+            </CardHeader>
+            <CardContent class={classes!("space-y-4")}>
+                <Form {onsubmit}>
+                    <Input
+                        id="passphrase"
+                        name="passphrase"
+                        placeholder={language_ctx.t("login_passphrase")}
+                        required={true}
+                        r#type={shady_minions::ui::InputType::Password}
+                        class={classes!("text-sm", "text-center")}/>
+                    <Button
+                        r#type={shady_minions::ui::ButtonType::Submit}
+                        disabled={*unlocking}
+                        class={classes!("mt-4", "w-full")}>
+                        <lucide_yew::LockOpen class={classes!("size-4", "mr-2")} />
+                        { if *unlocking {
+                            language_ctx.t("key_lock_unlocking")
+                        } else {
+                            language_ctx.t("key_lock_unlock")
+                        } }
+                    </Button>
+                </Form>
+                <Button
+                    onclick={use_other_key}
+                    r#type={shady_minions::ui::ButtonType::Button}
+                    variant={shady_minions::ui::ButtonVariant::Outline}
+                    class={classes!("w-full")}>
+                    { language_ctx.t("key_lock_use_other_key") }
+                </Button>
+            </CardContent>
+        </Card>
+        </>
+    }
+}
+
+#[function_component(RemoteSignerLogin)]
+pub fn remote_signer_login() -> Html {
+    let language_ctx = crate::language::use_language_ctx();
+    let signer_ctx = crate::signer::use_signer_ctx();
+    let connecting = use_state(|| false);
+
+    let bunker_submit = {
+        let signer_ctx = signer_ctx.clone();
+        let connecting = connecting.clone();
+        Callback::from(move |form: web_sys::HtmlFormElement| {
+            let Some(input) = form
+                .get_with_name("bunker-uri")
+                .map(|input| input.unchecked_into::<web_sys::HtmlInputElement>().value())
+            else {
+                return;
+            };
+            let uri = match input.parse::<crate::BunkerUri>() {
+                Ok(uri) => uri,
+                Err(error) => {
+                    crate::toast_failure(&error);
+                    return;
+                }
+            };
+            connecting.set(true);
+            let ctx = signer_ctx.dispatcher();
+            let inbox = signer_ctx.inbox();
+            let connecting = connecting.clone();
+            yew::platform::spawn_local(async move {
+                match crate::RemoteSigner::connect(&uri, inbox).await {
+                    Ok(session) => crate::signer::connect_remote(&ctx, session),
+                    Err(error) => crate::toast_failure(&error),
+                }
+                connecting.set(false);
+            });
+        })
+    };
+
+    html! {
+        <Card>
+            <CardHeader>
+                <CardTitle>{ language_ctx.t("login_remote_signer") }</CardTitle>
+                <CardDescription class={classes!("flex-1")}>
+                    { language_ctx.t("login_remote_signer_message") }
+                </CardDescription>
+            </CardHeader>
+            <CardContent class={classes!("space-y-4")}>
+                <Tabs default_value="bunker-uri" class={classes!("w-full")}>
+                    <TabsList class={classes!("justify-stretch", "w-full", "flex")}>
+                        <TabsTrigger value="bunker-uri">{ language_ctx.t("login_bunker_uri") }</TabsTrigger>
+                        <TabsTrigger value="nostrconnect">{ language_ctx.t("login_nostrconnect") }</TabsTrigger>
+                    </TabsList>
+                    <TabsContent value="bunker-uri" class={classes!("space-y-4")}>
+                        <Form onsubmit={bunker_submit}>
+                            <Input
+                                id="bunker-uri"
+                                name="bunker-uri"
+                                placeholder="bunker://..."
+                                required={true}
+                                r#type={shady_minions::ui::InputType::Text}
+                                class={classes!("text-sm")}/>
+                            <Button
+                                r#type={shady_minions::ui::ButtonType::Submit}
+                                disabled={*connecting}
+                                class={classes!("mt-4", "mr-4")}>
+                                { if *connecting {
+                                    language_ctx.t("login_remote_connecting")
+                                } else {
+                                    language_ctx.t("login_remote_connect")
+                                } }
+                            </Button>
+                        </Form>
+                    </TabsContent>
+                    <TabsContent value="nostrconnect" class={classes!("space-y-4")}>
+                        <NostrConnectQr />
+                    </TabsContent>
+                </Tabs>
+            </CardContent>
+        </Card>
+    }
+}
+
+const NOSTRCONNECT_RELAYS: usize = 3;
+
+/// Shows a `nostrconnect://` QR code and signs in once a remote signer scans it.
+#[function_component(NostrConnectQr)]
+fn nostrconnect_qr() -> Html {
+    let language_ctx = crate::language::use_language_ctx();
+    let signer_ctx = crate::signer::use_signer_ctx();
+    let relay_ctx = nostr_minions::relay_pool::use_nostr_relay_pool();
+    let client = use_memo((), |()| {
+        nostr_minions::nostro2_signer::keypair::NostrKeypair::generate(true)
+    });
+    let secret = use_memo((), |()| {
+        nostr_minions::nostro2_signer::keypair::NostrKeypair::generate(false).public_key()[..16]
+            .to_string()
+    });
+    let relays = use_memo((), |()| {
+        relay_ctx
+            .relay_health()
+            .keys()
+            .take(NOSTRCONNECT_RELAYS)
+            .cloned()
+            .collect::<Vec<_>>()
+    });
+    let uri = crate::nostrconnect_uri(&client.public_key(), &relays, &secret);
+
+    {
+        let ctx = signer_ctx.dispatcher();
+        let inbox = signer_ctx.inbox();
+        let client = (*client).clone();
+        let secret = (*secret).clone();
+        let relays = (*relays).clone();
+        use_effect_with((), move |()| {
+            let cancel = crate::imports::CancelToken::default();
+            inbox.listen(&relays, &client);
+            {
+                let cancel = cancel.clone();
+                yew::platform::spawn_local(async move {
+                    let session = match inbox.wait_for_connect(&secret, &cancel).await {
+                        Ok(remote_pubkey) => {
+                            crate::RemoteSigner::accept(client, remote_pubkey, relays, inbox).await
+                        }
+                        Err(error) => Err(error),
+                    };
+                    match session {
+                        Ok(session) => crate::signer::connect_remote(&ctx, session),
+                        // Closing the dialog is not a failure worth telling about.
+                        Err(_) if cancel.is_cancelled() => {}
+                        Err(error) => crate::toast_failure(&error),
+                    }
+                });
+            }
+            move || cancel.cancel()
+        });
+    }
+
+    let qr = qrcode::QrCode::new(uri.as_bytes())
+        .map(|code| {
+            code.render::<qrcode::render::svg::Color>()
+                .min_dimensions(240, 240)
+                .build()
+        })
+        .unwrap_or_default();
+    let copy_uri = {
+        let uri = uri.clone();
+        Callback::from(move |_| {
+            nostr_minions::browser_api::clipboard_copy(&uri);
+        })
+    };
+    html! {
+        <>
+            <p class={classes!("text-sm", "text-muted-foreground")}>
+                { language_ctx.t("login_nostrconnect_message") }
+            </p>
+            <div class={classes!("bg-white", "p-2", "rounded-lg", "w-fit", "mx-auto")}>
+                { Html::from_html_unchecked(AttrValue::from(qr)) }
+            </div>
+            <Button
+                onclick={copy_uri}
+                r#type={shady_minions::ui::ButtonType::Button}
+                variant={shady_minions::ui::ButtonVariant::Outline}
+                class={classes!("w-full")}>
+                <lucide_yew::Copy class={classes!("size-4", "mr-2")} />
+                { language_ctx.t("login_copy_nostrconnect") }
+            </Button>
+        </>
+    }
+}
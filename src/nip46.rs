@@ -0,0 +1,792 @@
+//! NIP-46 remote signing: the user's key stays with a remote signer ("bunker")
+//! and every signature or encryption is requested over relays.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use futures_util::{SinkExt, StreamExt};
+use gloo::net::websocket::{futures::WebSocket, Message};
+use nostr_minions::browser_api::IdbStoreManager;
+use nostr_minions::nostro2::NostrSigner;
+use nostr_minions::nostro2_signer::nostro2_nips::Nip44;
+
+pub const NIP46_KIND: u32 = 24133;
+const POLL_MS: u32 = 100;
+const REQUEST_TIMEOUT_MS: f64 = 30_000.0;
+/// How long to wait once the signer asked the user to approve a request.
+const AUTH_TIMEOUT_MS: f64 = 180_000.0;
+const CONNECT_TIMEOUT_MS: f64 = 300_000.0;
+
+fn now_ms() -> f64 {
+    web_sys::js_sys::Date::now()
+}
+
+/// A random hex string, taken from a freshly generated public key.
+fn random_hex() -> String {
+    nostr_minions::nostro2_signer::keypair::NostrKeypair::generate(false).public_key()[..32]
+        .to_string()
+}
+
+fn is_hex_pubkey(value: &str) -> bool {
+    value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// The query pairs of a `bunker://` or `nostrconnect://` URI, decoded.
+fn query_pairs(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .filter_map(|(key, value)| {
+            let value = web_sys::js_sys::decode_uri_component(value)
+                .ok()?
+                .as_string()?;
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
+
+fn encode(value: &str) -> String {
+    web_sys::js_sys::encode_uri_component(value)
+        .as_string()
+        .unwrap_or_default()
+}
+
+/// A parsed `bunker://<remote-signer-pubkey>?relay=...&secret=...` URI.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BunkerUri {
+    pub remote_pubkey: String,
+    pub relays: Vec<String>,
+    pub secret: Option<String>,
+}
+
+impl std::str::FromStr for BunkerUri {
+    type Err = String;
+
+    fn from_str(uri: &str) -> Result<Self, Self::Err> {
+        let rest = uri
+            .trim()
+            .strip_prefix("bunker://")
+            .ok_or("A remote signer URI starts with bunker://")?;
+        let (remote_pubkey, query) = rest.split_once('?').unwrap_or((rest, ""));
+        if !is_hex_pubkey(remote_pubkey) {
+            return Err("Invalid remote signer public key".to_string());
+        }
+        let pairs = query_pairs(query);
+        let relays = pairs
+            .iter()
+            .filter(|(key, _)| key == "relay")
+            .map(|(_, relay)| relay.trim_end_matches('/').to_string())
+            .collect::<Vec<_>>();
+        if relays.is_empty() {
+            return Err("The remote signer URI has no relays".to_string());
+        }
+        Ok(Self {
+            remote_pubkey: remote_pubkey.to_lowercase(),
+            relays,
+            secret: pairs
+                .into_iter()
+                .find(|(key, _)| key == "secret")
+                .map(|(_, secret)| secret),
+        })
+    }
+}
+
+/// Builds the `nostrconnect://` URI a remote signer scans to connect to this client.
+#[must_use]
+pub fn nostrconnect_uri(client_pubkey: &str, relays: &[String], secret: &str) -> String {
+    let mut uri = format!("nostrconnect://{client_pubkey}?");
+    for relay in relays {
+        uri.push_str(&format!("relay={}&", encode(relay)));
+    }
+    uri.push_str(&format!("secret={secret}&name={}", encode("Bunker")));
+    uri
+}
+
+/// A connection to a remote signer. Only the throwaway client key is stored;
+/// the user's private key never reaches the browser.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RemoteSignerSession {
+    pub user_pubkey: String,
+    pub remote_pubkey: String,
+    pub client_key: String,
+    pub relays: Vec<String>,
+}
+
+impl RemoteSignerSession {
+    #[must_use]
+    pub fn client_keypair(&self) -> Option<nostr_minions::nostro2_signer::keypair::NostrKeypair> {
+        self.client_key.parse().ok()
+    }
+}
+
+impl IdbStoreManager for RemoteSignerSession {
+    fn config() -> nostr_minions::browser_api::IdbStoreConfig {
+        crate::idb::store_config("remote_signer", "user_pubkey")
+    }
+    fn key(&self) -> web_sys::wasm_bindgen::JsValue {
+        web_sys::wasm_bindgen::JsValue::from_str(&self.user_pubkey)
+    }
+}
+
+impl From<RemoteSignerSession> for web_sys::wasm_bindgen::JsValue {
+    fn from(value: RemoteSignerSession) -> Self {
+        serde_wasm_bindgen::to_value(&value).unwrap_or_default()
+    }
+}
+
+impl TryFrom<web_sys::wasm_bindgen::JsValue> for RemoteSignerSession {
+    type Error = web_sys::wasm_bindgen::JsValue;
+    fn try_from(value: web_sys::wasm_bindgen::JsValue) -> Result<Self, Self::Error> {
+        Ok(serde_wasm_bindgen::from_value(value)?)
+    }
+}
+
+/// A decrypted NIP-46 response.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+pub struct Nip46Response {
+    pub id: String,
+    #[serde(default)]
+    pub result: String,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(skip)]
+    pub sender: String,
+}
+
+/// An open connection to a remote signer relay; dropping `sender` closes it.
+struct SignerSocket {
+    sender: yew::platform::pinned::mpsc::UnboundedSender<String>,
+    marker: Rc<()>,
+}
+
+/// Talks to remote signers over their own relays and collects their responses until a
+/// request picks them up.
+///
+/// The connections are kept out of the relay pool, so signing in with a remote signer
+/// never adds its relays to the user's saved relay list.
+#[derive(Clone, Default)]
+pub struct Nip46Inbox {
+    clients: Rc<RefCell<Vec<nostr_minions::nostro2_signer::keypair::NostrKeypair>>>,
+    responses: Rc<RefCell<HashMap<String, Nip46Response>>>,
+    sockets: Rc<RefCell<HashMap<String, SignerSocket>>>,
+    /// `REQ` messages sent again on every connection opened later.
+    subscriptions: Rc<RefCell<Vec<String>>>,
+}
+
+impl PartialEq for Nip46Inbox {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.responses, &other.responses)
+    }
+}
+
+impl Nip46Inbox {
+    /// Opens the relays in `relays` that are not connected yet.
+    fn connect(&self, relays: &[String]) {
+        for url in relays {
+            if self.sockets.borrow().contains_key(url) {
+                continue;
+            }
+            let Ok(socket) = WebSocket::open(url) else {
+                web_sys::console::error_1(&format!("Failed to open signer relay {url}").into());
+                continue;
+            };
+            let (mut sink, mut stream) = socket.split();
+            let (sender, mut outgoing) = yew::platform::pinned::mpsc::unbounded::<String>();
+            for request in self.subscriptions.borrow().iter() {
+                let _ = sender.send_now(request.clone());
+            }
+            let marker = Rc::new(());
+            self.sockets.borrow_mut().insert(
+                url.clone(),
+                SignerSocket {
+                    sender,
+                    marker: marker.clone(),
+                },
+            );
+            yew::platform::spawn_local(async move {
+                while let Some(message) = outgoing.next().await {
+                    if sink.send(Message::Text(message)).await.is_err() {
+                        break;
+                    }
+                }
+                let _ = sink.close().await;
+            });
+            let inbox = self.clone();
+            let url = url.clone();
+            yew::platform::spawn_local(async move {
+                while let Some(Ok(Message::Text(text))) = stream.next().await {
+                    if let Ok(nostr_minions::nostro2::NostrRelayEvent::NewNote(_, _, note)) =
+                        text.parse::<nostr_minions::nostro2::NostrRelayEvent>()
+                    {
+                        inbox.receive(&note);
+                    }
+                }
+                // A dropped connection is opened again by the next request.
+                let mut sockets = inbox.sockets.borrow_mut();
+                if sockets
+                    .get(&url)
+                    .is_some_and(|socket| Rc::ptr_eq(&socket.marker, &marker))
+                {
+                    sockets.remove(&url);
+                }
+            });
+        }
+    }
+
+    fn send_to(&self, relays: &[String], message: &str) {
+        self.connect(relays);
+        let sockets = self.sockets.borrow();
+        for url in relays {
+            if let Some(socket) = sockets.get(url) {
+                let _ = socket.sender.send_now(message.to_string());
+            }
+        }
+    }
+
+    /// Starts accepting responses addressed to `client` and subscribes to them on `relays`.
+    pub fn listen(
+        &self,
+        relays: &[String],
+        client: &nostr_minions::nostro2_signer::keypair::NostrKeypair,
+    ) {
+        let pubkey = client.public_key();
+        {
+            let mut clients = self.clients.borrow_mut();
+            if clients.iter().any(|known| known.public_key() == pubkey) {
+                self.connect(relays);
+                return;
+            }
+            clients.push(client.clone());
+        }
+        let mut filter = nostr_minions::nostro2::NostrSubscription {
+            kinds: vec![NIP46_KIND].into(),
+            since: Some((now_ms() / 1000.0) as u64 - 60),
+            ..Default::default()
+        };
+        filter.add_tag("#p", &pubkey);
+        let request = serde_json::json!(["REQ", random_hex(), filter]).to_string();
+        self.subscriptions.borrow_mut().push(request.clone());
+        self.connect(relays);
+        for socket in self.sockets.borrow().values() {
+            let _ = socket.sender.send_now(request.clone());
+        }
+    }
+
+    /// Sends a signed request to the remote signer's relays.
+    pub fn publish(&self, relays: &[String], note: &nostr_minions::nostro2::NostrNote) {
+        self.send_to(relays, &serde_json::json!(["EVENT", note]).to_string());
+    }
+
+    /// Closes every signer relay connection and forgets the clients listened for.
+    pub fn close(&self) {
+        self.sockets.borrow_mut().clear();
+        self.subscriptions.borrow_mut().clear();
+        self.clients.borrow_mut().clear();
+    }
+
+    /// Decrypts a relay note if it is a response to one of our clients.
+    pub fn receive(&self, note: &nostr_minions::nostro2::NostrNote) {
+        if note.kind != NIP46_KIND {
+            return;
+        }
+        let recipients = crate::tag_values(note, "p");
+        let response = self
+            .clients
+            .borrow()
+            .iter()
+            .filter(|client| recipients.contains(&client.public_key()))
+            .find_map(|client| {
+                let json = client.nip_44_decrypt(&note.content, &note.pubkey).ok()?;
+                serde_json::from_str::<Nip46Response>(&json).ok()
+            });
+        if let Some(mut response) = response {
+            response.sender = note.pubkey.clone();
+            self.responses
+                .borrow_mut()
+                .insert(response.id.clone(), response);
+        }
+    }
+
+    fn take(&self, matches: impl Fn(&Nip46Response) -> bool) -> Option<Nip46Response> {
+        let id = self
+            .responses
+            .borrow()
+            .values()
+            .find(|response| matches(response))
+            .map(|response| response.id.clone())?;
+        self.responses.borrow_mut().remove(&id)
+    }
+
+    /// Waits for the response to request `id`, following `auth_url` challenges.
+    async fn wait_for(&self, id: &str) -> Result<String, String> {
+        self.wait_for_within(id, REQUEST_TIMEOUT_MS).await
+    }
+
+    async fn wait_for_within(&self, id: &str, timeout_ms: f64) -> Result<String, String> {
+        let mut deadline = now_ms() + timeout_ms;
+        while now_ms() < deadline {
+            if let Some(response) = self.take(|response| response.id == id) {
+                if response.result == "auth_url" {
+                    // The signer wants the user to approve this request on its own page.
+                    if let Some(url) = response.error.as_deref() {
+                        if let Some(window) = web_sys::window() {
+                            let _ = window.open_with_url_and_target(url, "_blank");
+                        }
+                    }
+                    nostr_minions::widgets::toastify::ToastifyOptions::new_success(
+                        "Approve the request in your remote signer",
+                    )
+                    .show();
+                    deadline = now_ms() + AUTH_TIMEOUT_MS;
+                    continue;
+                }
+                return match response.error.filter(|error| !error.is_empty()) {
+                    Some(error) => Err(error),
+                    None => Ok(response.result),
+                };
+            }
+            gloo::timers::future::TimeoutFuture::new(POLL_MS).await;
+        }
+        Err("The remote signer did not answer in time".to_string())
+    }
+
+    /// Waits for a signer to accept a `nostrconnect://` URI, returning its public key.
+    ///
+    /// # Errors
+    /// Returns an error if no signer answers with `secret` before the timeout or `cancel`
+    /// is cancelled first.
+    pub async fn wait_for_connect(
+        &self,
+        secret: &str,
+        cancel: &crate::imports::CancelToken,
+    ) -> Result<String, String> {
+        let deadline = now_ms() + CONNECT_TIMEOUT_MS;
+        while now_ms() < deadline {
+            if cancel.is_cancelled() {
+                return Err("Remote signer connection cancelled".to_string());
+            }
+            if let Some(response) = self.take(|response| response.result == secret) {
+                return Ok(response.sender);
+            }
+            gloo::timers::future::TimeoutFuture::new(POLL_MS).await;
+        }
+        Err("No remote signer connected in time".to_string())
+    }
+}
+
+/// Signs and encrypts through a remote signer, with requests sent over the relay pool.
+#[derive(Clone)]
+pub struct RemoteSigner {
+    pub session: RemoteSignerSession,
+    client: nostr_minions::nostro2_signer::keypair::NostrKeypair,
+    inbox: Nip46Inbox,
+}
+
+impl PartialEq for RemoteSigner {
+    fn eq(&self, other: &Self) -> bool {
+        self.session == other.session
+    }
+}
+
+impl RemoteSigner {
+    #[must_use]
+    pub fn new(session: RemoteSignerSession, inbox: Nip46Inbox) -> Option<Self> {
+        Some(Self {
+            client: session.client_keypair()?,
+            session,
+            inbox,
+        })
+    }
+
+    async fn request(&self, method: &str, params: Vec<String>) -> Result<String, String> {
+        let id = random_hex();
+        let body = serde_json::json!({ "id": id, "method": method, "params": params });
+        let content = self
+            .client
+            .nip_44_encrypt(&body.to_string(), &self.session.remote_pubkey)
+            .map_err(|_| "Failed to encrypt remote signer request".to_string())?
+            .to_string();
+        let mut note = crate::with_tag(
+            nostr_minions::nostro2::NostrNote {
+                kind: NIP46_KIND,
+                pubkey: self.client.public_key(),
+                content,
+                ..Default::default()
+            },
+            &["p", &self.session.remote_pubkey],
+        );
+        self.client
+            .sign_note(&mut note)
+            .map_err(|_| "Failed to sign remote signer request".to_string())?;
+        self.inbox.publish(&self.session.relays, &note);
+        self.inbox.wait_for(&id).await
+    }
+
+    /// Runs the NIP-46 handshake against `uri` and returns the session to keep.
+    ///
+    /// # Errors
+    /// Returns an error if the signer rejects the connection or does not answer.
+    pub async fn connect(
+        uri: &BunkerUri,
+        inbox: Nip46Inbox,
+    ) -> Result<RemoteSignerSession, String> {
+        let client = nostr_minions::nostro2_signer::keypair::NostrKeypair::generate(true);
+        inbox.listen(&uri.relays, &client);
+        let session = RemoteSignerSession {
+            user_pubkey: String::new(),
+            remote_pubkey: uri.remote_pubkey.clone(),
+            client_key: client
+                .nsec()
+                .map_err(|_| "Failed to create a client key".to_string())?,
+            relays: uri.relays.clone(),
+        };
+        let signer = Self {
+            session,
+            client,
+            inbox,
+        };
+        let mut params = vec![uri.remote_pubkey.clone()];
+        params.extend(uri.secret.clone());
+        signer.request("connect", params).await?;
+        signer.public_key_session().await
+    }
+
+    /// Completes a `nostrconnect://` login once the signer at `remote_pubkey` answered.
+    ///
+    /// # Errors
+    /// Returns an error if the signer does not reveal the user's public key.
+    pub async fn accept(
+        client: nostr_minions::nostro2_signer::keypair::NostrKeypair,
+        remote_pubkey: String,
+        relays: Vec<String>,
+        inbox: Nip46Inbox,
+    ) -> Result<RemoteSignerSession, String> {
+        let signer = Self {
+            session: RemoteSignerSession {
+                user_pubkey: String::new(),
+                remote_pubkey,
+                client_key: client
+                    .nsec()
+                    .map_err(|_| "Failed to keep the client key".to_string())?,
+                relays,
+            },
+            client,
+            inbox,
+        };
+        signer.public_key_session().await
+    }
+
+    async fn public_key_session(self) -> Result<RemoteSignerSession, String> {
+        let user_pubkey = self.request("get_public_key", vec![]).await?;
+        if !is_hex_pubkey(&user_pubkey) {
+            return Err("The remote signer returned an invalid public key".to_string());
+        }
+        Ok(RemoteSignerSession {
+            user_pubkey,
+            ..self.session
+        })
+    }
+
+    /// # Errors
+    /// Returns an error if the signer refuses or returns an invalid event.
+    pub async fn sign_note(
+        &self,
+        note: &mut nostr_minions::nostro2::NostrNote,
+    ) -> Result<(), String> {
+        let mut unsigned = serde_json::to_value(&*note).map_err(|e| e.to_string())?;
+        if let Some(fields) = unsigned.as_object_mut() {
+            fields.remove("id");
+            fields.remove("sig");
+            fields.insert(
+                "pubkey".to_string(),
+                self.session.user_pubkey.clone().into(),
+            );
+        }
+        let signed = self
+            .request("sign_event", vec![unsigned.to_string()])
+            .await?;
+        let signed: nostr_minions::nostro2::NostrNote =
+            serde_json::from_str(&signed).map_err(|e| e.to_string())?;
+        if signed.pubkey != self.session.user_pubkey {
+            return Err("The remote signer signed with a different key".to_string());
+        }
+        *note = signed;
+        Ok(())
+    }
+
+    /// # Errors
+    /// Returns an error if the signer refuses to encrypt.
+    pub async fn nip44_encrypt(
+        &self,
+        peer_pubkey: &str,
+        plaintext: &str,
+    ) -> Result<String, String> {
+        self.request(
+            "nip44_encrypt",
+            vec![peer_pubkey.to_string(), plaintext.to_string()],
+        )
+        .await
+    }
+
+    /// # Errors
+    /// Returns an error if the signer refuses or cannot decrypt.
+    pub async fn nip44_decrypt(
+        &self,
+        peer_pubkey: &str,
+        ciphertext: &str,
+    ) -> Result<String, String> {
+        self.request(
+            "nip44_decrypt",
+            vec![peer_pubkey.to_string(), ciphertext.to_string()],
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr_minions::nostro2_signer::keypair::NostrKeypair;
+    use wasm_bindgen_test::wasm_bindgen_test;
+    use web_sys::wasm_bindgen::{closure::Closure, JsValue};
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    const RELAY: &str = "wss://signer.example";
+
+    fn run_js(body: &str) {
+        web_sys::js_sys::Function::new_no_args(body)
+            .call0(&JsValue::NULL)
+            .expect("stand-in installs");
+    }
+
+    /// A response note from `signer` to `client`, as a relay would deliver it.
+    fn response_note(
+        signer: &NostrKeypair,
+        client: &str,
+        response: &serde_json::Value,
+    ) -> nostr_minions::nostro2::NostrNote {
+        let mut note = crate::with_tag(
+            nostr_minions::nostro2::NostrNote {
+                kind: NIP46_KIND,
+                pubkey: signer.public_key(),
+                content: signer
+                    .nip_44_encrypt(&response.to_string(), client)
+                    .expect("signer encrypts")
+                    .to_string(),
+                ..Default::default()
+            },
+            &["p", client],
+        );
+        signer.sign_note(&mut note).expect("signer signs");
+        note
+    }
+
+    /// Answers a request the way a remote signer holding `user` would.
+    fn answer(user: &NostrKeypair, method: &str, params: &[String]) -> String {
+        match method {
+            "connect" => "ack".to_string(),
+            "get_public_key" => user.public_key(),
+            "sign_event" => {
+                let mut note =
+                    serde_json::from_str::<nostr_minions::nostro2::NostrNote>(&params[0])
+                        .expect("request carries an event");
+                user.sign_note(&mut note).expect("user key signs");
+                serde_json::to_string(&note).unwrap_or_default()
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// Replaces `WebSocket` with relays that hand every published request to a remote
+    /// signer holding `user`, reachable as `bunker`.
+    fn install_signer_relay(bunker: &NostrKeypair, user: &NostrKeypair) {
+        run_js(
+            "window.FakeRelay = class extends EventTarget { \
+                constructor(url) { \
+                    super(); \
+                    this.url = url; \
+                    this.readyState = 0; \
+                    setTimeout(() => { this.readyState = 1; this.dispatchEvent(new Event('open')); }, 0); \
+                } \
+                send(data) { window.onRelaySend(this, data); } \
+                close() { this.readyState = 3; } \
+            }; \
+            window.WebSocket = window.FakeRelay; \
+            window.Toastify = () => ({ showToast() {} });",
+        );
+        let bunker = bunker.clone();
+        let user = user.clone();
+        let on_send =
+            Closure::<dyn Fn(JsValue, String)>::new(move |relay: JsValue, data: String| {
+                let Ok(serde_json::Value::Array(message)) = serde_json::from_str(&data) else {
+                    return;
+                };
+                let [kind, note] = message.as_slice() else {
+                    return;
+                };
+                if kind != "EVENT" {
+                    return;
+                }
+                let Ok(request) =
+                    serde_json::from_value::<nostr_minions::nostro2::NostrNote>(note.clone())
+                else {
+                    return;
+                };
+                let Ok(body) = bunker.nip_44_decrypt(&request.content, &request.pubkey) else {
+                    return;
+                };
+                let body: serde_json::Value =
+                    serde_json::from_str(&body.to_string()).unwrap_or_default();
+                let params = serde_json::from_value::<Vec<String>>(body["params"].clone())
+                    .unwrap_or_default();
+                let result = answer(&user, body["method"].as_str().unwrap_or_default(), &params);
+                let response = response_note(
+                    &bunker,
+                    &request.pubkey,
+                    &serde_json::json!({ "id": body["id"], "result": result }),
+                );
+                let event = serde_json::json!(["EVENT", "signer", response]).to_string();
+                let deliver = web_sys::js_sys::Function::new_with_args(
+                "relay, data",
+                "setTimeout(() => relay.dispatchEvent(new MessageEvent('message', { data })), 0);",
+            );
+                let _ = deliver.call2(&JsValue::NULL, &relay, &event.into());
+            });
+        web_sys::js_sys::Reflect::set(
+            &web_sys::window().expect("tests run in a browser").into(),
+            &"onRelaySend".into(),
+            on_send.as_ref(),
+        )
+        .expect("stand-in installs");
+        on_send.forget();
+    }
+
+    #[wasm_bindgen_test]
+    fn parses_bunker_uris() {
+        let pubkey = NostrKeypair::generate(false).public_key();
+        let uri = format!(
+            "bunker://{}?relay=wss%3A%2F%2Frelay.one%2F&relay=wss://relay.two&secret=s3cr3t",
+            pubkey.to_uppercase()
+        );
+        assert_eq!(
+            uri.parse::<BunkerUri>(),
+            Ok(BunkerUri {
+                remote_pubkey: pubkey.clone(),
+                relays: vec!["wss://relay.one".to_string(), "wss://relay.two".to_string()],
+                secret: Some("s3cr3t".to_string()),
+            })
+        );
+        assert!(format!("nostrconnect://{pubkey}?relay=wss://relay.one")
+            .parse::<BunkerUri>()
+            .is_err());
+        assert!("bunker://abc?relay=wss://relay.one"
+            .parse::<BunkerUri>()
+            .is_err());
+        assert!(format!("bunker://{pubkey}").parse::<BunkerUri>().is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn builds_nostrconnect_uris() {
+        let uri = nostrconnect_uri(
+            "abcd",
+            &[
+                "wss://relay.one".to_string(),
+                "wss://relay.two/x?y".to_string(),
+            ],
+            "s3cr3t",
+        );
+        assert_eq!(
+            uri,
+            "nostrconnect://abcd?relay=wss%3A%2F%2Frelay.one&relay=wss%3A%2F%2Frelay.two%2Fx%3Fy&secret=s3cr3t&name=Bunker"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn receives_only_responses_for_its_clients() {
+        let inbox = Nip46Inbox::default();
+        let client = NostrKeypair::generate(false);
+        let signer = NostrKeypair::generate(false);
+        inbox.listen(&[], &client);
+
+        let response = serde_json::json!({ "id": "1", "result": "ack" });
+        inbox.receive(&response_note(
+            &signer,
+            &NostrKeypair::generate(false).public_key(),
+            &response,
+        ));
+        assert!(inbox.responses.borrow().is_empty());
+
+        inbox.receive(&response_note(&signer, &client.public_key(), &response));
+        assert_eq!(inbox.wait_for("1").await, Ok("ack".to_string()));
+    }
+
+    #[wasm_bindgen_test]
+    async fn waits_past_auth_challenges() {
+        run_js("window.Toastify = () => ({ showToast() {} }); window.open = () => null;");
+        let inbox = Nip46Inbox::default();
+        let client = NostrKeypair::generate(false);
+        let signer = NostrKeypair::generate(false);
+        inbox.listen(&[], &client);
+        inbox.receive(&response_note(
+            &signer,
+            &client.public_key(),
+            &serde_json::json!({ "id": "2", "result": "auth_url", "error": "https://signer.example/approve" }),
+        ));
+        let approved = {
+            let inbox = inbox.clone();
+            let pubkey = client.public_key();
+            async move {
+                gloo::timers::future::TimeoutFuture::new(300).await;
+                inbox.receive(&response_note(
+                    &signer,
+                    &pubkey,
+                    &serde_json::json!({ "id": "2", "result": "signed" }),
+                ));
+            }
+        };
+        // The challenge pushes the deadline past the short request timeout.
+        let (result, ()) =
+            futures_util::future::join(inbox.wait_for_within("2", 100.0), approved).await;
+        assert_eq!(result, Ok("signed".to_string()));
+    }
+
+    #[wasm_bindgen_test]
+    async fn gives_up_on_silent_signers() {
+        let inbox = Nip46Inbox::default();
+        assert!(inbox.wait_for_within("3", 200.0).await.is_err());
+
+        let cancel = crate::imports::CancelToken::default();
+        cancel.cancel();
+        assert!(inbox.wait_for_connect("secret", &cancel).await.is_err());
+    }
+
+    #[wasm_bindgen_test]
+    async fn signs_through_a_stand_in_signer() {
+        let bunker = NostrKeypair::generate(false);
+        let user = NostrKeypair::generate(false);
+        install_signer_relay(&bunker, &user);
+        let inbox = Nip46Inbox::default();
+        let uri = format!("bunker://{}?relay={RELAY}", bunker.public_key())
+            .parse::<BunkerUri>()
+            .expect("valid uri");
+
+        let session = RemoteSigner::connect(&uri, inbox.clone())
+            .await
+            .expect("stand-in connects");
+        assert_eq!(session.user_pubkey, user.public_key());
+        assert_eq!(session.relays, vec![RELAY.to_string()]);
+
+        let signer = RemoteSigner::new(session, inbox.clone()).expect("client key parses");
+        let mut note = nostr_minions::nostro2::NostrNote {
+            kind: 1,
+            content: "1. e4".to_string(),
+            ..Default::default()
+        };
+        signer.sign_note(&mut note).await.expect("stand-in signs");
+        assert_eq!(note.pubkey, user.public_key());
+        assert!(note.verify());
+        inbox.close();
+    }
+}
@@ -0,0 +1,173 @@
+//! NIP-49 private key encryption: a secret key sealed with a passphrase as an `ncryptsec` string.
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use unicode_normalization::UnicodeNormalization;
+
+const VERSION: u8 = 0x02;
+/// scrypt cost as a power of two; 2^16 takes well under a second in a browser.
+pub const DEFAULT_LOG_N: u8 = 16;
+/// Highest cost accepted from an `ncryptsec`. scrypt needs 1 KiB per unit of cost, so 2^20
+/// takes 1 GiB and anything above it would abort the wasm instance instead of failing.
+const MAX_LOG_N: u8 = 20;
+/// The key has never been known to be handled insecurely.
+const KEY_SECURITY_SECURE: u8 = 0x01;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const SECRET_LEN: usize = 32;
+const PAYLOAD_LEN: usize = 1 + 1 + SALT_LEN + NONCE_LEN + 1 + SECRET_LEN + 16;
+
+fn symmetric_key(passphrase: &str, salt: &[u8], log_n: u8) -> Result<[u8; 32], String> {
+    let passphrase = passphrase.nfkc().collect::<String>();
+    let params = scrypt::Params::new(log_n, 8, 1, 32).map_err(|_| "Invalid scrypt cost")?;
+    let mut key = [0u8; 32];
+    scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key)
+        .map_err(|_| "Failed to derive the encryption key")?;
+    Ok(key)
+}
+
+fn nsec_to_secret(nsec: &str) -> Result<[u8; SECRET_LEN], String> {
+    let (hrp, data) = bech32::decode(nsec).map_err(|_| "Invalid nsec")?;
+    if hrp.as_str() != "nsec" {
+        return Err("Invalid nsec".to_string());
+    }
+    data.try_into().map_err(|_| "Invalid nsec".to_string())
+}
+
+pub(crate) fn secret_to_nsec(secret: &[u8]) -> Result<String, String> {
+    let hrp = bech32::Hrp::parse("nsec").map_err(|e| e.to_string())?;
+    bech32::encode::<bech32::Bech32>(hrp, secret).map_err(|e| e.to_string())
+}
+
+/// Encrypts a 32 byte secret key with `passphrase`.
+///
+/// # Errors
+/// Returns an error if randomness or key derivation is unavailable.
+pub fn encrypt_secret(
+    secret: &[u8; SECRET_LEN],
+    passphrase: &str,
+    log_n: u8,
+) -> Result<String, String> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    getrandom::getrandom(&mut salt).map_err(|e| e.to_string())?;
+    getrandom::getrandom(&mut nonce).map_err(|e| e.to_string())?;
+    let key = symmetric_key(passphrase, &salt, log_n)?;
+    let cipher = chacha20poly1305::XChaCha20Poly1305::new((&key).into());
+    let ciphertext = cipher
+        .encrypt(
+            (&nonce).into(),
+            Payload {
+                msg: secret,
+                aad: &[KEY_SECURITY_SECURE],
+            },
+        )
+        .map_err(|_| "Failed to encrypt the key")?;
+
+    let mut payload = Vec::with_capacity(PAYLOAD_LEN);
+    payload.push(VERSION);
+    payload.push(log_n);
+    payload.extend_from_slice(&salt);
+    payload.extend_from_slice(&nonce);
+    payload.push(KEY_SECURITY_SECURE);
+    payload.extend_from_slice(&ciphertext);
+    let hrp = bech32::Hrp::parse("ncryptsec").map_err(|e| e.to_string())?;
+    bech32::encode::<bech32::Bech32>(hrp, &payload).map_err(|e| e.to_string())
+}
+
+/// Recovers the 32 byte secret key sealed in `ncryptsec`.
+///
+/// # Errors
+/// Returns an error if the string is malformed or the passphrase is wrong.
+pub fn decrypt_secret(ncryptsec: &str, passphrase: &str) -> Result<[u8; SECRET_LEN], String> {
+    let (hrp, payload) = bech32::decode(ncryptsec.trim()).map_err(|_| "Invalid ncryptsec")?;
+    if hrp.as_str() != "ncryptsec" || payload.len() != PAYLOAD_LEN {
+        return Err("Invalid ncryptsec".to_string());
+    }
+    if payload[0] != VERSION {
+        return Err("Unsupported ncryptsec version".to_string());
+    }
+    let log_n = payload[1];
+    if log_n > MAX_LOG_N {
+        return Err("Unsupported ncryptsec cost".to_string());
+    }
+    let salt = &payload[2..2 + SALT_LEN];
+    let nonce = &payload[2 + SALT_LEN..2 + SALT_LEN + NONCE_LEN];
+    let key_security = payload[2 + SALT_LEN + NONCE_LEN];
+    let ciphertext = &payload[3 + SALT_LEN + NONCE_LEN..];
+    let key = symmetric_key(passphrase, salt, log_n)?;
+    let cipher = chacha20poly1305::XChaCha20Poly1305::new((&key).into());
+    let secret = cipher
+        .decrypt(
+            chacha20poly1305::XNonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: &[key_security],
+            },
+        )
+        .map_err(|_| "Wrong passphrase")?;
+    secret
+        .try_into()
+        .map_err(|_| "Invalid ncryptsec".to_string())
+}
+
+/// Encrypts an extractable keypair as an `ncryptsec` string.
+///
+/// # Errors
+/// Returns an error if the key cannot be extracted or encrypted.
+pub fn encrypt_keypair(
+    keypair: &nostr_minions::nostro2_signer::keypair::NostrKeypair,
+    passphrase: &str,
+) -> Result<String, String> {
+    let nsec = keypair.nsec().map_err(|_| "The key cannot be exported")?;
+    encrypt_secret(&nsec_to_secret(&nsec)?, passphrase, DEFAULT_LOG_N)
+}
+
+/// Decrypts an `ncryptsec` string into an extractable keypair.
+///
+/// # Errors
+/// Returns an error if the string is malformed or the passphrase is wrong.
+pub fn decrypt_keypair(
+    ncryptsec: &str,
+    passphrase: &str,
+) -> Result<nostr_minions::nostro2_signer::keypair::NostrKeypair, String> {
+    let secret = decrypt_secret(ncryptsec, passphrase)?;
+    let mut keypair = secret_to_nsec(&secret)?
+        .parse::<nostr_minions::nostro2_signer::keypair::NostrKeypair>()
+        .map_err(|_| "Invalid secret key")?;
+    keypair.set_extractable(true);
+    Ok(keypair)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The test vector from NIP-49, encrypted with `log_n` 16.
+    const SPEC_NCRYPTSEC: &str = "ncryptsec1qgg9947rlpvqu76pj5ecreduf9jxhselq2nae2kghhvd5g7dgjtcxfqtd67p9m0w57lspw8gsq6yphnm8623nsl8xn9j4jdzz84zm3frztj3z7s35vpzmqf6ksu8r89qk5z2zxfmu5gv8th8wclt0h4p";
+
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    #[test]
+    fn decrypts_the_spec_vector() {
+        let secret = decrypt_secret(SPEC_NCRYPTSEC, "nostr").expect("spec vector decrypts");
+        assert_eq!(
+            to_hex(&secret),
+            "3501454135014541350145413501453fefb02227e449e57cf4d3a3ce05378683"
+        );
+        assert!(decrypt_secret(SPEC_NCRYPTSEC, "nostr2").is_err());
+    }
+
+    #[test]
+    fn rejects_costs_above_the_limit() {
+        let (hrp, mut payload) = bech32::decode(SPEC_NCRYPTSEC).expect("spec vector decodes");
+        assert_eq!(payload[1], 16);
+        payload[1] = MAX_LOG_N + 1;
+        let tampered = bech32::encode::<bech32::Bech32>(hrp, &payload).expect("re-encodes");
+        assert_eq!(
+            decrypt_secret(&tampered, "nostr"),
+            Err("Unsupported ncryptsec cost".to_string())
+        );
+    }
+}
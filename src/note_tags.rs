@@ -0,0 +1,32 @@
+//! Helpers for reading and writing NIP-01 tags on notes.
+
+/// Returns the first value of every tag named `name`.
+#[must_use]
+pub fn tag_values(note: &nostr_minions::nostro2::NostrNote, name: &str) -> Vec<String> {
+    tags_named(note, name)
+        .filter_map(|tag| tag.get(1).cloned())
+        .collect()
+}
+
+/// Returns every tag named `name`, including the name itself as the first element.
+pub fn tags_named<'a>(
+    note: &'a nostr_minions::nostro2::NostrNote,
+    name: &'a str,
+) -> impl Iterator<Item = &'a Vec<String>> + 'a {
+    note.tags
+        .0
+        .iter()
+        .filter(move |tag| tag.first().map(String::as_str) == Some(name))
+}
+
+/// Appends a tag to an unsigned note.
+#[must_use]
+pub fn with_tag(
+    mut note: nostr_minions::nostro2::NostrNote,
+    tag: &[&str],
+) -> nostr_minions::nostro2::NostrNote {
+    note.tags
+        .0
+        .push(tag.iter().map(|s| (*s).to_string()).collect());
+    note
+}
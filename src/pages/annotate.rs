@@ -22,6 +22,20 @@ pub fn js_chess_game() -> Html {
 
     let force_update = use_state(|| 0);
     let force_update_cb = { Callback::from(move |()| force_update.set(*force_update + 1)) };
+    // Once the game is saved, its card can edit the saved entry's labels.
+    let fingerprint =
+        (!pgn_game.borrow().moves.is_empty()).then(|| crate::game_fingerprint(&pgn_game.borrow()));
+    let saved_id = use_memo(
+        (game_ctx.rooky_game_entries(), fingerprint),
+        |(entries, fingerprint)| {
+            let fingerprint = fingerprint.as_ref()?;
+            entries
+                .iter()
+                .filter(|entry| entry.origin == rooky_core::idb::GameOrigin::Annotated)
+                .find(|entry| crate::note_fingerprint(&entry.note).as_ref() == Some(fingerprint))
+                .map(|entry| entry.id.clone())
+        },
+    );
 
     let position = game_position.clone();
     let on_snap_start = Box::new(
@@ -164,7 +178,9 @@ pub fn js_chess_game() -> Html {
                         </CardTitle>
                     </CardHeader>
                     <CardContent>
-                        <crate::components::GameCard pgn_game={pgn_game.borrow().clone()}  />
+                        <crate::components::GameCard
+                            pgn_game={pgn_game.borrow().clone()}
+                            game_id={(*saved_id).clone()} />
                         <ShareGameModal pgn_game={pgn_game.borrow().clone()} />
                     </CardContent>
                 </Card>
@@ -1,3 +1,7 @@
+use nostr_minions::browser_api::IdbStoreManager;
+use shady_minions::ui::{
+    Button, ButtonVariant, Card, CardContent, CardHeader, CardTitle, Input, Modal,
+};
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq, Clone)]
@@ -9,21 +13,38 @@ pub struct GameDetailPageProps {
 pub fn game_detail_page(props: &GameDetailPageProps) -> Html {
     let game_ctx = crate::live_game::use_game_history();
     let language_ctx = crate::contexts::language::use_language_ctx();
-    let game = use_memo((game_ctx.synced, props.id.clone()), |(synced, id)| {
-        synced.then(|| {
-            game_ctx
-                .rooky_game_entries()
-                .iter()
-                .find(|game| game.note.id.as_ref() == Some(id))
-                .map(rooky_core::RookyGame::from)
-        })
-    });
+    let entry = use_memo(
+        (
+            game_ctx.synced,
+            props.id.clone(),
+            game_ctx.rooky_game_entries(),
+        ),
+        |(synced, id, entries)| {
+            synced
+                .then(|| entries.iter().find(|game| &game.id == id).cloned())
+                .flatten()
+        },
+    );
 
     html! {
         <div class="pl-12 h-full flex flex-col justify-evenly">
-            <h2 class="text-4xl text-white font-black">{language_ctx.t("common_game_details")}</h2>
+            <div class="flex items-center gap-4">
+                <h2 class="text-4xl text-white font-black">{language_ctx.t("common_game_details")}</h2>
+                { if let Some(entry) = (*entry).clone() {
+                    html! {
+                        <>
+                            <EditGameModal entry={entry.clone()} />
+                            <DeleteGameButton entry={entry.clone()} />
+                            <GameRevisions {entry} />
+                        </>
+                    }
+                } else {
+                    html! {}
+                }}
+            </div>
             <div class="flex justify-evenly gap-6">
-                { if let Some(game) = (*game).clone().flatten() {
+                { if let Some(entry) = (*entry).clone() {
+                    let game = rooky_core::RookyGame::from(&entry);
                     html! { <crate::JsChessGame {game} /> }
                 } else {
                     html! { <p>{language_ctx.t("common_loading")}</p> }
@@ -32,3 +53,371 @@ pub fn game_detail_page(props: &GameDetailPageProps) -> Html {
         </div>
     }
 }
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct GameEntryProps {
+    pub entry: rooky_core::idb::RookyGameEntry,
+}
+
+#[function_component(EditGameModal)]
+pub fn edit_game_modal(props: &GameEntryProps) -> Html {
+    let is_open = use_state(|| false);
+    let close_modal = {
+        let is_open = is_open.clone();
+        Callback::from(move |()| is_open.set(false))
+    };
+    html! {
+        <>
+            <Button
+                class="p-4"
+                size={shady_minions::ui::ButtonSize::Small}
+                onclick={
+                    let is_open = is_open.clone();
+                    Callback::from(move |_| {
+                    is_open.set(!&*is_open);
+                })}>
+                <lucide_yew::SquarePen class="size-6" />
+            </Button>
+            <Modal is_open={is_open.clone()}>
+                {if *is_open {
+                    html! { <GameEditor entry={props.entry.clone()} on_close={close_modal} /> }
+                } else {
+                    html! {}
+                }}
+            </Modal>
+        </>
+    }
+}
+
+#[function_component(DeleteGameButton)]
+pub fn delete_game_button(props: &GameEntryProps) -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let relay_ctx = nostr_minions::relay_pool::use_nostr_relay_pool();
+    let outbox = crate::outbox::use_outbox();
+    let game_ctx = crate::live_game::use_game_history();
+    let keypair = crate::signer::use_signer();
+    let navigator = yew_router::hooks::use_navigator();
+    let onclick = {
+        let entry = props.entry.clone();
+        let lang_ctx = language_ctx.clone();
+        Callback::from(move |_| {
+            if let Some(true) = web_sys::window().and_then(|win| {
+                win.confirm_with_message(&lang_ctx.t("game_delete_confirm"))
+                    .ok()
+            }) {
+                crate::live_game::delete_games(
+                    keypair.as_ref(),
+                    &outbox,
+                    &relay_ctx,
+                    &game_ctx,
+                    vec![entry.clone()],
+                );
+                if let Some(navigator) = navigator.as_ref() {
+                    navigator.push(&crate::router::MainRoute::MyGames);
+                }
+            }
+        })
+    };
+    html! {
+        <Button
+            class="p-4"
+            size={shady_minions::ui::ButtonSize::Small}
+            variant={ButtonVariant::Destructive}
+            {onclick}>
+            <lucide_yew::Trash2 class="size-6" />
+        </Button>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct GameEditorProps {
+    pub entry: rooky_core::idb::RookyGameEntry,
+    pub on_close: Callback<()>,
+}
+
+#[function_component(GameEditor)]
+pub fn game_editor(props: &GameEditorProps) -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let relay_ctx = nostr_minions::relay_pool::use_nostr_relay_pool();
+    let outbox = crate::outbox::use_outbox();
+    let game_ctx = crate::live_game::use_game_history();
+    let backups = crate::backup::use_game_backups();
+    let keypair = crate::signer::use_signer();
+    let pgn_game = use_mut_ref(|| rooky_core::RookyGame::from(&props.entry));
+    let comments = use_mut_ref(|| {
+        let mut comments = crate::mainline_comments(&props.entry.note.content);
+        comments.resize(pgn_game.borrow().moves.len() + 1, String::new());
+        comments
+    });
+    let move_input = use_state(String::new);
+    let move_error = use_state(|| false);
+
+    let force_update = use_state(|| 0);
+    let force_update_cb = { Callback::from(move |()| force_update.set(*force_update + 1)) };
+
+    let add_move = {
+        let pgn_game = pgn_game.clone();
+        let comments = comments.clone();
+        let move_input = move_input.clone();
+        let move_error = move_error.clone();
+        let update_ui = force_update_cb.clone();
+        Callback::from(move |_| {
+            let position = pgn_game.borrow().game_positions().pop().unwrap_or_default();
+            let Some((san_plus, played)) = move_input
+                .trim()
+                .parse::<shakmaty::san::San>()
+                .ok()
+                .and_then(|san| san.to_move(&position).ok())
+                .and_then(|chess_move| {
+                    let played = shakmaty::Position::play(position.clone(), &chess_move).ok()?;
+                    let san_plus = shakmaty::san::SanPlus::from_move(position, &chess_move);
+                    Some((san_plus, played))
+                })
+            else {
+                move_error.set(true);
+                return;
+            };
+            let mut game = pgn_game.borrow_mut();
+            game.moves.push(san_plus);
+            if let Some(outcome) = shakmaty::Position::outcome(&played) {
+                game.outcome = outcome;
+            }
+            comments.borrow_mut().push(String::new());
+            move_error.set(false);
+            move_input.set(String::new());
+            update_ui.emit(());
+        })
+    };
+
+    let undo_move = {
+        let pgn_game = pgn_game.clone();
+        let comments = comments.clone();
+        let update_ui = force_update_cb.clone();
+        Callback::from(move |_| {
+            if pgn_game.borrow_mut().moves.pop().is_some() {
+                comments.borrow_mut().pop();
+                update_ui.emit(());
+            }
+        })
+    };
+
+    let save_revision = {
+        let pgn_game = pgn_game.clone();
+        let comments = comments.clone();
+        let entry = props.entry.clone();
+        let on_close = props.on_close.clone();
+        let game_ctx = game_ctx.dispatcher();
+        Callback::from(move |_| {
+            let Some(keypair) = keypair.clone() else {
+                nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
+                    "No Nostr keypair found",
+                )
+                .show();
+                return;
+            };
+            let entry = entry.clone();
+            let game = pgn_game.borrow().clone();
+            let comments = comments.borrow().clone();
+            let outbox = outbox.clone();
+            let relay_ctx = relay_ctx.clone();
+            let game_ctx = game_ctx.clone();
+            let backups = backups.clone();
+            yew::platform::spawn_local(async move {
+                let Ok(revised) =
+                    crate::live_game::revise_game(&keypair, &entry, game, &comments).await
+                else {
+                    nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
+                        "Failed to sign note",
+                    )
+                    .show();
+                    return;
+                };
+                // Only games the user annotated are republished; edits to other games stay local.
+                if entry.origin == rooky_core::idb::GameOrigin::Annotated {
+                    crate::outbox::publish_note(&outbox, &relay_ctx, revised.note.clone());
+                }
+                game_ctx.dispatch(crate::live_game::AnnotatedGameHistoryAction::AddGame(
+                    revised.clone(),
+                ));
+                let previous = crate::live_game::GameRevision::from_entry(&entry);
+                if previous.save_to_store().await.is_err() {
+                    web_sys::console::error_1(&"Failed to save game revision".into());
+                }
+                if revised.save_to_store().await.is_err() {
+                    nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
+                        "Failed to save game",
+                    )
+                    .show();
+                    return;
+                }
+                // The backup on other devices still holds the previous revision.
+                crate::backup::mark_outdated(&backups, vec![entry.id.clone()]);
+                nostr_minions::widgets::toastify::ToastifyOptions::new_success(
+                    "Game updated successfully",
+                )
+                .show();
+            });
+            on_close.emit(());
+        })
+    };
+
+    let move_labels = pgn_game
+        .borrow()
+        .moves
+        .iter()
+        .enumerate()
+        .map(|(ply, san_plus)| {
+            let number = ply / 2 + 1;
+            if ply % 2 == 0 {
+                format!("{number}. {san_plus}")
+            } else {
+                format!("{number}... {san_plus}")
+            }
+        })
+        .collect::<Vec<_>>();
+    let comment_rows = std::iter::once(language_ctx.t("edit_game_opening_comment"))
+        .chain(move_labels)
+        .enumerate()
+        .map(|(ply, label)| {
+            let value = comments.borrow().get(ply).cloned().unwrap_or_default();
+            let comments = comments.clone();
+            html! {
+                <div class="flex items-center gap-2">
+                    <span class="w-24 shrink-0 text-sm font-semibold text-white">{label}</span>
+                    <Input
+                        r#type={shady_minions::ui::InputType::Text}
+                        placeholder={language_ctx.t("edit_game_comment_placeholder")}
+                        {value}
+                        class="w-full"
+                        onchange={Callback::from(move |comment: String| {
+                            if let Some(slot) = comments.borrow_mut().get_mut(ply) {
+                                *slot = comment;
+                            }
+                        })}
+                    />
+                </div>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <Card class="h-fit min-w-sm max-h-[86vh] overflow-hidden">
+            <CardHeader>
+                <CardTitle class="mb-8">
+                    <div class="flex justify-between items-top gap-4">
+                        <h3 class="text-2xl font-bold text-white">
+                            { language_ctx.t("edit_game_info") }
+                        </h3>
+                        <crate::pages::GameDetailsModal pgn_game={pgn_game.clone()} on_update={force_update_cb} />
+                    </div>
+                </CardTitle>
+            </CardHeader>
+            <CardContent class="flex flex-col gap-2">
+                <crate::components::GameCard
+                    pgn_game={pgn_game.borrow().clone()}
+                    game_id={props.entry.id.clone()}
+                    author={(props.entry.origin != rooky_core::idb::GameOrigin::Annotated)
+                        .then(|| props.entry.note.pubkey.clone())} />
+                <div class="flex items-center gap-2">
+                    <Input
+                        r#type={shady_minions::ui::InputType::Text}
+                        placeholder={language_ctx.t("edit_game_move_placeholder")}
+                        value={(*move_input).clone()}
+                        class="w-full"
+                        onchange={{
+                            let move_input = move_input.clone();
+                            Callback::from(move |value: String| move_input.set(value))
+                        }}
+                    />
+                    <Button variant={ButtonVariant::Outline} onclick={add_move}>
+                        <lucide_yew::Plus class="size-5" />
+                        <span class="ml-2">{ language_ctx.t("edit_game_add_move") }</span>
+                    </Button>
+                </div>
+                {if *move_error {
+                    html! { <p class="text-sm text-red-400">{ language_ctx.t("edit_game_illegal_move") }</p> }
+                } else {
+                    html! {}
+                }}
+                <Button
+                    variant={ButtonVariant::Outline}
+                    onclick={undo_move}
+                    disabled={pgn_game.borrow().moves.is_empty()}
+                >
+                    <lucide_yew::Undo2 class="size-5" />
+                    <span class="ml-2">{ language_ctx.t("edit_game_undo_move") }</span>
+                </Button>
+                <div class="flex flex-col gap-1 max-h-64 overflow-y-auto">
+                    {comment_rows}
+                </div>
+                <Button onclick={save_revision}>
+                    <lucide_yew::Save class="size-5" />
+                    <span class="ml-2">{ language_ctx.t("edit_game_save_revision") }</span>
+                </Button>
+            </CardContent>
+        </Card>
+    }
+}
+
+#[function_component(GameRevisions)]
+pub fn game_revisions(props: &GameEntryProps) -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let revisions = use_state(Vec::<crate::live_game::GameRevision>::new);
+    {
+        let revisions = revisions.clone();
+        use_effect_with(props.entry.clone(), move |entry| {
+            let game_id = entry.id.clone();
+            yew::platform::spawn_local(async move {
+                match crate::live_game::GameRevision::for_game(&game_id).await {
+                    Ok(found) => revisions.set(found),
+                    Err(_) => web_sys::console::error_1(&"Failed to load revisions".into()),
+                }
+            });
+            || {}
+        });
+    }
+    if revisions.is_empty() {
+        return html! {};
+    }
+    html! {
+        <shady_minions::ui::Popover>
+            <shady_minions::ui::PopoverTrigger>
+                <div class="flex items-center gap-2 text-white hover:text-secondary">
+                    <lucide_yew::History class="size-6" />
+                    <span>{format!("{} {}", revisions.len(), language_ctx.t("edit_game_revisions"))}</span>
+                </div>
+            </shady_minions::ui::PopoverTrigger>
+            <shady_minions::ui::PopoverContent class="bg-background text-white p-2 rounded-lg shadow-lg">
+                <div class="flex flex-col gap-2 max-h-96 overflow-y-auto">
+                    {for revisions.iter().map(|revision| {
+                        let game = rooky_core::RookyGame::try_from(revision.note.clone()).ok();
+                        let revised_at = chrono::DateTime::from_timestamp_millis(revision.revised_at as i64)
+                            .map(|date| date.format("%Y-%m-%d %H:%M").to_string())
+                            .unwrap_or_default();
+                        html! {
+                            <div class="p-2 border border-border rounded-lg">
+                                <p class="font-semibold">{revised_at}</p>
+                                {if let Some(game) = game {
+                                    html! {
+                                        <p class="text-sm text-gray-300">
+                                            {format!(
+                                                "{} {} {} · {} {}",
+                                                game.white,
+                                                language_ctx.t("common_versus"),
+                                                game.black,
+                                                game.moves.len(),
+                                                language_ctx.t("edit_game_plies"),
+                                            )}
+                                        </p>
+                                    }
+                                } else {
+                                    html! {}
+                                }}
+                            </div>
+                        }
+                    })}
+                </div>
+            </shady_minions::ui::PopoverContent>
+        </shady_minions::ui::Popover>
+    }
+}
@@ -0,0 +1,376 @@
+use nostr_minions::browser_api::IdbStoreManager;
+use shady_minions::ui::{
+    Button, ButtonType, ButtonVariant, Card, CardContent, CardDescription, CardHeader, CardTitle,
+    Form, Input, Label,
+};
+use web_sys::wasm_bindgen::JsCast;
+use yew::prelude::*;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum FeedSource {
+    Follows,
+    Custom {
+        pubkeys: Vec<String>,
+        hashtags: Vec<String>,
+    },
+}
+
+#[function_component(FeedPage)]
+pub fn feed_page() -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let relay_ctx = nostr_minions::relay_pool::use_nostr_relay_pool();
+    let user_id = crate::signer::use_signer();
+    let source = use_state(|| FeedSource::Follows);
+    let follows = use_state(Vec::<String>::new);
+    let feed_notes = use_state(Vec::<nostr_minions::nostro2::NostrNote>::new);
+    let game_ctx = crate::live_game::use_game_history();
+    let known = use_memo(game_ctx.rooky_game_entries(), |entries| {
+        crate::known_fingerprints(entries)
+    });
+
+    {
+        let relay_ctx = relay_ctx.clone();
+        use_effect_with(user_id.clone(), move |user_id| {
+            if let Some(pubkey) = user_id.as_ref().map(|u| u.public_key()) {
+                let contacts_filter = nostr_minions::nostro2::NostrSubscription {
+                    kinds: vec![3].into(),
+                    authors: vec![pubkey].into(),
+                    limit: Some(1),
+                    ..Default::default()
+                };
+                relay_ctx.send(contacts_filter);
+            }
+            || {}
+        });
+    }
+    {
+        let profile_cache = crate::profiles::use_profile_cache();
+        use_effect_with((*follows).clone(), move |follows| {
+            // Contacts are named in the feed, so fetch their profiles in one batch.
+            for pubkey in follows {
+                profile_cache.request(pubkey);
+            }
+            || {}
+        });
+    }
+    {
+        let relay_ctx = relay_ctx.clone();
+        let feed_notes = feed_notes.clone();
+        use_effect_with(
+            ((*source).clone(), (*follows).clone()),
+            move |(source, follows)| {
+                feed_notes.set(vec![]);
+                let game_filter = || nostr_minions::nostro2::NostrSubscription {
+                    kinds: vec![
+                        crate::live_game::rooky_game_kind(),
+                        crate::live_game::replaceable_game_kind(),
+                    ]
+                    .into(),
+                    limit: Some(100),
+                    ..Default::default()
+                };
+                // Relays AND the fields of one filter, so authors and hashtags are asked for
+                // separately to match either, as `matches_source` does.
+                let mut filters = vec![];
+                match source {
+                    FeedSource::Follows => {
+                        if !follows.is_empty() {
+                            filters.push(nostr_minions::nostro2::NostrSubscription {
+                                authors: follows.clone().into(),
+                                ..game_filter()
+                            });
+                        }
+                    }
+                    FeedSource::Custom { pubkeys, hashtags } => {
+                        if !pubkeys.is_empty() {
+                            filters.push(nostr_minions::nostro2::NostrSubscription {
+                                authors: pubkeys.clone().into(),
+                                ..game_filter()
+                            });
+                        }
+                        if !hashtags.is_empty() {
+                            let mut hashtag_filter = game_filter();
+                            for hashtag in hashtags {
+                                hashtag_filter.add_tag("#t", hashtag);
+                            }
+                            filters.push(hashtag_filter);
+                        }
+                    }
+                }
+                let subscriptions = filters
+                    .into_iter()
+                    .filter_map(|filter| crate::subscribe(&relay_ctx, filter))
+                    .collect::<Vec<_>>();
+                move || {
+                    for id in subscriptions {
+                        crate::close_subscription(&relay_ctx, &id);
+                    }
+                }
+            },
+        );
+    }
+    {
+        let follows = follows.clone();
+        let feed_notes = feed_notes.clone();
+        let source = source.clone();
+        let cursor = use_mut_ref(|| 0usize);
+        // `created_at` of the contact list in use, so an older one arriving later is ignored.
+        let follows_at = use_mut_ref(|| 0i64);
+        use_effect_with(relay_ctx.unique_notes.clone(), move |notes| {
+            let own_pubkey = user_id
+                .as_ref()
+                .map(crate::signer::BunkerSigner::public_key);
+            let unseen = crate::unseen(&cursor, notes);
+            let contacts = unseen
+                .iter()
+                .filter(|note| note.kind == 3 && Some(&note.pubkey) == own_pubkey.as_ref())
+                .max_by_key(|note| note.created_at)
+                .filter(|note| note.created_at > *follows_at.borrow());
+            let mut current_follows = (*follows).clone();
+            if let Some(contacts) = contacts {
+                *follows_at.borrow_mut() = contacts.created_at;
+                current_follows = crate::tag_values(contacts, "p");
+                follows.set(current_follows.clone());
+            }
+            let mut new_notes = (*feed_notes).clone();
+            for note in unseen {
+                if is_game_kind(note.kind)
+                    && matches_source(note, &source, &current_follows)
+                    && !new_notes.iter().any(|feed_note| feed_note.id == note.id)
+                    && !has_newer_revision(&new_notes, note)
+                    && rooky_core::RookyGame::try_from(note.clone()).is_ok()
+                {
+                    // Edited games replace earlier revisions with the same author and `d` tag.
+                    if let Some(address) = replaceable_address(note) {
+                        new_notes.retain(|feed_note| {
+                            replaceable_address(feed_note).as_ref() != Some(&address)
+                        });
+                    }
+                    new_notes.push(note.clone());
+                }
+            }
+            if new_notes != *feed_notes {
+                new_notes.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+                feed_notes.set(new_notes);
+            }
+            || {}
+        });
+    }
+
+    html! {
+        <div class="h-full flex flex-col p-12 gap-6">
+            <h2 class="text-4xl text-white font-black">{ language_ctx.t("feed_title") }</h2>
+            <div class="flex gap-6 flex-1 min-h-0">
+                <FeedSourcePicker source={source.clone()} follows={follows.len()} />
+                <div class="flex-1 overflow-y-auto grid grid-cols-1 xl:grid-cols-2 gap-4 content-start">
+                    {if feed_notes.is_empty() {
+                        html! {
+                            <div class="text-center py-8 text-muted-foreground col-span-full">
+                                <lucide_yew::Rss class="w-12 h-12 mx-auto mb-2 opacity-50" />
+                                <p>{ language_ctx.t("feed_empty") }</p>
+                            </div>
+                        }
+                    } else {
+                        feed_notes.iter().map(|note| html! {
+                            <FeedItem note={note.clone()} known={known.clone()} />
+                        }).collect::<Html>()
+                    }}
+                </div>
+            </div>
+        </div>
+    }
+}
+
+fn is_game_kind(kind: u32) -> bool {
+    kind == crate::live_game::rooky_game_kind() || kind == crate::live_game::replaceable_game_kind()
+}
+
+fn replaceable_address(note: &nostr_minions::nostro2::NostrNote) -> Option<(String, String)> {
+    (note.kind == crate::live_game::replaceable_game_kind())
+        .then(|| crate::tag_values(note, "d").into_iter().next())
+        .flatten()
+        .map(|d| (note.pubkey.clone(), d))
+}
+
+fn has_newer_revision(
+    feed_notes: &[nostr_minions::nostro2::NostrNote],
+    note: &nostr_minions::nostro2::NostrNote,
+) -> bool {
+    replaceable_address(note).is_some_and(|address| {
+        feed_notes.iter().any(|feed_note| {
+            replaceable_address(feed_note).as_ref() == Some(&address)
+                && feed_note.created_at >= note.created_at
+        })
+    })
+}
+
+fn matches_source(
+    note: &nostr_minions::nostro2::NostrNote,
+    source: &FeedSource,
+    follows: &[String],
+) -> bool {
+    match source {
+        FeedSource::Follows => follows.contains(&note.pubkey),
+        FeedSource::Custom { pubkeys, hashtags } => {
+            pubkeys.contains(&note.pubkey)
+                || crate::tag_values(note, "t")
+                    .iter()
+                    .any(|tag| hashtags.contains(&tag.to_lowercase()))
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct FeedSourcePickerProps {
+    pub source: UseStateHandle<FeedSource>,
+    pub follows: usize,
+}
+
+#[function_component(FeedSourcePicker)]
+fn feed_source_picker(props: &FeedSourcePickerProps) -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let onsubmit = {
+        let source = props.source.clone();
+        Callback::from(move |form: web_sys::HtmlFormElement| {
+            let read_list = |name: &str| {
+                form.get_with_name(name)
+                    .map(|input| input.unchecked_into::<web_sys::HtmlInputElement>().value())
+                    .unwrap_or_default()
+                    .split([',', ' '])
+                    .map(|value| value.trim().trim_start_matches('#').to_lowercase())
+                    .filter(|value| !value.is_empty())
+                    .collect::<Vec<_>>()
+            };
+            let pubkeys = read_list("pubkeys")
+                .into_iter()
+                .filter_map(|key| crate::accounts::hex_pubkey(&key))
+                .collect();
+            source.set(FeedSource::Custom {
+                pubkeys,
+                hashtags: read_list("hashtags"),
+            });
+        })
+    };
+    let show_follows = {
+        let source = props.source.clone();
+        Callback::from(move |_| source.set(FeedSource::Follows))
+    };
+    html! {
+        <Card class="h-fit max-w-sm">
+            <CardHeader>
+                <CardTitle>{ language_ctx.t("feed_sources") }</CardTitle>
+                <CardDescription class="text-sm text-white">
+                    { language_ctx.t("feed_sources_description") }
+                </CardDescription>
+            </CardHeader>
+            <CardContent class="space-y-4">
+                <Button
+                    r#type={ButtonType::Button}
+                    variant={if *props.source == FeedSource::Follows {
+                        ButtonVariant::Normal
+                    } else {
+                        ButtonVariant::Outline
+                    }}
+                    class="w-full"
+                    onclick={show_follows}
+                >
+                    {format!("{} ({})", language_ctx.t("feed_follows"), props.follows)}
+                </Button>
+                <Form {onsubmit} class="space-y-4">
+                    <div class="grid gap-2">
+                        <Label r#for="pubkeys" class="font-medium">{ language_ctx.t("feed_pubkeys") }</Label>
+                        <Input id="pubkeys" name="pubkeys" placeholder="npub1..." />
+                    </div>
+                    <div class="grid gap-2">
+                        <Label r#for="hashtags" class="font-medium">{ language_ctx.t("feed_hashtags") }</Label>
+                        <Input id="hashtags" name="hashtags" placeholder="#chess" />
+                    </div>
+                    <Button r#type={ButtonType::Submit} class="w-full">
+                        { language_ctx.t("feed_show") }
+                    </Button>
+                </Form>
+            </CardContent>
+        </Card>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct FeedItemProps {
+    pub note: nostr_minions::nostro2::NostrNote,
+    pub known: std::rc::Rc<std::collections::HashSet<String>>,
+}
+
+#[function_component(FeedItem)]
+fn feed_item(props: &FeedItemProps) -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let game_ctx = crate::live_game::use_game_history();
+    let id = props.note.id.clone().unwrap_or_default();
+    let is_saved = game_ctx
+        .rooky_game_entries()
+        .iter()
+        .any(|entry| entry.id == id);
+    let is_duplicate = !is_saved
+        && crate::note_fingerprint(&props.note)
+            .is_some_and(|fingerprint| props.known.contains(&fingerprint));
+    let Ok(pgn_game) = rooky_core::RookyGame::try_from(props.note.clone()) else {
+        return html! {};
+    };
+    let onclick = {
+        let note = props.note.clone();
+        let game_ctx = game_ctx.dispatcher();
+        Callback::from(move |_| {
+            let entry = rooky_core::idb::RookyGameEntry {
+                id: note.id.clone().unwrap_or_default(),
+                note: note.clone(),
+                origin: rooky_core::idb::GameOrigin::Public,
+            };
+            let game_ctx = game_ctx.clone();
+            yew::platform::spawn_local(async move {
+                if entry.clone().save_to_store().await.is_err() {
+                    nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
+                        "Failed to save game",
+                    )
+                    .show();
+                    return;
+                }
+                game_ctx.dispatch(crate::live_game::AnnotatedGameHistoryAction::AddGame(entry));
+                nostr_minions::widgets::toastify::ToastifyOptions::new_success(
+                    "Game saved successfully",
+                )
+                .show();
+            });
+        })
+    };
+    html! {
+        <Card class="h-fit">
+            <CardContent class="pt-6">
+                <crate::components::GameCard {pgn_game} author={props.note.pubkey.clone()} />
+                {if is_saved {
+                    html! {
+                        <yew_router::components::Link<crate::router::MainRoute>
+                            to={crate::router::MainRoute::GameDetail { id }}>
+                            <Button variant={ButtonVariant::Outline} class="w-full">
+                                <lucide_yew::BookOpen class="size-5" />
+                                <span class="ml-2">{ language_ctx.t("feed_open_saved") }</span>
+                            </Button>
+                        </yew_router::components::Link<crate::router::MainRoute>>
+                    }
+                } else if is_duplicate {
+                    html! {
+                        <Button variant={ButtonVariant::Disabled} class="w-full">
+                            <lucide_yew::Copy class="size-5" />
+                            <span class="ml-2">{ language_ctx.t("feed_already_saved") }</span>
+                        </Button>
+                    }
+                } else {
+                    html! {
+                        <Button class="w-full" {onclick}>
+                            <lucide_yew::Download class="size-5" />
+                            <span class="ml-2">{ language_ctx.t("feed_save_game") }</span>
+                        </Button>
+                    }
+                }}
+            </CardContent>
+        </Card>
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use shady_minions::ui::Button;
 use yew::prelude::*;
 
@@ -16,13 +18,28 @@ pub fn games_page() -> Html {
     let filter_state = use_state(|| None::<rooky_core::idb::GameOrigin>);
     let page = use_state(|| 0);
     let total_pages = use_state(|| 0);
+    let selected = use_state(HashSet::<String>::new);
+    let label_filter = use_state(|| None::<crate::labels::LabelFilter>);
+    let query = use_state(String::new);
     let language_ctx = crate::contexts::language::use_language_ctx();
     html! {
         <div class="h-full flex flex-col justify-evenly p-12 gap-6">
             <h2 class="text-4xl text-white font-black">{language_ctx.t("common_games")}</h2>
             <div class="flex flex-col justify-evenly gap-6 flex-1">
-                <FilterSelector filter={filter_state.clone()} page={page.clone()} total_pages={total_pages.clone()} />
-                <GamesList filter={filter_state.clone()} page={page.clone()} total_pages={total_pages.clone()} />
+                <FilterSelector
+                    filter={filter_state.clone()}
+                    page={page.clone()}
+                    total_pages={total_pages.clone()}
+                    selected={selected.clone()}
+                    label_filter={label_filter.clone()}
+                    query={query.clone()} />
+                <GamesList
+                    filter={filter_state.clone()}
+                    page={page.clone()}
+                    total_pages={total_pages.clone()}
+                    selected={selected.clone()}
+                    label_filter={label_filter.clone()}
+                    query={query.clone()} />
             </div>
         </div>
     }
@@ -33,33 +50,58 @@ pub struct GamesFilterProps {
     pub filter: UseStateHandle<Option<rooky_core::idb::GameOrigin>>,
     pub page: UseStateHandle<usize>,
     pub total_pages: UseStateHandle<usize>,
+    pub selected: UseStateHandle<HashSet<String>>,
+    pub label_filter: UseStateHandle<Option<crate::labels::LabelFilter>>,
+    pub query: UseStateHandle<String>,
 }
 
 #[function_component(GamesList)]
 pub fn games_list(props: &GamesFilterProps) -> Html {
     let filter = props.filter.clone();
+    let selected = props.selected.clone();
     let game_ctx = crate::contexts::live_game::use_game_history();
+    let labels_ctx = crate::labels::use_game_labels();
     let language_ctx = crate::contexts::language::use_language_ctx();
+    let own_pubkey = crate::signer::use_signer().map(|signer| signer.public_key());
     let sort_state = use_state(|| SortGamesBy::Date(true));
     let total_setter = props.total_pages.setter();
+    let query = (*props.query).trim().to_string();
+    // Kept across renders so new or edited games are indexed without re-reading the rest.
+    let search_index = use_mut_ref(crate::GameSearchIndex::default);
+    let found = use_memo(
+        (game_ctx.rooky_game_entries(), query.clone()),
+        move |(entries, query)| {
+            if query.is_empty() {
+                return HashSet::new();
+            }
+            let mut index = search_index.borrow_mut();
+            index.sync(entries);
+            index.search(query)
+        },
+    );
+    let mut unfiltered_games = game_ctx.rooky_game_entries();
+    if let Some(filter) = filter.as_ref() {
+        unfiltered_games.retain(|game| game.origin == *filter);
+    }
+    if let Some(label_filter) = props.label_filter.as_ref() {
+        unfiltered_games.retain(|game| labels_ctx.matches(&game.id, label_filter));
+    }
+    if !query.is_empty() {
+        unfiltered_games.retain(|game| found.contains(&game.id));
+    }
+    let total_pages = (unfiltered_games.len() as f64 / 5.0).ceil() as usize;
+    use_effect_with(total_pages, move |total_pages| {
+        total_setter.set(*total_pages);
+        || {}
+    });
     let games = {
-        let mut unfiltered_games = game_ctx.rooky_game_entries();
-        let filter = filter.clone();
         let page = props.page.clone();
         let sort = sort_state.clone();
-        let total_pages = (unfiltered_games.len() as f64 / 5.0).ceil() as usize;
-        total_setter.set(total_pages);
         let start = *page * 5;
         let end = start + 5;
         if start >= unfiltered_games.len() {
             vec![]
         } else {
-            if let Some(filter) = filter.as_ref() {
-                unfiltered_games = unfiltered_games
-                    .into_iter()
-                    .filter(|game| game.origin == *filter)
-                    .collect::<Vec<_>>();
-            }
             unfiltered_games.sort_by(|a, b| {
                 let a_game: rooky_core::RookyGame = a.into();
                 let b_game: rooky_core::RookyGame = b.into();
@@ -126,7 +168,27 @@ pub fn games_list(props: &GamesFilterProps) -> Html {
 
     html! {
         <div class="flex flex-col gap-4 flex-1 w-full">
-            <div class="grid grid-cols-7 gap-4 bg-zinc-800 rounded-lg w-full px-6 py-3">
+            <div class="flex items-center gap-4 bg-zinc-800 rounded-lg w-full px-6 py-3">
+            <input
+                type="checkbox"
+                class="size-5"
+                checked={!games.is_empty() && games.iter().all(|game| selected.contains(&game.id))}
+                onchange={
+                    let selected = selected.clone();
+                    let page_ids = games.iter().map(|game| game.id.clone()).collect::<Vec<_>>();
+                    Callback::from(move |_| {
+                        let mut new_selected = (*selected).clone();
+                        if page_ids.iter().all(|id| new_selected.contains(id)) {
+                            for id in &page_ids {
+                                new_selected.remove(id);
+                            }
+                        } else {
+                            new_selected.extend(page_ids.iter().cloned());
+                        }
+                        selected.set(new_selected);
+                    })
+                } />
+            <div class="grid grid-cols-7 gap-4 flex-1">
                 <div class="flex gap-2 items-center ">
                     <h3 class="text-xl text-white font-black">{language_ctx.t("game_details_date")}</h3>
                     <Button
@@ -232,24 +294,80 @@ pub fn games_list(props: &GamesFilterProps) -> Html {
                 </div>
                 <h3 class="text-xl text-white font-black align-center">{language_ctx.t("common_id_title")}</h3>
             </div>
+            </div>
             { for (*games).iter().map(|game| {
                 let pgn_game = rooky_core::RookyGame::from(game);
+                let toggle_selected = {
+                    let selected = selected.clone();
+                    let id = game.id.clone();
+                    Callback::from(move |_| {
+                        let mut new_selected = (*selected).clone();
+                        if !new_selected.remove(&id) {
+                            new_selected.insert(id.clone());
+                        }
+                        selected.set(new_selected);
+                    })
+                };
                 html! {
+                    <div class="flex items-center gap-4 w-full">
+                    <input
+                        type="checkbox"
+                        class="size-5 ml-6"
+                        checked={selected.contains(&game.id)}
+                        onchange={toggle_selected} />
+                    <shady_minions::ui::Popover>
+                        <shady_minions::ui::PopoverTrigger>
+                            <lucide_yew::Tags class="size-5 text-white" />
+                        </shady_minions::ui::PopoverTrigger>
+                        <shady_minions::ui::PopoverContent>
+                            <crate::components::GameLabelsEditor game_id={game.id.clone()} />
+                        </shady_minions::ui::PopoverContent>
+                    </shady_minions::ui::Popover>
+                    <div class="flex-1 min-w-0">
                     <yew_router::components::Link<crate::router::MainRoute>
                         to={crate::router::MainRoute::GameDetail {
-                            id: game.note.id.clone().unwrap_or_default(),
+                            id: game.id.clone(),
                         }}
                         >
                     <div class="grid grid-cols-7  gap-4 bg-white rounded-lg w-full px-6 py-3 overflow-hidden h-fit hover:bg-muted">
                         <h3 class="text-lg text-black font-light">{pgn_game.date.format("%Y-%m-%d").to_string()}</h3>
-                        <h3 class="text-lg text-black font-light">{pgn_game.opening().map(|o| o.name).unwrap_or_default()}</h3>
-                        <h3 class="text-lg text-black font-light">{pgn_game.white.clone()}</h3>
-                        <h3 class="text-lg text-black font-light">{pgn_game.black.clone()}</h3>
+                        <h3 class="text-lg text-black font-light">
+                            <Highlighted text={pgn_game.opening().map(|o| o.name.to_string()).unwrap_or_default()} query={query.clone()} />
+                        </h3>
+                        <h3 class="text-lg text-black font-light">
+                            <Highlighted text={pgn_game.white.clone()} query={query.clone()} />
+                        </h3>
+                        <h3 class="text-lg text-black font-light">
+                            <Highlighted text={pgn_game.black.clone()} query={query.clone()} />
+                        </h3>
                         <h3 class="text-lg text-black font-light">{pgn_game.outcome.to_string()}</h3>
-                        <h3 class="text-lg text-black font-light">{pgn_game.event.to_string()}</h3>
+                        <h3 class="text-lg text-black font-light">
+                            <Highlighted text={pgn_game.event.to_string()} query={query.clone()} />
+                        </h3>
                         <h3 class="text-lg text-black font-light truncate">{game.note.id.clone()}</h3>
+                        {if let Some(pubkey) = crate::live_game::game_author(game, own_pubkey.as_deref()) {
+                            html! {
+                                <crate::components::ProfileBadge {pubkey} class="col-span-7 text-sm text-black" />
+                            }
+                        } else {
+                            html! {}
+                        }}
+                        {if let Some(comment) = (!query.is_empty())
+                            .then(|| crate::matching_comment(game, &query))
+                            .flatten()
+                        {
+                            html! {
+                                <p class="col-span-7 text-sm text-muted truncate">
+                                    <Highlighted text={comment} query={query.clone()} />
+                                </p>
+                            }
+                        } else {
+                            html! {}
+                        }}
                     </div>
                     </yew_router::components::Link<crate::router::MainRoute>>
+                    </div>
+                    </div>
                 }
             }) }
         </div>
@@ -261,7 +379,42 @@ pub fn filter_selector(props: &GamesFilterProps) -> Html {
     let filter = props.filter.clone();
     let page = props.page.clone();
     let total_pages = props.total_pages.clone();
+    let selected = props.selected.clone();
     let language_ctx = crate::contexts::language::use_language_ctx();
+    let game_ctx = crate::contexts::live_game::use_game_history();
+    let outbox = crate::outbox::use_outbox();
+    let relay_ctx = nostr_minions::relay_pool::use_nostr_relay_pool();
+    let keypair = crate::signer::use_signer();
+    let delete_selected = {
+        let selected = selected.clone();
+        let lang_ctx = language_ctx.clone();
+        Callback::from(move |_| {
+            let confirmed = web_sys::window().and_then(|win| {
+                win.confirm_with_message(
+                    &lang_ctx
+                        .t("games_delete_confirm")
+                        .replace("{0}", &selected.len().to_string()),
+                )
+                .ok()
+            });
+            if confirmed != Some(true) {
+                return;
+            }
+            let entries = game_ctx
+                .rooky_game_entries()
+                .into_iter()
+                .filter(|entry| selected.contains(&entry.id))
+                .collect();
+            crate::live_game::delete_games(
+                keypair.as_ref(),
+                &outbox,
+                &relay_ctx,
+                &game_ctx,
+                entries,
+            );
+            selected.set(HashSet::new());
+        })
+    };
     html! {
         <div class="flex flex-row justify-between">
             <div class="flex flex-row gap-4">
@@ -297,8 +450,39 @@ pub fn filter_selector(props: &GamesFilterProps) -> Html {
                     })}>
                         {language_ctx.t("common_public")}
                 </Button>
+                <DuplicatesModal />
+                <crate::components::LabelFilterSelect filter={props.label_filter.clone()} />
+                <shady_minions::ui::Input
+                    id="games-search"
+                    name="games-search"
+                    class="w-64"
+                    placeholder={language_ctx.t("games_search_placeholder")}
+                    value={(*props.query).clone()}
+                    onchange={
+                        let query = props.query.clone();
+                        let page = page.clone();
+                        Callback::from(move |value: String| {
+                            page.set(0);
+                            query.set(value);
+                        })
+                    }
+                />
             </div>
             <div class="flex flex-row gap-4">
+                {if selected.is_empty() {
+                    html! {}
+                } else {
+                    html! {
+                        <Button
+                            variant={shady_minions::ui::ButtonVariant::Destructive}
+                            onclick={delete_selected}>
+                            <lucide_yew::Trash2 class="size-5" />
+                            <span class="ml-2">
+                                {format!("{} ({})", language_ctx.t("games_delete_selected"), selected.len())}
+                            </span>
+                        </Button>
+                    }
+                }}
                 <Button
                     variant={if *page == 0 {
                         shady_minions::ui::ButtonVariant::Disabled
@@ -334,3 +518,181 @@ pub fn filter_selector(props: &GamesFilterProps) -> Html {
         </div>
     }
 }
+
+#[function_component(DuplicatesModal)]
+pub fn duplicates_modal() -> Html {
+    let is_open = use_state(|| false);
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    html! {
+        <>
+            <Button
+                variant={shady_minions::ui::ButtonVariant::Outline}
+                onclick={
+                    let is_open = is_open.clone();
+                    Callback::from(move |_| {
+                    is_open.set(!*is_open);
+                })}>
+                <lucide_yew::Copy class="size-5" />
+                <span class="ml-2">{language_ctx.t("games_find_duplicates")}</span>
+            </Button>
+            <shady_minions::ui::Modal is_open={is_open.clone()}>
+                {if *is_open {
+                    html! { <DuplicatesList /> }
+                } else {
+                    html! {}
+                }}
+            </shady_minions::ui::Modal>
+        </>
+    }
+}
+
+#[function_component(DuplicatesList)]
+fn duplicates_list() -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let game_ctx = crate::contexts::live_game::use_game_history();
+    let outbox = crate::outbox::use_outbox();
+    let relay_ctx = nostr_minions::relay_pool::use_nostr_relay_pool();
+    let labels_ctx = crate::labels::use_game_labels();
+    let backups = crate::backup::use_game_backups();
+    let keypair = crate::signer::use_signer();
+    let groups = use_memo(game_ctx.rooky_game_entries(), |entries| {
+        crate::duplicate_groups(entries)
+    });
+
+    // Copies published to relays are left alone; the user's other devices drop theirs
+    // through backup tombstones.
+    // Copies carrying annotations the kept game lacks are only removed once confirmed.
+    let discard = {
+        let game_ctx = game_ctx.clone();
+        let language_ctx = language_ctx.clone();
+        Callback::from(
+            move |(kept, entries): (
+                rooky_core::idb::RookyGameEntry,
+                Vec<rooky_core::idb::RookyGameEntry>,
+            )| {
+                let loses_annotations = entries
+                    .iter()
+                    .any(|entry| crate::loses_annotations(&kept, entry));
+                if loses_annotations
+                    && !web_sys::window()
+                        .and_then(|win| {
+                            win.confirm_with_message(
+                                &language_ctx.t("games_duplicate_annotations_confirm"),
+                            )
+                            .ok()
+                        })
+                        .unwrap_or(false)
+                {
+                    return;
+                }
+                crate::live_game::discard_duplicates(
+                    keypair.as_ref(),
+                    &outbox,
+                    &relay_ctx,
+                    &game_ctx,
+                    entries,
+                );
+            },
+        )
+    };
+
+    html! {
+        <shady_minions::ui::Card class="size-fit max-w-3xl max-h-[80vh] overflow-y-auto">
+            <shady_minions::ui::CardHeader>
+                <shady_minions::ui::CardTitle>{language_ctx.t("games_duplicates_title")}</shady_minions::ui::CardTitle>
+            </shady_minions::ui::CardHeader>
+            <shady_minions::ui::CardContent class="space-y-4">
+                {if groups.is_empty() {
+                    html! { <p class="text-muted">{language_ctx.t("games_no_duplicates")}</p> }
+                } else {
+                    groups.iter().map(|group| {
+                        let merge = {
+                            let discard = discard.clone();
+                            let labels_ctx = labels_ctx.clone();
+                            let backups = backups.clone();
+                            let kept = group[0].clone();
+                            let extra = group[1..].to_vec();
+                            Callback::from(move |_| {
+                                // The kept game takes over the tags and collections of its copies.
+                                let kept_labels = labels_ctx.labels(&kept.id);
+                                let merged = extra.iter().fold(kept_labels.clone(), |labels, entry| {
+                                    labels.merged(&labels_ctx.labels(&entry.id))
+                                });
+                                if merged != kept_labels {
+                                    crate::labels::save_labels(&labels_ctx, &backups, merged);
+                                }
+                                discard.emit((kept.clone(), extra.clone()));
+                            })
+                        };
+                        html! {
+                            <div class="p-3 border border-border rounded-lg space-y-2">
+                                {for group.iter().enumerate().map(|(i, entry)| {
+                                    let pgn_game = rooky_core::RookyGame::from(entry);
+                                    let discard_one = {
+                                        let discard = discard.clone();
+                                        let kept = group[0].clone();
+                                        let entry = entry.clone();
+                                        Callback::from(move |_| {
+                                            discard.emit((kept.clone(), vec![entry.clone()]));
+                                        })
+                                    };
+                                    html! {
+                                        <div class="flex items-center justify-between gap-4">
+                                            <div class="min-w-0">
+                                                <p class="font-semibold truncate">
+                                                    {format!("{} {} {}", pgn_game.white, language_ctx.t("common_versus"), pgn_game.black)}
+                                                </p>
+                                                <p class="text-xs text-muted truncate">
+                                                    {format!("{} · {:?} · {}", pgn_game.date.format("%Y-%m-%d"), entry.origin, entry.id)}
+                                                </p>
+                                            </div>
+                                            {if i == 0 {
+                                                html! { <span class="text-xs text-green-500">{language_ctx.t("games_duplicate_keep")}</span> }
+                                            } else {
+                                                html! {
+                                                    <Button
+                                                        variant={shady_minions::ui::ButtonVariant::Outline}
+                                                        size={shady_minions::ui::ButtonSize::Small}
+                                                        onclick={discard_one}>
+                                                        <lucide_yew::Trash2 class="size-4" />
+                                                    </Button>
+                                                }
+                                            }}
+                                        </div>
+                                    }
+                                })}
+                                <Button class="w-full" onclick={merge}>
+                                    <lucide_yew::Merge class="size-5" />
+                                    <span class="ml-2">{language_ctx.t("games_duplicate_merge")}</span>
+                                </Button>
+                            </div>
+                        }
+                    }).collect::<Html>()
+                }}
+            </shady_minions::ui::CardContent>
+        </shady_minions::ui::Card>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct HighlightedProps {
+    pub text: String,
+    pub query: String,
+}
+
+#[function_component(Highlighted)]
+fn highlighted(props: &HighlightedProps) -> Html {
+    if props.query.is_empty() {
+        return html! { {props.text.clone()} };
+    }
+    crate::highlight_segments(&props.text, &props.query)
+        .into_iter()
+        .map(|(segment, is_match)| {
+            if is_match {
+                html! { <mark class="bg-secondary rounded-sm">{segment}</mark> }
+            } else {
+                html! { {segment} }
+            }
+        })
+        .collect::<Html>()
+}
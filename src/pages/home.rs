@@ -56,7 +56,7 @@ pub fn database_summary() -> Html {
 #[function_component(NostrIdSummary)]
 pub fn nostr_id_summary() -> Html {
     let language_ctx = crate::language::use_language_ctx();
-    if nostr_minions::key_manager::use_nostr_key().is_none() {
+    if crate::signer::use_signer().is_none() {
         return html! {
             <Card class="bg-black border-white">
                 <CardHeader>
@@ -1,12 +1,16 @@
 mod annotate;
 mod detail;
+mod feed;
 mod games;
 mod home;
 mod search;
 mod settings;
+mod stats;
 pub use annotate::*;
 pub use detail::*;
+pub use feed::*;
 pub use games::*;
 pub use home::*;
 pub use search::*;
 pub use settings::*;
+pub use stats::*;
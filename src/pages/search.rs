@@ -12,8 +12,14 @@ use yew::prelude::*;
 pub fn search_page() -> Html {
     let selected = use_state(|| None);
     let inner_html = match *selected {
-        Some(SearchType::Lichess) => html! { <LichessSearchForm /> },
+        Some(SearchType::Lichess) => html! {
+            <>
+                <LichessSearchForm />
+                <TrackedLichessAccounts />
+            </>
+        },
         Some(SearchType::ChessCom) => html! { <ChessComSearchForm /> },
+        Some(SearchType::Pgn) => html! { <PgnImportForm /> },
         None => html! { <SearchPicker selected={selected.clone()} /> },
     };
     let close_search = {
@@ -39,6 +45,7 @@ pub fn search_page() -> Html {
     html! {
         <div class="relative flex-1 p-4 overflow-y-auto h-full flex gap-8 flex justify-center items-center">
             {inner_html}
+            <ImportJobsPanel />
             {close_button}
         </div>
     }
@@ -48,6 +55,7 @@ pub fn search_page() -> Html {
 enum SearchType {
     Lichess,
     ChessCom,
+    Pgn,
 }
 
 #[derive(Properties, PartialEq)]
@@ -104,6 +112,20 @@ fn search_picker(props: &SearchPickerProps) -> Html {
                             class="size-6 mr-2 object-contain" />
                         {"Chess.com"}
                     </Button>
+                    <Button
+                        r#type={ButtonType::Button}
+                        variant={ButtonVariant::Outline}
+                        class="w-full"
+                        onclick={
+                            let selected = selected.clone();
+                            Callback::from(move |_| {
+                                selected.set(Some(SearchType::Pgn));
+                            })
+                        }
+                    >
+                        <lucide_yew::FileText class="size-6 mr-2" />
+                        {language_ctx.t("search_pgn_source")}
+                    </Button>
                 </div>
             </CardContent>
         </Card>
@@ -114,58 +136,59 @@ fn search_picker(props: &SearchPickerProps) -> Html {
 pub fn external_search_form() -> Html {
     let language_ctx = crate::contexts::language::use_language_ctx();
     let query_state = use_state(external::LichessGameQuery::default);
-    let keypair = nostr_minions::key_manager::use_nostr_key();
+    let keypair = crate::signer::use_signer();
     let game_ctx = crate::live_game::use_game_history();
+    let jobs = crate::imports::use_import_jobs();
     let onsubmit = {
         let query_state = query_state.clone();
-        let game_ctx = game_ctx.dispatcher();
+        let game_ctx = game_ctx.clone();
+        let jobs = jobs.clone();
         Callback::from(move |_| {
             let query_state = (*query_state).clone();
 
             if let Some(key_ctx) = keypair.clone() {
-                let game_ctx = game_ctx.clone();
+                let mut known = crate::known_fingerprints(&game_ctx.rooky_game_entries());
+                let game_ctx = game_ctx.dispatcher();
+                let mut job = crate::imports::ImportJobHandle::start(
+                    jobs.dispatcher(),
+                    crate::imports::ImportSource::Lichess,
+                    &query_state.username,
+                );
                 yew::platform::spawn_local(async move {
                     let Ok(mut resp) = external::LichessClient::default()
                         .stream_game_history(query_state)
                         .await
                     else {
-                        web_sys::console::log_1(&"Failed to get request".into());
+                        job.finish(crate::imports::ImportStatus::Failed);
                         nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
                             "Failed to fetch games. Please check your query parameters.",
                         )
                         .show();
                         return;
                     };
-                    let mut count = 0;
                     while let Some(game) = resp.next().await {
-                        count += 1;
-                        let mut new_note: nostr_minions::nostro2::NostrNote = game.into();
-                        if key_ctx.sign_note(&mut new_note).is_err() {
-                            continue;
+                        if job.is_cancelled() {
+                            break;
                         }
-                        let entry = rooky_core::idb::RookyGameEntry {
-                            id: new_note.id.clone().unwrap_or_default(),
-                            note: new_note,
-                            origin: rooky_core::idb::GameOrigin::Public,
-                        };
-                        if entry.clone().save_to_store().await.is_err() {
-                            continue;
-                        }
-                        game_ctx.dispatch(crate::live_game::AnnotatedGameHistoryAction::AddGame(
-                            entry.clone(),
-                        ));
+                        job.import_note(&key_ctx, &mut known, &game_ctx, game.into())
+                            .await;
+                    }
+                    let job = job.finish(crate::imports::ImportStatus::Completed);
+                    if job.status == crate::imports::ImportStatus::Cancelled {
+                        return;
                     }
-                    if count == 0 {
+                    if job.fetched == 0 {
                         nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
                             "No games found for the given query.",
                         )
                         .show();
                         return;
                     }
-                    nostr_minions::widgets::toastify::ToastifyOptions::new_success(
-                        "Games saved successfully!",
-                    )
-                    .show();
+                    crate::toast_success(&format!(
+                        "Saved {} games, skipped {} already in the database.",
+                        job.saved.len(),
+                        job.skipped
+                    ));
                 });
             }
         })
@@ -334,50 +357,151 @@ pub fn external_search_form() -> Html {
     </Card>
     }
 }
+fn parse_month(value: &str) -> Option<(u32, u32)> {
+    let (year, month) = value.split_once('-')?;
+    let month = month
+        .parse::<u32>()
+        .ok()
+        .filter(|month| (1..=12).contains(month))?;
+    Some((year.parse().ok()?, month))
+}
+
+/// Every month from `from` to `until`, both included.
+fn months_between(from: (u32, u32), until: (u32, u32)) -> Vec<(u32, u32)> {
+    let mut months = vec![];
+    let mut current = from;
+    while current <= until {
+        months.push(current);
+        current = if current.1 == 12 {
+            (current.0 + 1, 1)
+        } else {
+            (current.0, current.1 + 1)
+        };
+    }
+    months
+}
+
 #[function_component(ChessComSearchForm)]
 pub fn external_search_form() -> Html {
     let language_ctx = crate::contexts::language::use_language_ctx();
-    let keypair = nostr_minions::key_manager::use_nostr_key();
+    let keypair = crate::signer::use_signer();
+    let game_ctx = crate::live_game::use_game_history();
+    let jobs = crate::imports::use_import_jobs();
+    let is_running = jobs.is_running(crate::imports::ImportSource::ChessCom);
     let onsubmit = {
         Callback::from(move |e: web_sys::HtmlFormElement| {
-            let name = e
-                .get_with_name("username")
-                .map(|x| x.unchecked_into::<web_sys::HtmlInputElement>().value())
-                .expect("Failed to get username");
+            if is_running {
+                return;
+            }
+            let Some(keypair) = keypair.clone() else {
+                nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
+                    "No Nostr keypair found",
+                )
+                .show();
+                return;
+            };
+            let field = |name: &str| {
+                e.get_with_name(name)
+                    .map(|x| x.unchecked_into::<web_sys::HtmlInputElement>())
+            };
+            let Some(name) = field("username")
+                .map(|input| input.value().trim().to_string())
+                .filter(|name| !name.is_empty())
+            else {
+                return;
+            };
+            let all_archives = field("all-archives").is_some_and(|input| input.checked());
+            let from = field("date").and_then(|input| parse_month(&input.value()));
+            let until = field("date-until")
+                .and_then(|input| parse_month(&input.value()))
+                .or(from);
+            let range = match (from, until) {
+                (Some(from), Some(until)) => Some((from, until)),
+                _ => None,
+            };
+            if !all_archives && range.is_none() {
+                nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
+                    "Pick a month or import all archives.",
+                )
+                .show();
+                return;
+            }
 
-            let month_str = e
-                .get_with_name("date")
-                .map(|x| x.unchecked_into::<web_sys::HtmlInputElement>().value())
-                .expect("Failed to get username");
-            let (year, month) = month_str.split_once('-').unwrap_or_default();
-            let year = year.parse::<u32>().unwrap_or_default();
-            let month = month.parse::<u32>().unwrap_or_default();
-            if let Some(keypair) = keypair.clone() {
-                yew::platform::spawn_local(async move {
-                    let Ok(mut resp) = external::ChessComClient::default()
-                        .find_games(&name, year, month)
-                        .await
-                    else {
-                        web_sys::console::log_1(&"Failed to get request".into());
+            let mut known = crate::known_fingerprints(&game_ctx.rooky_game_entries());
+            let game_ctx = game_ctx.dispatcher();
+            let query = match range {
+                Some(((from_year, from_month), (until_year, until_month))) if !all_archives => {
+                    format!("{name} {from_year}-{from_month:02}..{until_year}-{until_month:02}")
+                }
+                _ => name.clone(),
+            };
+            let mut job = crate::imports::ImportJobHandle::start(
+                jobs.dispatcher(),
+                crate::imports::ImportSource::ChessCom,
+                &query,
+            );
+            yew::platform::spawn_local(async move {
+                // The archive list skips months without games; a range is only used as a fallback.
+                let archives = crate::ChessComArchives::default().months(&name).await;
+                let months = match (archives, range) {
+                    (Ok(archives), _) if all_archives => archives,
+                    (Ok(archives), Some((from, until))) => archives
+                        .into_iter()
+                        .filter(|month| (from..=until).contains(month))
+                        .collect(),
+                    (Err(_), Some((from, until))) => months_between(from, until),
+                    _ => {
+                        job.finish(crate::imports::ImportStatus::Failed);
+                        nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
+                            "Failed to fetch the Chess.com archive list.",
+                        )
+                        .show();
                         return;
+                    }
+                };
+                job.set_batches(months.len());
+
+                let client = external::ChessComClient::default();
+                let mut failed_months = 0;
+                'months: for (year, month) in months {
+                    let Ok(mut resp) = client.find_games(&name, year, month).await else {
+                        failed_months += 1;
+                        job.batch_done();
+                        continue;
                     };
                     while let Some(game) = resp.next().await {
-                        let mut new_note: nostr_minions::nostro2::NostrNote = game.into();
-                        keypair
-                            .sign_note(&mut new_note)
-                            .expect("Failed to sign note");
-                        let entry = rooky_core::idb::RookyGameEntry {
-                            id: new_note.id.clone().unwrap_or_default(),
-                            note: new_note,
-                            origin: rooky_core::idb::GameOrigin::Public,
-                        };
-                        let Ok(_) = entry.save_to_store().await else {
-                            web_sys::console::log_1(&"Failed to save note".into());
-                            return;
-                        };
+                        if job.is_cancelled() {
+                            break 'months;
+                        }
+                        job.import_note(&keypair, &mut known, &game_ctx, game.into())
+                            .await;
                     }
-                });
-            }
+                    job.batch_done();
+                }
+                let job = job.finish(crate::imports::ImportStatus::Completed);
+                if job.status == crate::imports::ImportStatus::Cancelled {
+                    return;
+                }
+
+                if failed_months > 0 || job.failed > 0 {
+                    crate::toast_failure(&format!(
+                        "{failed_months} months could not be fetched and {} games could not be saved.",
+                        job.failed
+                    ));
+                }
+                if job.fetched == 0 {
+                    nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
+                        "No games found for the given query.",
+                    )
+                    .show();
+                    return;
+                }
+                crate::toast_success(&format!(
+                    "Saved {} games, skipped {} already in the database.",
+                    job.saved.len(),
+                    job.skipped
+                ));
+            });
         })
     };
     html! {
@@ -405,7 +529,7 @@ pub fn external_search_form() -> Html {
 
                       <div class="grid grid-cols-1 md:grid-cols-2 gap-6">
                         <div class="grid gap-2">
-                          <Label r#for="month-date" class="font-medium">
+                          <Label r#for="date" class="font-medium">
                             {language_ctx.t("search_date_label")}
                           </Label>
                           <Input
@@ -415,7 +539,23 @@ pub fn external_search_form() -> Html {
                               placeholder={language_ctx.t("search_month_format_placeholder")}
                             />
                         </div>
+                        <div class="grid gap-2">
+                          <Label r#for="date-until" class="font-medium">
+                            {language_ctx.t("search_until_month_label")}
+                          </Label>
+                          <Input
+                              id="date-until"
+                              name="date-until"
+                              r#type={InputType::Month}
+                              placeholder={language_ctx.t("search_month_format_placeholder")}
+                            />
+                        </div>
                       </div>
+
+                      <label class="flex items-center gap-2 text-sm">
+                        <input type="checkbox" id="all-archives" name="all-archives" />
+                        {language_ctx.t("search_all_archives")}
+                      </label>
                     </div>
 
                     <div class="w-full flex gap-2">
@@ -425,7 +565,7 @@ pub fn external_search_form() -> Html {
                             class="flex-1">
                             {language_ctx.t("common_clear")}
                         </Button>
-                        <Button r#type={ButtonType::Submit} class="flex-1">
+                        <Button r#type={ButtonType::Submit} class="flex-1" disabled={is_running}>
                             {language_ctx.t("search_generate_query")}
                         </Button>
                     </div>
@@ -434,3 +574,572 @@ pub fn external_search_form() -> Html {
         </Card>
     }
 }
+
+#[function_component(TrackedLichessAccounts)]
+pub fn tracked_lichess_accounts() -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let accounts_ctx = crate::lichess_sync::use_lichess_accounts();
+    let jobs = crate::imports::use_import_jobs();
+    let game_ctx = crate::live_game::use_game_history();
+    let keypair = crate::signer::use_signer();
+
+    let sync = {
+        let accounts_ctx = accounts_ctx.clone();
+        let game_ctx = game_ctx.clone();
+        Callback::from(
+            move |accounts: Vec<crate::lichess_sync::TrackedLichessAccount>| {
+                if let Some(keypair) = keypair.as_ref() {
+                    crate::lichess_sync::sync_accounts(
+                        external::LichessClient::default(),
+                        &accounts_ctx,
+                        &jobs,
+                        keypair,
+                        &game_ctx,
+                        accounts,
+                    );
+                }
+            },
+        )
+    };
+    let onsubmit = {
+        let accounts_ctx = accounts_ctx.clone();
+        let sync = sync.clone();
+        Callback::from(move |form: web_sys::HtmlFormElement| {
+            let Some(username) = form
+                .get_with_name("tracked-username")
+                .map(|x| x.unchecked_into::<web_sys::HtmlInputElement>().value())
+                .filter(|username| !username.trim().is_empty())
+            else {
+                return;
+            };
+            let account = crate::lichess_sync::TrackedLichessAccount::new(&username);
+            if accounts_ctx.is_tracked(&account.username) {
+                return;
+            }
+            form.reset();
+            accounts_ctx.dispatch(crate::lichess_sync::LichessAccountsAction::Track(
+                account.clone(),
+            ));
+            let sync = sync.clone();
+            yew::platform::spawn_local(async move {
+                if account.clone().save_to_store().await.is_err() {
+                    nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
+                        "Failed to save Lichess account",
+                    )
+                    .show();
+                    return;
+                }
+                sync.emit(vec![account]);
+            });
+        })
+    };
+    let sync_all = {
+        let accounts_ctx = accounts_ctx.clone();
+        let sync = sync.clone();
+        Callback::from(move |_| sync.emit(accounts_ctx.accounts()))
+    };
+
+    html! {
+        <Card class="size-fit max-w-md mx-auto">
+            <CardHeader>
+                <CardTitle>{language_ctx.t("search_tracked_accounts")}</CardTitle>
+                <CardDescription class="text-sm text-white">
+                    {language_ctx.t("search_tracked_accounts_description")}
+                </CardDescription>
+            </CardHeader>
+            <CardContent class="space-y-4">
+                <Form {onsubmit} class="flex gap-2">
+                    <Input
+                        id="tracked-username"
+                        name="tracked-username"
+                        placeholder={language_ctx.t("search_lichess_username_placeholder")}
+                        required={true}
+                    />
+                    <Button r#type={ButtonType::Submit}>
+                        <lucide_yew::UserPlus class="size-5" />
+                    </Button>
+                </Form>
+                <div class="flex flex-col gap-2 max-h-96 overflow-y-auto">
+                    {for accounts_ctx.accounts().into_iter().map(|account| {
+                        let is_syncing = accounts_ctx.is_syncing(&account.username);
+                        let last_synced = account
+                            .last_synced_at
+                            .and_then(|at| chrono::DateTime::from_timestamp_millis(at as i64))
+                            .map_or_else(
+                                || language_ctx.t("search_never_synced"),
+                                |date| date.format("%Y-%m-%d %H:%M").to_string(),
+                            );
+                        let sync_one = {
+                            let sync = sync.clone();
+                            let account = account.clone();
+                            Callback::from(move |_| sync.emit(vec![account.clone()]))
+                        };
+                        let untrack = {
+                            let accounts_ctx = accounts_ctx.clone();
+                            let account = account.clone();
+                            Callback::from(move |_| {
+                                accounts_ctx.dispatch(
+                                    crate::lichess_sync::LichessAccountsAction::Untrack(
+                                        account.username.clone(),
+                                    ),
+                                );
+                                let account = account.clone();
+                                yew::platform::spawn_local(async move {
+                                    if account.delete_from_store().await.is_err() {
+                                        web_sys::console::error_1(
+                                            &"Failed to remove Lichess account".into(),
+                                        );
+                                    }
+                                });
+                            })
+                        };
+                        html! {
+                            <div class="flex items-center justify-between gap-4 p-2 border border-border rounded-lg">
+                                <div class="min-w-0">
+                                    <p class="font-semibold truncate">{&account.username}</p>
+                                    <p class="text-xs text-muted">{last_synced}</p>
+                                </div>
+                                <div class="flex gap-2">
+                                    <Button
+                                        variant={ButtonVariant::Outline}
+                                        size={shady_minions::ui::ButtonSize::Small}
+                                        disabled={is_syncing}
+                                        onclick={sync_one}>
+                                        <lucide_yew::RefreshCw class={classes!("size-4", is_syncing.then_some("animate-spin"))} />
+                                    </Button>
+                                    <Button
+                                        variant={ButtonVariant::Outline}
+                                        size={shady_minions::ui::ButtonSize::Small}
+                                        disabled={is_syncing}
+                                        onclick={untrack}>
+                                        <lucide_yew::Trash2 class="size-4" />
+                                    </Button>
+                                </div>
+                            </div>
+                        }
+                    })}
+                </div>
+                <Button
+                    class="w-full"
+                    disabled={accounts_ctx.accounts().is_empty()}
+                    onclick={sync_all}>
+                    <lucide_yew::RefreshCw class="size-5" />
+                    <span class="ml-2">{language_ctx.t("search_sync_now")}</span>
+                </Button>
+            </CardContent>
+        </Card>
+    }
+}
+
+#[function_component(ImportJobsPanel)]
+pub fn import_jobs_panel() -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let jobs = crate::imports::use_import_jobs();
+    let all_jobs = jobs.jobs();
+    if all_jobs.is_empty() {
+        return html! {};
+    }
+    let clear_history = {
+        let jobs = jobs.clone();
+        Callback::from(move |_| crate::imports::clear_import_history(&jobs))
+    };
+    html! {
+        <Card class="size-fit w-sm max-w-md mx-auto">
+            <CardHeader>
+                <CardTitle>{language_ctx.t("import_jobs_title")}</CardTitle>
+            </CardHeader>
+            <CardContent class="space-y-4">
+                <div class="flex flex-col gap-2 max-h-[60vh] overflow-y-auto">
+                    {for all_jobs.into_iter().map(|job| {
+                        let is_running = job.status == crate::imports::ImportStatus::Running;
+                        let started_at = chrono::DateTime::from_timestamp_millis(job.started_at as i64)
+                            .map(|date| date.format("%Y-%m-%d %H:%M").to_string())
+                            .unwrap_or_default();
+                        let status = match job.status {
+                            crate::imports::ImportStatus::Running => language_ctx.t("import_status_running"),
+                            crate::imports::ImportStatus::Completed => language_ctx.t("import_status_completed"),
+                            crate::imports::ImportStatus::Cancelled => language_ctx.t("import_status_cancelled"),
+                            crate::imports::ImportStatus::Failed => language_ctx.t("import_status_failed"),
+                        };
+                        let cancel = {
+                            let jobs = jobs.clone();
+                            let id = job.id.clone();
+                            Callback::from(move |_| {
+                                jobs.dispatch(crate::imports::ImportJobsAction::Cancel(id.clone()));
+                            })
+                        };
+                        html! {
+                            <div class="p-2 border border-border rounded-lg space-y-1">
+                                <div class="flex items-center justify-between gap-4">
+                                    <div class="min-w-0">
+                                        <p class="font-semibold truncate">
+                                            {format!("{} · {}", language_ctx.t(job.source.label_key()), job.query)}
+                                        </p>
+                                        <p class="text-xs text-muted">{format!("{started_at} · {status}")}</p>
+                                    </div>
+                                    {if is_running {
+                                        html! {
+                                            <Button
+                                                variant={ButtonVariant::Outline}
+                                                size={shady_minions::ui::ButtonSize::Small}
+                                                disabled={jobs.is_cancelling(&job.id)}
+                                                onclick={cancel}>
+                                                <lucide_yew::CircleStop class="size-4" />
+                                                <span class="ml-2">{language_ctx.t("import_cancel")}</span>
+                                            </Button>
+                                        }
+                                    } else {
+                                        html! {}
+                                    }}
+                                </div>
+                                {if job.batches_total > 0 {
+                                    html! {
+                                        <p class="text-xs">
+                                            {format!("{} {}/{}", language_ctx.t("import_batches"), job.batches_done, job.batches_total)}
+                                        </p>
+                                    }
+                                } else {
+                                    html! {}
+                                }}
+                                <p class="text-xs text-muted">
+                                    {format!(
+                                        "{} {} · {} {} · {} {} · {} {}",
+                                        job.fetched,
+                                        language_ctx.t("search_import_fetched"),
+                                        job.saved.len(),
+                                        language_ctx.t("search_import_saved"),
+                                        job.skipped,
+                                        language_ctx.t("search_import_skipped"),
+                                        job.failed,
+                                        language_ctx.t("search_import_failed"),
+                                    )}
+                                </p>
+                                {if job.saved.is_empty() || is_running {
+                                    html! {}
+                                } else {
+                                    html! { <ImportedGames ids={job.saved.clone()} /> }
+                                }}
+                            </div>
+                        }
+                    })}
+                </div>
+                <Button
+                    variant={ButtonVariant::Outline}
+                    class="w-full"
+                    onclick={clear_history}>
+                    {language_ctx.t("import_clear_history")}
+                </Button>
+            </CardContent>
+        </Card>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct ImportedGamesProps {
+    pub ids: Vec<String>,
+}
+
+/// The games a finished import saved that are still in the database.
+#[function_component(ImportedGames)]
+fn imported_games(props: &ImportedGamesProps) -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let game_ctx = crate::live_game::use_game_history();
+    let entries = game_ctx
+        .rooky_game_entries()
+        .into_iter()
+        .filter(|entry| props.ids.contains(&entry.id))
+        .collect::<Vec<_>>();
+    html! {
+        <Popover>
+            <PopoverTrigger>
+                <span class="text-xs underline hover:text-secondary">
+                    {format!("{} {}", entries.len(), language_ctx.t("import_view_games"))}
+                </span>
+            </PopoverTrigger>
+            <PopoverContent class="bg-background text-white p-2 rounded-lg shadow-lg">
+                <div class="flex flex-col gap-1 max-h-64 overflow-y-auto">
+                    {for entries.iter().map(|entry| {
+                        let game = rooky_core::RookyGame::from(entry);
+                        html! {
+                            <yew_router::components::Link<crate::router::MainRoute>
+                                to={crate::router::MainRoute::GameDetail { id: entry.id.clone() }}
+                                classes="text-sm hover:text-secondary truncate">
+                                {format!("{} {} {}", game.white, language_ctx.t("common_versus"), game.black)}
+                            </yew_router::components::Link<crate::router::MainRoute>>
+                        }
+                    })}
+                </div>
+            </PopoverContent>
+        </Popover>
+    }
+}
+
+#[function_component(PgnImportForm)]
+pub fn pgn_import_form() -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let keypair = crate::signer::use_signer();
+    let game_ctx = crate::live_game::use_game_history();
+    let jobs = crate::imports::use_import_jobs();
+    let pgn_text = use_state(String::new);
+    let source = use_state(|| (crate::imports::ImportSource::Pgn, String::new()));
+    let candidates = use_state(Vec::<crate::PgnImportCandidate>::new);
+    let selected = use_state(std::collections::HashSet::<usize>::new);
+    let loading = use_state(|| false);
+    let known = use_memo(game_ctx.rooky_game_entries(), |entries| {
+        crate::known_fingerprints(entries)
+    });
+
+    let paste = {
+        let pgn_text = pgn_text.clone();
+        Callback::from(move |_| {
+            let Some(window) = web_sys::window() else {
+                return;
+            };
+            let read = window.navigator().clipboard().read_text();
+            let pgn_text = pgn_text.clone();
+            yew::platform::spawn_local(async move {
+                match wasm_bindgen_futures::JsFuture::from(read).await {
+                    Ok(text) => pgn_text.set(text.as_string().unwrap_or_default()),
+                    Err(_) => nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
+                        "Failed to read the clipboard",
+                    )
+                    .show(),
+                }
+            });
+        })
+    };
+    let onsubmit = {
+        let pgn_text = pgn_text.clone();
+        let source = source.clone();
+        let candidates = candidates.clone();
+        let selected = selected.clone();
+        let loading = loading.clone();
+        let known = known.clone();
+        Callback::from(move |form: web_sys::HtmlFormElement| {
+            let url = form
+                .get_with_name("pgn-url")
+                .map(|x| x.unchecked_into::<web_sys::HtmlInputElement>().value())
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            let text = (*pgn_text).clone();
+            let source = source.clone();
+            let candidates = candidates.clone();
+            let selected = selected.clone();
+            let loading = loading.clone();
+            let known = known.clone();
+            loading.set(true);
+            yew::platform::spawn_local(async move {
+                // lichess.org study and broadcast pages go through the export API.
+                let export = crate::LichessExporter::from_page_url(&url);
+                let fetched = match &export {
+                    _ if url.is_empty() => Ok(text),
+                    Some((exporter, export)) => exporter.fetch(export).await,
+                    None => crate::fetch_pgn(&url).await,
+                };
+                let import_source = export
+                    .as_ref()
+                    .map_or(crate::imports::ImportSource::Pgn, |(_, export)| {
+                        export.import_source()
+                    });
+                let (label, text) = if url.is_empty() {
+                    ("pasted PGN".to_string(), fetched.unwrap_or_default())
+                } else {
+                    match fetched {
+                        Ok(text) => (url, text),
+                        Err(_) => {
+                            loading.set(false);
+                            nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
+                                "Failed to fetch PGN from the given URL.",
+                            )
+                            .show();
+                            return;
+                        }
+                    }
+                };
+                let is_broadcast = export
+                    .as_ref()
+                    .is_some_and(|(_, export)| export.is_broadcast());
+                let parsed = crate::parse_pgn_collection(&text, is_broadcast);
+                loading.set(false);
+                if parsed.is_empty() {
+                    nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
+                        "No games found in the given PGN.",
+                    )
+                    .show();
+                }
+                selected.set(
+                    parsed
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, candidate)| !known.contains(&candidate.fingerprint))
+                        .map(|(index, _)| index)
+                        .collect(),
+                );
+                source.set((import_source, label));
+                candidates.set(parsed);
+            });
+        })
+    };
+    let import_selected = {
+        let candidates = candidates.clone();
+        let selected = selected.clone();
+        let source = source.clone();
+        Callback::from(move |_| {
+            let Some(keypair) = keypair.clone() else {
+                nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
+                    "No Nostr keypair found",
+                )
+                .show();
+                return;
+            };
+            let chosen = candidates
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| selected.contains(index))
+                .map(|(_, candidate)| candidate.to_note())
+                .collect::<Vec<_>>();
+            if chosen.is_empty() {
+                return;
+            }
+            let mut known = crate::known_fingerprints(&game_ctx.rooky_game_entries());
+            let game_ctx = game_ctx.dispatcher();
+            let (import_source, label) = (*source).clone();
+            let mut job =
+                crate::imports::ImportJobHandle::start(jobs.dispatcher(), import_source, &label);
+            candidates.set(vec![]);
+            selected.set(std::collections::HashSet::new());
+            yew::platform::spawn_local(async move {
+                for note in chosen {
+                    if job.is_cancelled() {
+                        break;
+                    }
+                    job.import_note(&keypair, &mut known, &game_ctx, note).await;
+                }
+                let job = job.finish(crate::imports::ImportStatus::Completed);
+                crate::toast_success(&format!(
+                    "Saved {} games, skipped {} already in the database.",
+                    job.saved.len(),
+                    job.skipped
+                ));
+            });
+        })
+    };
+    let oninput = {
+        let pgn_text = pgn_text.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(textarea) = e.target_dyn_into::<web_sys::HtmlTextAreaElement>() {
+                pgn_text.set(textarea.value());
+            }
+        })
+    };
+
+    html! {
+        <Card class="size-fit max-w-3xl mx-auto">
+            <CardHeader>
+                <CardTitle>{language_ctx.t("search_pgn_title")}</CardTitle>
+                <CardDescription class="text-sm text-white">
+                    {language_ctx.t("search_pgn_description")}
+                </CardDescription>
+            </CardHeader>
+            <CardContent class="space-y-6">
+                <Form {onsubmit} class="space-y-4">
+                    <div class="grid gap-2">
+                        <Label r#for="pgn-url" class="font-medium">
+                            {language_ctx.t("search_pgn_url_label")}
+                        </Label>
+                        <Input
+                            id="pgn-url"
+                            name="pgn-url"
+                            placeholder="https://lichess.org/study/..."
+                        />
+                    </div>
+                    <div class="grid gap-2">
+                        <div class="flex items-center justify-between">
+                            <Label r#for="pgn-text" class="font-medium">
+                                {language_ctx.t("search_pgn_text_label")}
+                            </Label>
+                            <Button
+                                r#type={ButtonType::Button}
+                                variant={ButtonVariant::Outline}
+                                size={shady_minions::ui::ButtonSize::Small}
+                                onclick={paste}>
+                                <lucide_yew::ClipboardPaste class="size-4" />
+                                <span class="ml-2">{language_ctx.t("search_pgn_paste")}</span>
+                            </Button>
+                        </div>
+                        <textarea
+                            id="pgn-text"
+                            name="pgn-text"
+                            rows="8"
+                            class="w-full rounded-md border border-border bg-transparent p-2 font-mono text-sm"
+                            value={(*pgn_text).clone()}
+                            {oninput}
+                        />
+                    </div>
+                    <Button r#type={ButtonType::Submit} class="w-full" disabled={*loading}>
+                        {language_ctx.t("search_pgn_preview")}
+                    </Button>
+                </Form>
+                {if candidates.is_empty() {
+                    html! {}
+                } else {
+                    html! {
+                        <div class="space-y-2">
+                            <div class="flex flex-col gap-2 max-h-96 overflow-y-auto">
+                                {for candidates.iter().enumerate().map(|(index, candidate)| {
+                                    let is_known = known.contains(&candidate.fingerprint);
+                                    let toggle = {
+                                        let selected = selected.clone();
+                                        Callback::from(move |_| {
+                                            let mut next = (*selected).clone();
+                                            if !next.remove(&index) {
+                                                next.insert(index);
+                                            }
+                                            selected.set(next);
+                                        })
+                                    };
+                                    html! {
+                                        <label class="flex items-center gap-4 p-2 border border-border rounded-lg">
+                                            <input
+                                                type="checkbox"
+                                                checked={selected.contains(&index)}
+                                                disabled={is_known}
+                                                onchange={toggle} />
+                                            <div class="min-w-0">
+                                                <p class="font-semibold truncate">
+                                                    {format!("{} {} {}", candidate.game.white, language_ctx.t("common_versus"), candidate.game.black)}
+                                                </p>
+                                                <p class="text-xs text-muted truncate">
+                                                    {format!(
+                                                        "{} · {} · {} · {} {}",
+                                                        candidate.game.date.format("%Y-%m-%d"),
+                                                        candidate.game.event,
+                                                        candidate.game.outcome,
+                                                        candidate.game.moves.len(),
+                                                        language_ctx.t("edit_game_plies"),
+                                                    )}
+                                                </p>
+                                            </div>
+                                            {if is_known {
+                                                html! { <span class="text-xs text-muted ml-auto">{language_ctx.t("feed_already_saved")}</span> }
+                                            } else {
+                                                html! {}
+                                            }}
+                                        </label>
+                                    }
+                                })}
+                            </div>
+                            <Button class="w-full" onclick={import_selected} disabled={selected.is_empty()}>
+                                <lucide_yew::Download class="size-5" />
+                                <span class="ml-2">
+                                    {format!("{} ({})", language_ctx.t("search_pgn_import_selected"), selected.len())}
+                                </span>
+                            </Button>
+                        </div>
+                    }
+                }}
+            </CardContent>
+        </Card>
+    }
+}
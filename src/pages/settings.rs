@@ -1,5 +1,7 @@
 use nostr_minions::browser_api::IdbStoreManager;
-use shady_minions::ui::{Button, ButtonVariant, Card, CardContent, CardHeader, CardTitle, Input};
+use shady_minions::ui::{
+    Button, ButtonVariant, Card, CardContent, CardHeader, CardTitle, Input, Modal,
+};
 use web_sys::MouseEvent;
 
 use nostr_minions::key_manager::{NostrIdAction, NostrIdStore};
@@ -29,6 +31,7 @@ pub fn key_recovery_page() -> Html {
                     </p>
                 </div>
                 <KeyRecoverySection />
+                <NcryptsecSection />
             </CardContent>
         </Card>
     }
@@ -37,6 +40,10 @@ pub fn key_recovery_page() -> Html {
 #[function_component(KeyRecoverySection)]
 fn key_recovery_section() -> Html {
     let key_ctx = use_context::<NostrIdStore>().expect("No NostrIdStore found");
+    let signer_ctx = crate::signer::use_signer_ctx();
+    let lock_ctx = crate::key_lock::use_key_lock_ctx();
+    let accounts_ctx = crate::accounts::use_accounts();
+    let active_pubkey = crate::signer::use_signer().map(|signer| signer.public_key());
     let language_ctx = crate::contexts::language::use_language_ctx();
 
     let priv_key_copied = use_state(|| false);
@@ -55,7 +62,7 @@ fn key_recovery_section() -> Html {
     let id_state = use_state(|| key_ctx.get_identity().cloned());
 
     // Check if user is using extension-based identity
-    let is_extension = use_state(|| false);
+    let is_extension = key_ctx.get_identity().is_none() && signer_ctx.is_connected();
 
     // Fetch key information and determine if user is using extension
     let priv_key_handle = priv_key.clone();
@@ -64,6 +71,10 @@ fn key_recovery_section() -> Html {
     let id_handle = id_state.clone();
     let pubkey_setter = pubkey.setter();
     let notification_text = language_ctx.t("notification_copied_to_clipboard");
+    let external_pubkey = signer_ctx
+        .extension()
+        .map(|extension| extension.pubkey.clone())
+        .or_else(|| signer_ctx.remote().map(|remote| remote.user_pubkey.clone()));
     use_effect_with(key_ctx.clone(), move |key_handle| {
         let priv_key_handle = priv_key_handle.clone();
         let recovery_phrase_handle = recovery_phrase_handle.clone();
@@ -71,6 +82,9 @@ fn key_recovery_section() -> Html {
         let key_handle = key_handle.clone();
 
         let pubkey_setter = pubkey_setter.clone();
+        if let (None, Some(external_pubkey)) = (key_handle.get_identity(), external_pubkey) {
+            pubkey_setter.set(external_pubkey);
+        }
         yew::platform::spawn_local(async move {
             // Check if identity is extension-based by attempting to get the key
             id_handle.set(key_handle.get_identity().cloned());
@@ -150,7 +164,19 @@ fn key_recovery_section() -> Html {
         let key_handle = key_ctx.dispatcher();
         let id_state = id_state.clone();
         let lang_ctx = language_ctx.clone();
+        let signer_ctx = signer_ctx.clone();
+        let lock_ctx = lock_ctx.clone();
         Callback::from(move |_| {
+            let forget_active = || {
+                if let Some(pubkey) = active_pubkey.as_deref() {
+                    crate::accounts::forget_account(&accounts_ctx, pubkey);
+                }
+            };
+            if is_extension {
+                forget_active();
+                crate::signer::disconnect_signer(&signer_ctx);
+                return;
+            }
             // if let Some(Ok(confirmed)) =
             if let Some(true) = web_sys::window().and_then(|win| {
                 win.confirm_with_message(&lang_ctx.t("key_recovery_delete_confirm"))
@@ -158,6 +184,8 @@ fn key_recovery_section() -> Html {
             }) {
                 // First dispatch the DeleteIdentity action
                 key_handle.dispatch(NostrIdAction::DeleteIdentity);
+                crate::key_lock::forget_encrypted_key(&lock_ctx);
+                forget_active();
                 let id = (*id_state).clone();
                 yew::platform::spawn_local(async move {
                     let Some(id) = id else {
@@ -180,7 +208,7 @@ fn key_recovery_section() -> Html {
             <div class="flex gap-3 w-full">
                 // Show/Hide Toggle Button
                 {
-                    if !(*is_extension) {
+                    if !is_extension {
                         html! {
                             <Button
                                 onclick={
@@ -217,8 +245,21 @@ fn key_recovery_section() -> Html {
                     variant={ButtonVariant::Destructive}
                     class="flex items-center gap-2 flex-1"
                 >
-                    <span>{ language_ctx.t("key_recovery_delete") }</span>
-                    <lucide_yew::Trash2 class="w-4 h-4" />
+                    {if is_extension {
+                        html! {
+                            <>
+                                <span>{ language_ctx.t("key_recovery_disconnect_signer") }</span>
+                                <lucide_yew::LogOut class="w-4 h-4" />
+                            </>
+                        }
+                    } else {
+                        html! {
+                            <>
+                                <span>{ language_ctx.t("key_recovery_delete") }</span>
+                                <lucide_yew::Trash2 class="w-4 h-4" />
+                            </>
+                        }
+                    }}
                 </Button>
             </div>
 
@@ -261,17 +302,16 @@ fn key_recovery_section() -> Html {
                     <h3 class="text-lg font-medium text-muted">{ language_ctx.t("key_recovery_private_key") }</h3>
 
                     {
-                        // if *is_extension {
-                        //     html! {
-                        //         <div class="bg-muted p-4 rounded-lg">
-                        //             <div class="flex items-center text-gray-700 space-x-2">
-                        //                 <lucide_yew::TriangleAlert class="text-amber-500 w-5 h-5 flex-shrink-0" />
-                        //                 // <p>{ language_ctx.t("key_recovery_no_private_key") }</p>
-                        //             </div>
-                        //         </div>
-                        //     }
-                        // } else
-                        if *show_sensitive {
+                        if is_extension {
+                            html! {
+                                <div class="bg-muted p-4 rounded-lg">
+                                    <div class="flex items-center text-muted-foreground space-x-2">
+                                        <lucide_yew::TriangleAlert class="text-amber-500 w-5 h-5 flex-shrink-0" />
+                                        <p>{ language_ctx.t("key_recovery_no_private_key") }</p>
+                                    </div>
+                                </div>
+                            }
+                        } else if *show_sensitive {
                             html! {
                                 <div class="bg-muted p-4 rounded-lg overflow-x-auto flex gap-3">
                                     // <pre class="text-sm text-gray-800 whitespace-pre-wrap break-all select-all">
@@ -315,17 +355,16 @@ fn key_recovery_section() -> Html {
                 <div class="space-y-2">
                     <h3 class="text-lg font-medium text-muted">{ language_ctx.t("key_recovery_recovery_phrase") }</h3>
                     {
-                        // if *is_extension {
-                        //     html! {
-                        //         <div class="bg-muted p-4 rounded-lg">
-                        //             <div class="flex items-center text-gray-700 space-x-2">
-                        //                 <lucide_yew::TriangleAlert class="text-amber-500 w-5 h-5 flex-shrink-0" />
-                        //     //            <p>{ language_ctx.t("key_recovery_extension_warning") }</p>
-                        //             </div>
-                        //         </div>
-                        //     }
-                        // } else
-                        if *show_sensitive {
+                        if is_extension {
+                            html! {
+                                <div class="bg-muted p-4 rounded-lg">
+                                    <div class="flex items-center text-muted-foreground space-x-2">
+                                        <lucide_yew::TriangleAlert class="text-amber-500 w-5 h-5 flex-shrink-0" />
+                                        <p>{ language_ctx.t("key_recovery_extension_warning") }</p>
+                                    </div>
+                                </div>
+                            }
+                        } else if *show_sensitive {
                             if recovery_phrase.is_empty() {
                                 html! {
                                     <div class="bg-muted p-4 rounded-lg">
@@ -387,6 +426,241 @@ fn key_recovery_section() -> Html {
     }
 }
 
+#[function_component(NcryptsecSection)]
+fn ncryptsec_section() -> Html {
+    let key_ctx = use_context::<NostrIdStore>().expect("No NostrIdStore found");
+    let lock_ctx = crate::key_lock::use_key_lock_ctx();
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let passphrase = use_state(String::new);
+    let exported = use_state(String::new);
+    let import_value = use_state(String::new);
+    let import_passphrase = use_state(String::new);
+    let busy = use_state(|| false);
+
+    if key_ctx.get_identity().is_none() {
+        return html! {};
+    }
+
+    let export_key = {
+        let key_ctx = key_ctx.clone();
+        let passphrase = passphrase.clone();
+        let exported = exported.clone();
+        let busy = busy.clone();
+        Callback::from(move |_: MouseEvent| {
+            if passphrase.is_empty() {
+                return;
+            }
+            let key_ctx = key_ctx.clone();
+            let passphrase = (*passphrase).clone();
+            let exported = exported.clone();
+            let busy = busy.clone();
+            busy.set(true);
+            yew::platform::spawn_local(async move {
+                gloo::timers::future::TimeoutFuture::new(0).await;
+                let result = match key_ctx.get_nostr_key().await {
+                    Some(mut key) => {
+                        key.set_extractable(true);
+                        crate::nip49::encrypt_keypair(&key, &passphrase)
+                    }
+                    None => Err("No key to export".to_string()),
+                };
+                match result {
+                    Ok(ncryptsec) => exported.set(ncryptsec),
+                    Err(error) => {
+                        crate::toast_failure(&error);
+                    }
+                }
+                busy.set(false);
+            });
+        })
+    };
+
+    let protect_key = {
+        let key_ctx = key_ctx.clone();
+        let lock_ctx = lock_ctx.clone();
+        let passphrase = passphrase.clone();
+        let busy = busy.clone();
+        let success_message = language_ctx.t("ncryptsec_protected");
+        Callback::from(move |_: MouseEvent| {
+            if passphrase.is_empty() {
+                return;
+            }
+            let key_ctx = key_ctx.clone();
+            let lock_ctx = lock_ctx.clone();
+            let passphrase = (*passphrase).clone();
+            let busy = busy.clone();
+            let success_message = success_message.clone();
+            busy.set(true);
+            yew::platform::spawn_local(async move {
+                gloo::timers::future::TimeoutFuture::new(0).await;
+                match crate::key_lock::protect_stored_key(&key_ctx, &lock_ctx, &passphrase).await {
+                    Ok(()) => {
+                        crate::toast_success(&success_message);
+                    }
+                    Err(error) => {
+                        crate::toast_failure(&error);
+                    }
+                }
+                busy.set(false);
+            });
+        })
+    };
+
+    let import_key = {
+        let key_ctx = key_ctx.clone();
+        let lock_ctx = lock_ctx.clone();
+        let import_value = import_value.clone();
+        let import_passphrase = import_passphrase.clone();
+        let busy = busy.clone();
+        let confirm_message = language_ctx.t("ncryptsec_import_confirm");
+        Callback::from(move |_: MouseEvent| {
+            if import_value.is_empty() || import_passphrase.is_empty() {
+                return;
+            }
+            if let Some(true) =
+                web_sys::window().and_then(|win| win.confirm_with_message(&confirm_message).ok())
+            {
+                let key_ctx = key_ctx.clone();
+                let lock_ctx = lock_ctx.clone();
+                let ncryptsec = import_value.trim().to_string();
+                let passphrase = (*import_passphrase).clone();
+                let previous = key_ctx.get_identity().cloned();
+                let busy = busy.clone();
+                busy.set(true);
+                yew::platform::spawn_local(async move {
+                    gloo::timers::future::TimeoutFuture::new(0).await;
+                    let result = match crate::nip49::decrypt_keypair(&ncryptsec, &passphrase) {
+                        Ok(keypair) => {
+                            if let Some(previous) = previous {
+                                if previous.delete_from_store().await.is_err() {
+                                    web_sys::console::error_1(
+                                        &"Failed to remove previous key".into(),
+                                    );
+                                }
+                            }
+                            crate::key_lock::store_identity(
+                                &key_ctx,
+                                &lock_ctx,
+                                keypair,
+                                Some(passphrase),
+                            )
+                            .await
+                        }
+                        Err(error) => Err(error),
+                    };
+                    if let Err(error) = result {
+                        crate::toast_failure(&error);
+                    }
+                    busy.set(false);
+                });
+            }
+        })
+    };
+
+    let lock_now = {
+        let key_handle = key_ctx.dispatcher();
+        Callback::from(move |_: MouseEvent| {
+            key_handle.dispatch(NostrIdAction::DeleteIdentity);
+        })
+    };
+
+    html! {
+        <div class="space-y-4">
+            <h3 class="text-lg font-medium text-muted">{ language_ctx.t("ncryptsec_title") }</h3>
+            <p class="text-sm text-muted-foreground">
+                {if lock_ctx.is_protected() {
+                    language_ctx
+                        .t("ncryptsec_protected_status")
+                        .replace("{0}", &crate::key_lock::AUTO_LOCK_MINUTES.to_string())
+                } else {
+                    language_ctx.t("ncryptsec_unprotected_status")
+                }}
+            </p>
+            <Input
+                id="ncryptsec-passphrase"
+                name="ncryptsec-passphrase"
+                r#type={shady_minions::ui::InputType::Password}
+                placeholder={language_ctx.t("login_passphrase")}
+                onchange={{
+                    let passphrase = passphrase.clone();
+                    Callback::from(move |value: String| passphrase.set(value))
+                }}
+            />
+            <div class="flex gap-3">
+                <Button onclick={export_key} disabled={*busy} class="flex items-center gap-2 flex-1">
+                    <lucide_yew::Download class="w-4 h-4" />
+                    <span>{ language_ctx.t("ncryptsec_export") }</span>
+                </Button>
+                {if lock_ctx.is_protected() {
+                    html! {
+                        <Button
+                            onclick={lock_now}
+                            variant={ButtonVariant::Outline}
+                            class="flex items-center gap-2 flex-1">
+                            <lucide_yew::Lock class="w-4 h-4" />
+                            <span>{ language_ctx.t("ncryptsec_lock_now") }</span>
+                        </Button>
+                    }
+                } else {
+                    html! {
+                        <Button
+                            onclick={protect_key}
+                            disabled={*busy}
+                            variant={ButtonVariant::Outline}
+                            class="flex items-center gap-2 flex-1">
+                            <lucide_yew::Lock class="w-4 h-4" />
+                            <span>{ language_ctx.t("ncryptsec_protect") }</span>
+                        </Button>
+                    }
+                }}
+            </div>
+            {if exported.is_empty() {
+                html! {}
+            } else {
+                let ncryptsec = (*exported).clone();
+                html! {
+                    <div class="bg-muted p-4 rounded-lg flex gap-3">
+                        <pre class="text-sm text-muted-foreground truncate select-all">{ &*exported }</pre>
+                        <button
+                            onclick={Callback::from(move |_| {
+                                nostr_minions::browser_api::clipboard_copy(&ncryptsec);
+                            })}
+                            class="hover:bg-muted hover:text-primary rounded-lg transition-colors"
+                            title={ language_ctx.t("ncryptsec_copy") }
+                        >
+                            <lucide_yew::Copy class="w-5 h-5 text-muted-foreground" />
+                        </button>
+                    </div>
+                }
+            }}
+            <h3 class="text-lg font-medium text-muted">{ language_ctx.t("ncryptsec_import_title") }</h3>
+            <Input
+                id="ncryptsec-import"
+                name="ncryptsec-import"
+                placeholder="ncryptsec1..."
+                onchange={{
+                    let import_value = import_value.clone();
+                    Callback::from(move |value: String| import_value.set(value))
+                }}
+            />
+            <Input
+                id="ncryptsec-import-passphrase"
+                name="ncryptsec-import-passphrase"
+                r#type={shady_minions::ui::InputType::Password}
+                placeholder={language_ctx.t("login_passphrase")}
+                onchange={{
+                    let import_passphrase = import_passphrase.clone();
+                    Callback::from(move |value: String| import_passphrase.set(value))
+                }}
+            />
+            <Button onclick={import_key} disabled={*busy} variant={ButtonVariant::Outline} class="flex items-center gap-2 w-full">
+                <lucide_yew::Upload class="w-4 h-4" />
+                <span>{ language_ctx.t("ncryptsec_import") }</span>
+            </Button>
+        </div>
+    }
+}
+
 #[function_component(RelayManagementPage)]
 pub fn relay_management_page() -> Html {
     let language_ctx = crate::contexts::language::use_language_ctx();
@@ -395,25 +669,40 @@ pub fn relay_management_page() -> Html {
 
     // Loading relays from IndexedDB on component mount
     let relays = relay_ctx.relay_health();
+    // The pool only reports connection state; read and write markers live in IndexedDB.
+    let saved_relays = use_state(Vec::<nostr_minions::relay_pool::UserRelay>::new);
+    {
+        let saved_relays = saved_relays.clone();
+        use_effect_with(relays.len(), move |_| {
+            yew::platform::spawn_local(async move {
+                if let Ok(relays) =
+                    nostr_minions::relay_pool::UserRelay::retrieve_all_from_store().await
+                {
+                    saved_relays.set(relays);
+                }
+            });
+            || {}
+        });
+    }
 
+    let testing = use_state(|| false);
+    let url_error = (!new_relay_url.trim().is_empty())
+        .then(|| crate::validate_relay_url(&new_relay_url).err())
+        .flatten();
     let add_relay = {
         let relays = relays.clone();
         let new_relay_url = new_relay_url.clone();
         let relay_ctx = relay_ctx.clone();
+        let testing = testing.clone();
+        let unreachable_message = language_ctx.t("relay_unreachable");
 
         Callback::from(move |_: MouseEvent| {
-            let mut url = (*new_relay_url).clone();
-            if url.trim().is_empty() {
+            let Ok(url) = crate::validate_relay_url(&new_relay_url) else {
                 return;
-            }
-            if !url.starts_with("wss://") {
-                url = format!("wss://{}", url.trim());
-            }
-
-            let relays = relays.clone();
+            };
 
             // Check if relay already exists
-            if relays.contains_key(url.trim()) {
+            if relays.contains_key(&url) {
                 nostr_minions::widgets::toastify::ToastifyOptions::new_failure(
                     "Relay already exists",
                 )
@@ -421,18 +710,36 @@ pub fn relay_management_page() -> Html {
                 return;
             }
 
-            let new_relay = nostr_minions::relay_pool::UserRelay {
-                url: url.trim().to_string(),
-                read: true,
-                write: true,
-            };
-            relay_ctx.dispatch(nostr_minions::relay_pool::NostrRelayPoolAction::AddRelay(
-                new_relay.clone(),
-            ));
-            nostr_minions::widgets::toastify::ToastifyOptions::new_success(
-                "Relay added successfully",
-            )
-            .show();
+            testing.set(true);
+            let relay_ctx = relay_ctx.clone();
+            let new_relay_url = new_relay_url.clone();
+            let testing = testing.clone();
+            let unreachable_message = unreachable_message.clone();
+            yew::platform::spawn_local(async move {
+                // Only relays that answer a subscription are saved.
+                if crate::measure_latency(&url).await.is_none() {
+                    testing.set(false);
+                    crate::toast_failure(&unreachable_message.replace("{0}", &url));
+                    return;
+                }
+                let new_relay = nostr_minions::relay_pool::UserRelay {
+                    url,
+                    read: true,
+                    write: true,
+                };
+                relay_ctx.dispatch(nostr_minions::relay_pool::NostrRelayPoolAction::AddRelay(
+                    new_relay.clone(),
+                ));
+                if new_relay.save_to_store().await.is_err() {
+                    web_sys::console::error_1(&"Failed to save relay".into());
+                }
+                testing.set(false);
+                new_relay_url.set(String::new());
+                nostr_minions::widgets::toastify::ToastifyOptions::new_success(
+                    "Relay added successfully",
+                )
+                .show();
+            });
         })
     };
 
@@ -463,6 +770,29 @@ pub fn relay_management_page() -> Html {
         })
     };
 
+    let set_access = {
+        let relay_ctx = relay_ctx.clone();
+        let saved_relays = saved_relays.clone();
+        Callback::from(move |relay: nostr_minions::relay_pool::UserRelay| {
+            // The pool picks up new markers when the relay is added again.
+            relay_ctx.dispatch(
+                nostr_minions::relay_pool::NostrRelayPoolAction::RemoveRelay(relay.clone()),
+            );
+            relay_ctx.dispatch(nostr_minions::relay_pool::NostrRelayPoolAction::AddRelay(
+                relay.clone(),
+            ));
+            let mut updated = (*saved_relays).clone();
+            updated.retain(|saved| saved.url != relay.url);
+            updated.push(relay.clone());
+            saved_relays.set(updated);
+            yew::platform::spawn_local(async move {
+                if relay.save_to_store().await.is_err() {
+                    web_sys::console::error_1(&"Failed to save relay".into());
+                }
+            });
+        })
+    };
+
     let on_url_input = {
         let new_relay_url = new_relay_url.clone();
         Callback::from(move |value: String| {
@@ -486,11 +816,20 @@ pub fn relay_management_page() -> Html {
                         />
                         <Button
                             onclick={add_relay}
-                            disabled={(*new_relay_url).trim().is_empty()}
+                            disabled={(*new_relay_url).trim().is_empty() || url_error.is_some() || *testing}
                         >
-                            <lucide_yew::Plus class="size-4" />
+                            {if *testing {
+                                html! { <lucide_yew::RefreshCw class="size-4 animate-spin" /> }
+                            } else {
+                                html! { <lucide_yew::Plus class="size-4" /> }
+                            }}
                         </Button>
                     </div>
+                    {if let Some(error) = url_error {
+                        html! { <p class="text-sm text-red-500 mt-2">{ error.message(&language_ctx) }</p> }
+                    } else {
+                        html! {}
+                    }}
                 </CardContent>
                 <CardHeader>
                     <CardTitle>{ language_ctx.t("relay_connected_relays") }</CardTitle>
@@ -514,11 +853,30 @@ pub fn relay_management_page() -> Html {
                                         let url = url.clone();
                                         Callback::from(move |_| remove_relay.emit(url.clone()))
                                     };
+                                    let (read, write) = saved_relays
+                                        .iter()
+                                        .find(|saved| saved.url == url)
+                                        .map_or((true, true), |saved| (saved.read, saved.write));
+                                    let toggle = |read: bool, write: bool| {
+                                        let set_access = set_access.clone();
+                                        let url = url.clone();
+                                        Callback::from(move |_| {
+                                            set_access.emit(nostr_minions::relay_pool::UserRelay {
+                                                url: url.clone(),
+                                                read,
+                                                write,
+                                            });
+                                        })
+                                    };
 
                                     html! {
                                         <RelayItem
                                             url={url.clone()}
                                             relay={*relay}
+                                            {read}
+                                            {write}
+                                            on_toggle_read={toggle(!read, write)}
+                                            on_toggle_write={toggle(read, !write)}
                                             on_remove={remove_callback}
                                         />
                                     }
@@ -527,20 +885,209 @@ pub fn relay_management_page() -> Html {
                         }
                     }}
                 </CardContent>
+                <RelayListSync />
             </Card>
     }
 }
 
+/// Publishes the relay list as NIP-65 and NIP-17 notes, or imports a published NIP-65 list.
+#[function_component(RelayListSync)]
+fn relay_list_sync() -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let relay_ctx = nostr_minions::relay_pool::use_nostr_relay_pool();
+    let outbox = crate::outbox::use_outbox();
+    let signer = crate::signer::use_signer();
+    // The import subscription still waiting for its EOSE.
+    let importing = use_state(|| None::<String>);
+    let own_pubkey = signer.as_ref().map(crate::signer::BunkerSigner::public_key);
+    {
+        // Fallback for pools where no relay ever ends the subscription.
+        let importing = importing.clone();
+        let relay_ctx = relay_ctx.clone();
+        let not_found = language_ctx.t("relay_list_not_found");
+        use_effect_with((*importing).clone(), move |sub_id| {
+            let timeout = sub_id.clone().map(|sub_id| {
+                gloo::timers::callback::Timeout::new(10_000, move || {
+                    crate::close_subscription(&relay_ctx, &sub_id);
+                    importing.set(None);
+                    crate::toast_failure(&not_found);
+                })
+            });
+            move || drop(timeout)
+        });
+    }
+    {
+        let importing = importing.clone();
+        let relay_ctx = relay_ctx.clone();
+        let imported_message = language_ctx.t("relay_list_imported");
+        let not_found = language_ctx.t("relay_list_not_found");
+        let cursor = use_mut_ref(|| 0usize);
+        use_effect_with(relay_ctx.relay_events.clone(), move |events| {
+            let finished = crate::unseen(&cursor, events)
+                .iter()
+                .find_map(|event| match event {
+                    nostr_minions::nostro2::NostrRelayEvent::EndOfSubscription(_, sub_id)
+                        if Some(sub_id) == importing.as_ref() =>
+                    {
+                        Some(sub_id.clone())
+                    }
+                    _ => None,
+                });
+            if let Some(sub_id) = finished {
+                crate::close_subscription(&relay_ctx, &sub_id);
+                importing.set(None);
+                let newest = relay_ctx
+                    .unique_notes
+                    .iter()
+                    .filter(|note| {
+                        note.kind == crate::RELAY_LIST_KIND
+                            && Some(&note.pubkey) == own_pubkey.as_ref()
+                            && note.verify()
+                    })
+                    .max_by_key(|note| note.created_at);
+                match newest {
+                    Some(note) => {
+                        let relays = crate::relays_from_note(note);
+                        import_relays(&relay_ctx, relays, &imported_message);
+                    }
+                    None => crate::toast_failure(&not_found),
+                }
+            }
+            || {}
+        });
+    }
+    let Some(signer) = signer else {
+        return html! {};
+    };
+
+    let publish = {
+        let signer = signer.clone();
+        let relay_ctx = relay_ctx.clone();
+        let published_message = language_ctx.t("relay_list_published");
+        let sign_failed = language_ctx.t("relay_list_sign_failed");
+        Callback::from(move |_: MouseEvent| {
+            let signer = signer.clone();
+            let relay_ctx = relay_ctx.clone();
+            let outbox = outbox.clone();
+            let published_message = published_message.clone();
+            let sign_failed = sign_failed.clone();
+            yew::platform::spawn_local(async move {
+                let relays = nostr_minions::relay_pool::UserRelay::retrieve_all_from_store()
+                    .await
+                    .unwrap_or_default();
+                let dm_relays = relays
+                    .iter()
+                    .filter(|relay| relay.read)
+                    .map(|relay| relay.url.clone())
+                    .collect::<Vec<_>>();
+                let mut relay_list = crate::relay_list_note(signer.public_key(), &relays);
+                let mut dm_relay_list = crate::dm_relay_list_note(signer.public_key(), &dm_relays);
+                if signer.sign_note(&mut relay_list).await.is_err()
+                    || signer.sign_note(&mut dm_relay_list).await.is_err()
+                {
+                    crate::toast_failure(&sign_failed);
+                    return;
+                }
+                crate::outbox::publish_note(&outbox, &relay_ctx, relay_list);
+                crate::outbox::publish_note(&outbox, &relay_ctx, dm_relay_list);
+                crate::toast_success(&published_message);
+            });
+        })
+    };
+
+    let import = {
+        let relay_ctx = relay_ctx.clone();
+        let importing = importing.clone();
+        let pubkey = signer.public_key();
+        Callback::from(move |_: MouseEvent| {
+            let filter = nostr_minions::nostro2::NostrSubscription {
+                kinds: vec![crate::RELAY_LIST_KIND].into(),
+                authors: vec![pubkey.clone()].into(),
+                limit: Some(1),
+                ..Default::default()
+            };
+            if let nostr_minions::nostro2::NostrClientEvent::Subscribe(_, id, _) =
+                relay_ctx.send(filter)
+            {
+                importing.set(Some(id));
+            }
+        })
+    };
+    html! {
+        <>
+        <CardHeader>
+            <CardTitle>{ language_ctx.t("relay_list_title") }</CardTitle>
+            <p class="text-sm text-muted">{ language_ctx.t("relay_list_description") }</p>
+        </CardHeader>
+        <CardContent class="flex flex-col gap-2">
+            <Button onclick={publish} class="flex items-center gap-2 w-full">
+                <lucide_yew::Upload class="w-4 h-4" />
+                <span>{ language_ctx.t("relay_list_publish") }</span>
+            </Button>
+            <Button
+                onclick={import}
+                disabled={importing.is_some()}
+                variant={ButtonVariant::Outline}
+                class="flex items-center gap-2 w-full">
+                <lucide_yew::Download class="w-4 h-4" />
+                <span>{ language_ctx.t("relay_list_import") }</span>
+            </Button>
+        </CardContent>
+        </>
+    }
+}
+
+/// Replaces the pool's relays with an imported list and saves them.
+fn import_relays(
+    relay_ctx: &nostr_minions::relay_pool::NostrRelayPoolStore,
+    relays: Vec<nostr_minions::relay_pool::UserRelay>,
+    imported_message: &str,
+) {
+    for relay in &relays {
+        relay_ctx
+            .dispatch(nostr_minions::relay_pool::NostrRelayPoolAction::RemoveRelay(relay.clone()));
+        relay_ctx.dispatch(nostr_minions::relay_pool::NostrRelayPoolAction::AddRelay(
+            relay.clone(),
+        ));
+    }
+    crate::toast_success(&imported_message.replace("{0}", &relays.len().to_string()));
+    yew::platform::spawn_local(async move {
+        for relay in relays {
+            if relay.save_to_store().await.is_err() {
+                web_sys::console::error_1(&"Failed to save relay".into());
+            }
+        }
+    });
+}
+
 #[derive(Properties, PartialEq, Clone)]
 pub struct RelayItemProps {
     pub url: String,
     pub relay: nostr_minions::relay_pool::ReadyState,
+    pub read: bool,
+    pub write: bool,
+    pub on_toggle_read: Callback<MouseEvent>,
+    pub on_toggle_write: Callback<MouseEvent>,
     pub on_remove: Callback<MouseEvent>,
 }
 
 #[function_component(RelayItem)]
 pub fn relay_item(props: &RelayItemProps) -> Html {
     let language_ctx = crate::contexts::language::use_language_ctx();
+    let relay_ctx = nostr_minions::relay_pool::use_nostr_relay_pool();
+    let reconnect_ctx = crate::relay_reconnect::use_relay_reconnect();
+    let show_details = use_state(|| false);
+    let backoff = reconnect_ctx.backoff(&props.url).cloned();
+    let reconnect = {
+        let url = props.url.clone();
+        let reconnect_ctx = reconnect_ctx.clone();
+        Callback::from(move |_: MouseEvent| {
+            reconnect_ctx.dispatch(crate::relay_reconnect::RelayReconnectAction::Reset(
+                url.clone(),
+            ));
+            crate::relay_reconnect::reconnect_relay(&relay_ctx, &url);
+        })
+    };
     let (status_color, status_text, status_icon) = match props.relay {
         nostr_minions::relay_pool::ReadyState::CONNECTING => (
             "text-yellow-500",
@@ -571,12 +1118,53 @@ pub fn relay_item(props: &RelayItemProps) -> Html {
                         <p class="text-sm font-medium truncate">{&props.url}</p>
                         <div class="flex items-center space-x-4 text-xs text-muted">
                             <span class={classes!("font-medium", status_color)}>{status_text}</span>
-                            // <span>{if props.relay.read { "📖 Read" } else { "" }}</span>
-                            // <span>{if props.relay.write { "✏️ Write" } else { "" }}</span>
+                            <button
+                                onclick={props.on_toggle_read.clone()}
+                                class={classes!(if props.read { "text-white" } else { "line-through" })}>
+                                { language_ctx.t("relay_read") }
+                            </button>
+                            <button
+                                onclick={props.on_toggle_write.clone()}
+                                class={classes!(if props.write { "text-white" } else { "line-through" })}>
+                                { language_ctx.t("relay_write") }
+                            </button>
+                            {if let Some(backoff) = backoff.filter(|_| props.relay == nostr_minions::relay_pool::ReadyState::CLOSED) {
+                                html! {
+                                    <span>{ language_ctx
+                                        .t("relay_retry_in")
+                                        .replace("{0}", &backoff.seconds_left().to_string()) }</span>
+                                }
+                            } else {
+                                html! {}
+                            }}
                         </div>
                     </div>
                 </div>
             </div>
+            {if props.relay == nostr_minions::relay_pool::ReadyState::CLOSED {
+                html! {
+                    <Button
+                        variant={ButtonVariant::Outline}
+                        onclick={reconnect}
+                        class="ml-2 px-3 py-1"
+                    >
+                        <lucide_yew::RefreshCw class="w-4 h-4" />
+                        <span class="sr-only">{ language_ctx.t("relay_reconnect") }</span>
+                    </Button>
+                }
+            } else {
+                html! {}
+            }}
+            <Button
+                variant={ButtonVariant::Outline}
+                onclick={
+                    let show_details = show_details.clone();
+                    Callback::from(move |_| show_details.set(true))
+                }
+                class="ml-2 px-3 py-1"
+            >
+                <lucide_yew::Info class="w-4 h-4" />
+            </Button>
             <Button
                 variant={ButtonVariant::Outline}
                 onclick={props.on_remove.clone()}
@@ -584,6 +1172,550 @@ pub fn relay_item(props: &RelayItemProps) -> Html {
             >
                 <lucide_yew::Trash2 class="w-4 h-4" />
             </Button>
+            <Modal is_open={show_details.clone()}>
+                // Only mounted while open, so the relay is probed on demand.
+                {if *show_details {
+                    html! { <RelayDetails url={props.url.clone()} /> }
+                } else {
+                    html! {}
+                }}
+            </Modal>
+        </div>
+    }
+}
+
+fn format_duration(ms: f64) -> String {
+    let seconds = (ms / 1000.0) as u64;
+    match (seconds / 3600, seconds / 60 % 60, seconds % 60) {
+        (0, 0, s) => format!("{s}s"),
+        (0, m, s) => format!("{m}m {s:02}s"),
+        (h, m, s) => format!("{h}h {m:02}m {s:02}s"),
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct RelayDetailsProps {
+    pub url: String,
+}
+
+/// NIP-11 information, latency and this session's connection history for one relay.
+#[function_component(RelayDetails)]
+pub fn relay_details(props: &RelayDetailsProps) -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let stats = crate::relay_stats::use_relay_stats();
+    let info = use_state(|| None::<Result<crate::RelayInfo, String>>);
+    // `None` while a probe is running, then the round trip if the relay answered.
+    let latency = use_state(|| None::<Option<f64>>);
+    let probe = use_state(|| 0u32);
+    let tick = use_state(|| 0u32);
+
+    {
+        let info = info.clone();
+        use_effect_with(props.url.clone(), move |url| {
+            let url = url.clone();
+            yew::platform::spawn_local(async move {
+                let fetched = crate::fetch_relay_info(&url)
+                    .await
+                    .map_err(|error| error.to_string());
+                info.set(Some(fetched));
+            });
+            || {}
+        });
+    }
+    {
+        let latency = latency.clone();
+        use_effect_with((props.url.clone(), *probe), move |(url, _)| {
+            latency.set(None);
+            let url = url.clone();
+            yew::platform::spawn_local(async move {
+                latency.set(Some(crate::measure_latency(&url).await));
+            });
+            || {}
+        });
+    }
+    {
+        let tick = tick.clone();
+        use_effect_with((), move |()| {
+            let interval = gloo::timers::callback::Interval::new(1_000, move || {
+                tick.set((*tick).wrapping_add(1));
+            });
+            move || drop(interval)
+        });
+    }
+
+    let connection = stats.connection(&props.url);
+    let measure_again = {
+        let probe = probe.clone();
+        Callback::from(move |_: MouseEvent| probe.set((*probe).wrapping_add(1)))
+    };
+    let kinds = stats
+        .sent()
+        .keys()
+        .chain(stats.received().keys())
+        .copied()
+        .collect::<std::collections::BTreeSet<_>>();
+    let row = |label: &str, value: String| {
+        html! {
+            <>
+                <dt class="text-muted">{ language_ctx.t(label) }</dt>
+                <dd class="truncate">{ value }</dd>
+            </>
+        }
+    };
+
+    html! {
+        <Card class="max-w-lg max-h-[90vh] overflow-y-auto">
+            <CardHeader>
+                <CardTitle>{ language_ctx.t("relay_details_title") }</CardTitle>
+                <p class="text-sm text-muted truncate">{ &props.url }</p>
+            </CardHeader>
+            <CardContent class="space-y-4 text-sm">
+                <dl class="grid grid-cols-2 gap-x-4 gap-y-1">
+                    { row("relay_latency", match *latency {
+                        None => language_ctx.t("relay_latency_measuring"),
+                        Some(Some(ms)) => format!("{ms:.0} ms"),
+                        Some(None) => language_ctx.t("relay_latency_failed"),
+                    }) }
+                    { row("relay_uptime", connection
+                        .uptime_ms()
+                        .map_or_else(|| language_ctx.t("relay_not_connected"), format_duration)) }
+                    { row("relay_reconnects", connection.reconnects().to_string()) }
+                </dl>
+                <Button
+                    onclick={measure_again}
+                    disabled={latency.is_none()}
+                    variant={ButtonVariant::Outline}
+                    class="flex items-center gap-2 w-full">
+                    <lucide_yew::Timer class="w-4 h-4" />
+                    <span>{ language_ctx.t("relay_measure_again") }</span>
+                </Button>
+
+                <h3 class="text-lg font-medium">{ language_ctx.t("relay_info_title") }</h3>
+                {match (*info).clone() {
+                    None => html! { <p class="text-muted">{ language_ctx.t("relay_info_loading") }</p> },
+                    Some(Err(error)) => html! {
+                        <p class="text-red-500">{ format!("{}: {error}", language_ctx.t("relay_info_unavailable")) }</p>
+                    },
+                    Some(Ok(info)) => html! {
+                        <>
+                        <dl class="grid grid-cols-2 gap-x-4 gap-y-1">
+                            { row("relay_name", info.name.clone().unwrap_or_default()) }
+                            { row("relay_software", format!(
+                                "{} {}",
+                                info.software.clone().unwrap_or_default(),
+                                info.version.clone().unwrap_or_default(),
+                            )) }
+                            { row("relay_contact", info.contact.clone().unwrap_or_default()) }
+                        </dl>
+                        {info.description.clone().map(|description| html! {
+                            <p class="text-muted">{ description }</p>
+                        }).unwrap_or_default()}
+                        <div>
+                            <p class="font-medium mb-1">{ language_ctx.t("relay_supported_nips") }</p>
+                            <div class="flex flex-wrap gap-1">
+                                { info.supported_nips.iter().map(|nip| html! {
+                                    <span class="px-2 py-0.5 rounded bg-muted text-xs">{ format!("NIP-{nip:02}") }</span>
+                                }).collect::<Html>() }
+                            </div>
+                        </div>
+                        {info.limitation.map(|limitation| html! {
+                            <div>
+                                <p class="font-medium mb-1">{ language_ctx.t("relay_limitations") }</p>
+                                <dl class="grid grid-cols-2 gap-x-4 gap-y-1 text-xs">
+                                    { limitation.entries().into_iter().map(|(name, value)| html! {
+                                        <>
+                                            <dt class="text-muted">{ name }</dt>
+                                            <dd>{ value }</dd>
+                                        </>
+                                    }).collect::<Html>() }
+                                </dl>
+                            </div>
+                        }).unwrap_or_default()}
+                        </>
+                    },
+                }}
+
+                <h3 class="text-lg font-medium">{ language_ctx.t("relay_session_totals") }</h3>
+                <p class="text-xs text-muted">{ language_ctx.t("relay_session_totals_note") }</p>
+                {if kinds.is_empty() {
+                    html! { <p class="text-muted">{ language_ctx.t("relay_no_events") }</p> }
+                } else {
+                    html! {
+                        <table class="w-full text-left">
+                            <thead>
+                                <tr>
+                                    <th>{ language_ctx.t("relay_kind") }</th>
+                                    <th>{ language_ctx.t("relay_sent") }</th>
+                                    <th>{ language_ctx.t("relay_received") }</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                { kinds.into_iter().map(|kind| html! {
+                                    <tr>
+                                        <td>{ kind }</td>
+                                        <td>{ stats.sent().get(&kind).copied().unwrap_or_default() }</td>
+                                        <td>{ stats.received().get(&kind).copied().unwrap_or_default() }</td>
+                                    </tr>
+                                }).collect::<Html>() }
+                            </tbody>
+                        </table>
+                    }
+                }}
+            </CardContent>
+        </Card>
+    }
+}
+
+#[function_component(OutboxQueuePage)]
+pub fn outbox_queue_page() -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let outbox = crate::outbox::use_outbox();
+    let relay_ctx = nostr_minions::relay_pool::use_nostr_relay_pool();
+    let queued = outbox.queued();
+
+    let retry_all = {
+        let outbox = outbox.clone();
+        let relay_ctx = relay_ctx.clone();
+        Callback::from(move |_: MouseEvent| {
+            for entry in outbox.queued() {
+                crate::outbox::retry_entry(&outbox, &relay_ctx, &entry);
+            }
+        })
+    };
+
+    let discard = {
+        let outbox = outbox.clone();
+        Callback::from(move |entry: crate::outbox::OutboxEntry| {
+            outbox.dispatch(crate::outbox::OutboxAction::Acknowledged(entry.id.clone()));
+            yew::platform::spawn_local(async move {
+                if entry.delete_from_store().await.is_err() {
+                    web_sys::console::log_1(&"Failed to discard outbox entry".into());
+                }
+            });
+        })
+    };
+
+    html! {
+        <Card class="max-w-md h-fit">
+            <CardHeader>
+                <CardTitle>
+                    <div class="flex items-center justify-between gap-3 pb-2">
+                        <div class="flex items-center space-x-3">
+                            <lucide_yew::Inbox class="text-primary size-6" />
+                            <h3 class="text-2xl font-bold">{ language_ctx.t("outbox_title") }</h3>
+                        </div>
+                        <Button
+                            variant={ButtonVariant::Outline}
+                            onclick={retry_all}
+                            disabled={queued.is_empty()}
+                        >
+                            <lucide_yew::RefreshCw class="size-4" />
+                        </Button>
+                    </div>
+                </CardTitle>
+            </CardHeader>
+            <CardContent>
+                {if queued.is_empty() {
+                    html! {
+                        <div class="text-center py-8 text-muted-foreground">
+                            <lucide_yew::CircleCheck class="w-12 h-12 mx-auto mb-2 opacity-50" />
+                            <p>{ language_ctx.t("outbox_empty") }</p>
+                        </div>
+                    }
+                } else {
+                    html! {
+                        <div class="space-y-3 max-h-[50vh] overflow-y-auto">
+                            {for queued.into_iter().map(|entry| {
+                                let on_discard = {
+                                    let discard = discard.clone();
+                                    let entry = entry.clone();
+                                    Callback::from(move |_| discard.emit(entry.clone()))
+                                };
+                                html! {
+                                    <OutboxItem entry={entry} {on_discard} />
+                                }
+                            })}
+                        </div>
+                    }
+                }}
+            </CardContent>
+        </Card>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct OutboxItemProps {
+    pub entry: crate::outbox::OutboxEntry,
+    pub on_discard: Callback<MouseEvent>,
+}
+
+#[function_component(OutboxItem)]
+pub fn outbox_item(props: &OutboxItemProps) -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let queued_at = chrono::DateTime::from_timestamp_millis(props.entry.queued_at as i64)
+        .map(|date| date.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default();
+    html! {
+        <div class="flex items-center justify-between p-3 border border-border rounded-lg">
+            <div class="min-w-0 flex-1">
+                <p class="text-sm font-medium truncate">
+                    {format!("{} {}", language_ctx.t("outbox_kind"), props.entry.note.kind)}
+                </p>
+                <div class="flex items-center space-x-4 text-xs text-muted">
+                    <span>{queued_at}</span>
+                    <span>{format!("{} {}", props.entry.attempts, language_ctx.t("outbox_attempts"))}</span>
+                </div>
+            </div>
+            <Button
+                variant={ButtonVariant::Outline}
+                onclick={props.on_discard.clone()}
+                class="ml-2 px-3 py-1 text-red-600 border-red-200 hover:bg-red-50"
+            >
+                <lucide_yew::Trash2 class="w-4 h-4" />
+            </Button>
         </div>
     }
 }
+
+const RESYNC_PAGE_SIZE: u32 = 500;
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+struct InboxResync {
+    running: bool,
+    relays: usize,
+    finished_relays: usize,
+    pages: usize,
+    received: usize,
+}
+
+enum InboxResyncAction {
+    Started(usize),
+    /// A page came back holding this many wraps not seen on any relay yet.
+    Page(usize),
+    RelayFinished,
+}
+
+impl Reducible for InboxResync {
+    type Action = InboxResyncAction;
+
+    fn reduce(self: std::rc::Rc<Self>, action: Self::Action) -> std::rc::Rc<Self> {
+        match action {
+            InboxResyncAction::Started(relays) => std::rc::Rc::new(Self {
+                running: relays > 0,
+                relays,
+                ..Self::default()
+            }),
+            InboxResyncAction::Page(new_wraps) => std::rc::Rc::new(Self {
+                pages: self.pages + 1,
+                received: self.received + new_wraps,
+                ..(*self).clone()
+            }),
+            InboxResyncAction::RelayFinished => std::rc::Rc::new(Self {
+                running: self.finished_relays + 1 < self.relays,
+                finished_relays: self.finished_relays + 1,
+                ..(*self).clone()
+            }),
+        }
+    }
+}
+
+fn inbox_page_filter(
+    pubkey: &str,
+    since: Option<u64>,
+    until: Option<u64>,
+) -> nostr_minions::nostro2::NostrSubscription {
+    let mut inbox_filter = nostr_minions::nostro2::NostrSubscription {
+        kinds: vec![1059].into(),
+        since,
+        until,
+        limit: Some(RESYNC_PAGE_SIZE),
+        ..Default::default()
+    };
+    inbox_filter.add_tag("#p", pubkey);
+    inbox_filter
+}
+
+/// Pages through one relay's gift wraps for the user, newest first, with a cursor of its own.
+///
+/// `until` is inclusive, so each page overlaps the previous one on its oldest second and the
+/// repeated wraps are recognised by id. A full page that brings nothing new means more wraps
+/// share that second than a page holds, so the cursor steps past it instead of stopping.
+async fn resync_relay(
+    url: String,
+    user_id: crate::signer::BunkerSigner,
+    since: Option<u64>,
+    seen: std::rc::Rc<std::cell::RefCell<std::collections::HashSet<String>>>,
+    inbox: crate::backup::Inbox,
+    resync: UseReducerDispatcher<InboxResync>,
+) {
+    let pubkey = user_id.public_key();
+    let mut relay_seen = std::collections::HashSet::new();
+    let mut until = None;
+    while let Some(wraps) =
+        crate::fetch_notes(&url, &inbox_page_filter(&pubkey, since, until)).await
+    {
+        let is_full = wraps.len() >= RESYNC_PAGE_SIZE as usize;
+        let Some(oldest) = wraps.iter().map(|wrap| wrap.created_at as u64).min() else {
+            break;
+        };
+        let page = wraps
+            .into_iter()
+            .filter(|wrap| relay_seen.insert(wrap.id.clone().unwrap_or_default()))
+            .collect::<Vec<_>>();
+        until = Some(if page.is_empty() {
+            oldest.saturating_sub(1)
+        } else {
+            oldest
+        });
+        // Relays overlap, so a wrap is only unwrapped for the first relay that returns it.
+        let new_wraps = page
+            .into_iter()
+            .filter(|wrap| {
+                seen.borrow_mut()
+                    .insert(wrap.id.clone().unwrap_or_default())
+            })
+            .collect::<Vec<_>>();
+        resync.dispatch(InboxResyncAction::Page(new_wraps.len()));
+        for wrap in new_wraps {
+            if let Ok(rumor) = user_id.extract_rumor(&wrap).await {
+                inbox.receive_rumor(&pubkey, rumor).await;
+            }
+        }
+        if !is_full {
+            break;
+        }
+    }
+    resync.dispatch(InboxResyncAction::RelayFinished);
+}
+
+#[function_component(InboxResyncPage)]
+pub fn inbox_resync_page() -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let user_id = crate::signer::use_signer();
+    let game_ctx = crate::live_game::use_game_history();
+    let resync = use_reducer(InboxResync::default);
+    let since_date = use_state(String::new);
+    let games_before = use_state(|| 0usize);
+    let backups = crate::backup::use_game_backups();
+    let labels_ctx = crate::labels::use_game_labels();
+
+    let start_resync = {
+        let resync = resync.dispatcher();
+        let since_date = since_date.clone();
+        let games_before = games_before.setter();
+        let inbox = crate::backup::Inbox {
+            backups: backups.clone(),
+            games: game_ctx.clone(),
+            labels: labels_ctx.clone(),
+        };
+        Callback::from(move |_: MouseEvent| {
+            let Some(user_id) = user_id.clone() else {
+                return;
+            };
+            let since = chrono::NaiveDate::parse_from_str(&since_date, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|date| date.and_utc().timestamp() as u64);
+            games_before.set(inbox.games.rooky_game_entries().len());
+            let resync = resync.clone();
+            let inbox = inbox.clone();
+            yew::platform::spawn_local(async move {
+                // Gift wraps are delivered to the user's read relays, so only those are asked.
+                let relays = nostr_minions::relay_pool::UserRelay::retrieve_all_from_store()
+                    .await
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|relay| relay.read)
+                    .map(|relay| relay.url)
+                    .collect::<Vec<_>>();
+                resync.dispatch(InboxResyncAction::Started(relays.len()));
+                let seen =
+                    std::rc::Rc::new(std::cell::RefCell::new(std::collections::HashSet::new()));
+                for url in relays {
+                    yew::platform::spawn_local(resync_relay(
+                        url,
+                        user_id.clone(),
+                        since,
+                        seen.clone(),
+                        inbox.clone(),
+                        resync.clone(),
+                    ));
+                }
+            });
+        })
+    };
+
+    let recovered = game_ctx
+        .rooky_game_entries()
+        .len()
+        .saturating_sub(*games_before);
+    let pending_backups = game_ctx
+        .rooky_game_entries()
+        .iter()
+        .filter(|entry| crate::backup::needs_backup(entry, &backups, &labels_ctx))
+        .count();
+
+    html! {
+        <Card class="max-w-md h-fit">
+            <CardHeader>
+                <CardTitle>
+                    <div class="flex items-center space-x-3 pb-2">
+                        <lucide_yew::History class="text-primary size-6" />
+                        <h3 class="text-2xl font-bold">{ language_ctx.t("resync_title") }</h3>
+                    </div>
+                </CardTitle>
+            </CardHeader>
+            <CardContent class="space-y-4">
+                <p class="text-muted">{ language_ctx.t("resync_description") }</p>
+                <div class="flex items-center gap-2 text-sm text-muted-foreground">
+                    <lucide_yew::CloudUpload class="size-4" />
+                    <span>
+                        {format!(
+                            "{}: {} / {}: {}",
+                            language_ctx.t("backup_synced"),
+                            backups.backed_up_count(),
+                            language_ctx.t("backup_pending"),
+                            pending_backups,
+                        )}
+                    </span>
+                </div>
+                <div class="flex gap-2">
+                    <Input
+                        r#type={shady_minions::ui::InputType::Date}
+                        value={(*since_date).clone()}
+                        oninput={
+                            let since_date = since_date.clone();
+                            Callback::from(move |value: String| since_date.set(value))
+                        }
+                        class="flex-1"
+                    />
+                    <Button onclick={start_resync} disabled={resync.running}>
+                        {if since_date.is_empty() {
+                            language_ctx.t("resync_all_time")
+                        } else {
+                            language_ctx.t("resync_from_date")
+                        }}
+                    </Button>
+                </div>
+                {if resync.pages > 0 {
+                    html! {
+                        <div class="bg-muted p-4 rounded-lg text-sm text-muted-foreground space-y-1">
+                            <p>{format!("{}: {}", language_ctx.t("resync_pages"), resync.pages)}</p>
+                            <p>{format!("{}: {}", language_ctx.t("resync_received"), resync.received)}</p>
+                            <p>{format!("{}: {}", language_ctx.t("resync_recovered"), recovered)}</p>
+                            <p class="font-medium">
+                                {if resync.running {
+                                    language_ctx.t("resync_running")
+                                } else {
+                                    language_ctx.t("resync_done")
+                                }}
+                            </p>
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }}
+            </CardContent>
+        </Card>
+    }
+}
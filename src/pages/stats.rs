@@ -0,0 +1,157 @@
+use shady_minions::ui::{Card, CardContent, CardHeader, CardTitle, Input};
+use yew::prelude::*;
+
+const SUGGESTED_PLAYERS: usize = 8;
+
+#[function_component(StatsPage)]
+pub fn stats_page() -> Html {
+    let language_ctx = crate::contexts::language::use_language_ctx();
+    let game_ctx = crate::live_game::use_game_history();
+    let names = use_memo(game_ctx.rooky_game_entries(), |entries| {
+        crate::player_names(entries)
+    });
+    let player = use_state(|| None::<String>);
+    let selected = (*player)
+        .clone()
+        .or_else(|| names.first().cloned())
+        .unwrap_or_default();
+    let stats = use_memo(
+        (game_ctx.rooky_game_entries(), selected.clone()),
+        |(entries, selected)| crate::player_stats(entries, selected),
+    );
+
+    let onchange = {
+        let player = player.clone();
+        Callback::from(move |name: String| player.set(Some(name)))
+    };
+    let total = stats.total();
+
+    html! {
+        <div class="flex-1 p-8 overflow-y-auto h-full flex flex-col gap-6 text-white">
+            <div class="flex items-center justify-between gap-6">
+                <h2 class="text-4xl font-black">{language_ctx.t("stats_title")}</h2>
+                <div class="flex items-center gap-2">
+                    {for names.iter().take(SUGGESTED_PLAYERS).map(|name| {
+                        let onclick = {
+                            let player = player.clone();
+                            let name = name.clone();
+                            Callback::from(move |_| player.set(Some(name.clone())))
+                        };
+                        let is_selected = name.eq_ignore_ascii_case(&selected);
+                        html! {
+                            <button
+                                class={classes!(
+                                    "px-3", "py-1", "rounded-full", "text-sm",
+                                    if is_selected { "bg-primary" } else { "bg-zinc-800" },
+                                )}
+                                {onclick}>
+                                {name}
+                            </button>
+                        }
+                    })}
+                    <Input
+                        id="stats-player"
+                        name="stats-player"
+                        class="w-48"
+                        placeholder={language_ctx.t("stats_player_placeholder")}
+                        {onchange}
+                    />
+                </div>
+            </div>
+            {if total.games() == 0 {
+                html! { <p class="text-muted">{language_ctx.t("stats_no_games")}</p> }
+            } else {
+                html! {
+                    <div class="grid grid-cols-1 xl:grid-cols-3 gap-6">
+                        <Card>
+                            <CardHeader>
+                                <CardTitle>{selected.clone()}</CardTitle>
+                            </CardHeader>
+                            <CardContent class="space-y-2">
+                                <RecordRow label={language_ctx.t("stats_total")} record={total} />
+                                <RecordRow label={language_ctx.t("stats_as_white")} record={stats.as_white} />
+                                <RecordRow label={language_ctx.t("stats_as_black")} record={stats.as_black} />
+                                <p class="text-sm text-muted">
+                                    {format!("{} {:.1}", language_ctx.t("stats_average_length"), stats.average_length)}
+                                </p>
+                            </CardContent>
+                        </Card>
+                        <Card>
+                            <CardHeader>
+                                <CardTitle>{language_ctx.t("stats_by_origin")}</CardTitle>
+                            </CardHeader>
+                            <CardContent class="space-y-2">
+                                {for stats.by_origin.iter().map(|(origin, record)| html! {
+                                    <RecordRow label={language_ctx.t(crate::origin_label_key(origin))} record={*record} />
+                                })}
+                            </CardContent>
+                        </Card>
+                        <Card>
+                            <CardHeader>
+                                <CardTitle>{language_ctx.t("stats_over_time")}</CardTitle>
+                            </CardHeader>
+                            <CardContent class="space-y-1 max-h-80 overflow-y-auto">
+                                {for stats.by_month.iter().map(|(month, record)| html! {
+                                    <div class="flex items-center gap-2 text-sm">
+                                        <span class="w-20">{month}</span>
+                                        <div class="flex-1 h-3 rounded bg-zinc-800 overflow-hidden">
+                                            <div
+                                                class="h-full bg-primary"
+                                                style={format!("width: {:.0}%", record.score() * 100.0)}>
+                                            </div>
+                                        </div>
+                                        <span class="w-24 text-right">
+                                            {format!("{:.0}% · {}", record.score() * 100.0, record.games())}
+                                        </span>
+                                    </div>
+                                })}
+                            </CardContent>
+                        </Card>
+                        <Card class="xl:col-span-2">
+                            <CardHeader>
+                                <CardTitle>{language_ctx.t("stats_openings")}</CardTitle>
+                            </CardHeader>
+                            <CardContent class="space-y-2">
+                                {for stats.openings.iter().map(|(opening, record)| html! {
+                                    <RecordRow label={opening.clone()} record={*record} />
+                                })}
+                            </CardContent>
+                        </Card>
+                        <Card>
+                            <CardHeader>
+                                <CardTitle>{language_ctx.t("stats_opponents")}</CardTitle>
+                            </CardHeader>
+                            <CardContent class="space-y-2">
+                                {for stats.opponents.iter().map(|(opponent, record)| html! {
+                                    <RecordRow label={opponent.clone()} record={*record} />
+                                })}
+                            </CardContent>
+                        </Card>
+                    </div>
+                }
+            }}
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct RecordRowProps {
+    pub label: String,
+    pub record: crate::ResultRecord,
+}
+
+#[function_component(RecordRow)]
+fn record_row(props: &RecordRowProps) -> Html {
+    let record = props.record;
+    html! {
+        <div class="flex items-center justify-between gap-4 text-sm">
+            <span class="truncate">{&props.label}</span>
+            <span class="whitespace-nowrap">
+                <span class="text-green-500">{format!("+{}", record.wins)}</span>
+                {format!(" ={} ", record.draws)}
+                <span class="text-red-500">{format!("-{}", record.losses)}</span>
+                <span class="text-muted">{format!(" · {:.0}%", record.score() * 100.0)}</span>
+            </span>
+        </div>
+    }
+}
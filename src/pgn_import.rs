@@ -0,0 +1,347 @@
+//! Splits PGN collections into single games for import, keeping comments and variations.
+
+use pgn_reader::{BufferedReader, Nag, Outcome, RawComment, RawTag, SanPlus, Skip, Visitor};
+
+/// Re-serializes each game read by `pgn_reader` so collections can be split game by game.
+#[derive(Default)]
+struct PgnWriter {
+    headers: Vec<(String, String)>,
+    tokens: Vec<String>,
+    ply: u32,
+    variation_plies: Vec<u32>,
+    needs_number: bool,
+    outcome: Option<String>,
+    /// Whether the games come from a Lichess broadcast and need its headers mapped.
+    is_broadcast: bool,
+}
+
+impl PgnWriter {
+    fn push_move_number(&mut self) {
+        let number = self.ply / 2 + 1;
+        if self.ply % 2 == 0 {
+            self.tokens.push(format!("{number}."));
+        } else if self.needs_number {
+            self.tokens.push(format!("{number}..."));
+        }
+        self.needs_number = false;
+    }
+}
+
+impl Visitor for PgnWriter {
+    type Result = String;
+
+    fn begin_game(&mut self) {
+        *self = Self {
+            is_broadcast: self.is_broadcast,
+            ..Self::default()
+        };
+    }
+
+    fn tag(&mut self, key: &[u8], value: RawTag<'_>) {
+        self.headers.push((
+            String::from_utf8_lossy(key).into_owned(),
+            value.decode_utf8_lossy().into_owned(),
+        ));
+    }
+
+    fn san(&mut self, san_plus: SanPlus) {
+        self.push_move_number();
+        self.tokens.push(san_plus.to_string());
+        self.ply += 1;
+    }
+
+    fn nag(&mut self, nag: Nag) {
+        self.tokens.push(nag.to_string());
+    }
+
+    fn comment(&mut self, comment: RawComment<'_>) {
+        let text = String::from_utf8_lossy(comment.as_bytes());
+        self.tokens.push(format!("{{{}}}", text.trim()));
+        self.needs_number = true;
+    }
+
+    fn begin_variation(&mut self) -> Skip {
+        // A variation replaces the move just played.
+        self.variation_plies.push(self.ply);
+        self.ply = self.ply.saturating_sub(1);
+        self.tokens.push("(".to_string());
+        self.needs_number = true;
+        Skip(false)
+    }
+
+    fn end_variation(&mut self) {
+        self.tokens.push(")".to_string());
+        self.ply = self.variation_plies.pop().unwrap_or(self.ply);
+        self.needs_number = true;
+    }
+
+    fn outcome(&mut self, outcome: Option<Outcome>) {
+        self.outcome = outcome.map(|outcome| outcome.to_string());
+    }
+
+    fn end_tags(&mut self) -> Skip {
+        if self.is_broadcast {
+            map_broadcast_headers(&mut self.headers);
+        }
+        Skip(false)
+    }
+
+    fn end_game(&mut self) -> Self::Result {
+        let result = self.outcome.clone().unwrap_or_else(|| {
+            self.headers
+                .iter()
+                .find(|(key, _)| key == "Result")
+                .map_or_else(|| "*".to_string(), |(_, value)| value.clone())
+        });
+        let headers = self
+            .headers
+            .iter()
+            .map(|(key, value)| {
+                // Backslashes first, so the ones escaping quotes are not doubled.
+                let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+                format!("[{key} \"{value}\"]")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let movetext = self.tokens.join(" ").replace("( ", "(").replace(" )", ")");
+        format!("{headers}\n\n{movetext} {result}\n")
+    }
+}
+
+fn header_value<'a>(headers: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(name, value)| name == key && !value.is_empty() && value != "?")
+        .map(|(_, value)| value.as_str())
+}
+
+fn set_header(headers: &mut Vec<(String, String)>, key: &str, value: String) {
+    if let Some(header) = headers.iter_mut().find(|(name, _)| name == key) {
+        header.1 = value;
+    } else {
+        headers.push((key.to_string(), value));
+    }
+}
+
+/// Fills `Event` and `Round` from the extra headers Lichess writes on broadcast exports,
+/// so they land in the game's `PgnEvent` and `PgnRound`.
+fn map_broadcast_headers(headers: &mut Vec<(String, String)>) {
+    if header_value(headers, "Event").is_none() {
+        let event = header_value(headers, "BroadcastName").map(ToString::to_string);
+        if let Some(event) = event {
+            set_header(headers, "Event", event);
+        }
+    }
+    // Broadcast rounds are written as "round.board"; the board is kept in its own header.
+    let split_round = header_value(headers, "Round")
+        .and_then(|round| round.split_once('.'))
+        .map(|(round, board)| (round.to_string(), board.to_string()));
+    if let Some((round, board)) = split_round {
+        set_header(headers, "Round", round);
+        set_header(headers, "Board", board);
+    }
+}
+
+/// Every game in a PGN collection, as standalone PGN text.
+///
+/// Broadcast exports get their `BroadcastName` and `round.board` headers mapped to `Event`,
+/// `Round` and `Board`.
+#[must_use]
+pub fn split_pgn(text: &str, is_broadcast: bool) -> Vec<String> {
+    let mut reader = BufferedReader::new_cursor(text.as_bytes());
+    let mut writer = PgnWriter {
+        is_broadcast,
+        ..PgnWriter::default()
+    };
+    let mut games = vec![];
+    while let Ok(Some(game)) = reader.read_game(&mut writer) {
+        if !writer.tokens.is_empty() || !writer.headers.is_empty() {
+            games.push(game);
+        }
+    }
+    games
+}
+
+/// Collects the comments on a game's main line, skipping variations.
+#[derive(Default)]
+struct MainlineComments {
+    comments: Vec<String>,
+}
+
+impl Visitor for MainlineComments {
+    type Result = Vec<String>;
+
+    fn begin_game(&mut self) {
+        self.comments = vec![String::new()];
+    }
+
+    fn san(&mut self, _san_plus: SanPlus) {
+        self.comments.push(String::new());
+    }
+
+    fn comment(&mut self, comment: RawComment<'_>) {
+        let text = String::from_utf8_lossy(comment.as_bytes());
+        if let Some(last) = self.comments.last_mut() {
+            if !last.is_empty() {
+                last.push(' ');
+            }
+            last.push_str(text.trim());
+        }
+    }
+
+    fn begin_variation(&mut self) -> Skip {
+        Skip(true)
+    }
+
+    fn end_game(&mut self) -> Self::Result {
+        std::mem::take(&mut self.comments)
+    }
+}
+
+/// The main-line comments of the first game in `pgn`: index 0 holds the comment before the
+/// first move and index `n` the comment after the `n`th ply.
+#[must_use]
+pub fn mainline_comments(pgn: &str) -> Vec<String> {
+    let mut reader = BufferedReader::new_cursor(pgn.as_bytes());
+    reader
+        .read_game(&mut MainlineComments::default())
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+}
+
+/// Writes `game` as PGN with `comments` placed as in [`mainline_comments`].
+#[must_use]
+pub fn annotated_pgn(game: &rooky_core::RookyGame, comments: &[String]) -> String {
+    let pgn = game.to_pgn();
+    let headers = pgn
+        .split_once("\n\n")
+        .map_or(pgn.as_str(), |(headers, _)| headers);
+    let comment = |ply: usize| {
+        comments
+            .get(ply)
+            .map(|comment| comment.replace(['{', '}'], "").trim().to_string())
+            .filter(|comment| !comment.is_empty())
+            .map(|comment| format!("{{{comment}}}"))
+    };
+    let mut tokens = comment(0).into_iter().collect::<Vec<_>>();
+    for (ply, san_plus) in game.moves.iter().enumerate() {
+        let number = ply / 2 + 1;
+        if ply % 2 == 0 {
+            tokens.push(format!("{number}."));
+        } else if comment(ply).is_some() {
+            tokens.push(format!("{number}..."));
+        }
+        tokens.push(san_plus.to_string());
+        tokens.extend(comment(ply + 1));
+    }
+    tokens.push(game.outcome.to_string());
+    format!("{headers}\n\n{}\n", tokens.join(" "))
+}
+
+/// A game found in pasted or fetched PGN, before it is signed and saved.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PgnImportCandidate {
+    pub pgn: String,
+    pub game: rooky_core::RookyGame,
+    pub fingerprint: String,
+}
+
+impl PgnImportCandidate {
+    /// The unsigned game note; its content keeps the PGN as given, comments and variations included.
+    #[must_use]
+    pub fn to_note(&self) -> nostr_minions::nostro2::NostrNote {
+        let mut note = nostr_minions::nostro2::NostrNote::from(self.game.clone());
+        note.content.clone_from(&self.pgn);
+        note
+    }
+}
+
+/// Splits `text` and keeps the games `RookyGame` can read.
+#[must_use]
+pub fn parse_pgn_collection(text: &str, is_broadcast: bool) -> Vec<PgnImportCandidate> {
+    split_pgn(text, is_broadcast)
+        .into_iter()
+        .filter_map(|pgn| {
+            let note = nostr_minions::nostro2::NostrNote {
+                content: pgn.clone(),
+                kind: crate::live_game::rooky_game_kind(),
+                ..Default::default()
+            };
+            let game = rooky_core::RookyGame::try_from(note).ok()?;
+            Some(PgnImportCandidate {
+                fingerprint: crate::game_fingerprint(&game),
+                pgn,
+                game,
+            })
+        })
+        .collect()
+}
+
+/// Downloads PGN text from a raw `.pgn` link or an export URL.
+///
+/// # Errors
+/// Returns the request error if the URL cannot be fetched or the response is not text.
+pub async fn fetch_pgn(url: &str) -> Result<String, gloo::net::Error> {
+    let response = gloo::net::http::Request::get(url)
+        .header("Accept", "application/x-chess-pgn, text/plain")
+        .send()
+        .await?;
+    if !response.ok() {
+        return Err(gloo::net::Error::GlooError(format!(
+            "{url} returned {}",
+            response.status()
+        )));
+    }
+    response.text().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLLECTION: &str = "[Event \"Club \\\"Open\\\" C:\\\\games\"]\n[Round \"5.3\"]\n[White \"Giri\"]\n[Black \"Firouzja\"]\n[Result \"1/2-1/2\"]\n\n{ Prepared } 1. d4 { Solid } Nf6 (1... d5 2. c4 $1) 2. c4 1/2-1/2\n\n[Event \"Blitz\"]\n[Result \"*\"]\n\n1. e4 e5 *\n";
+
+    #[test]
+    fn splits_collections_keeping_annotations_and_escapes() {
+        let games = split_pgn(COLLECTION, false);
+        assert_eq!(games.len(), 2);
+        assert!(games[0].contains("[Event \"Club \\\"Open\\\" C:\\\\games\"]"));
+        assert!(games[0].contains("[Round \"5.3\"]"));
+        assert!(games[0]
+            .ends_with("{Prepared} 1. d4 {Solid} 1... Nf6 (1... d5 2. c4 $1) 2. c4 1/2-1/2\n"));
+        assert!(games[1].ends_with("\n\n1. e4 e5 *\n"));
+        // Written games read back unchanged.
+        assert_eq!(split_pgn(&games[0], false), vec![games[0].clone()]);
+    }
+
+    #[test]
+    fn reads_mainline_comments_by_ply() {
+        assert_eq!(
+            mainline_comments(COLLECTION),
+            vec!["Prepared", "Solid", "", ""]
+        );
+        assert_eq!(
+            mainline_comments("1. e4 {Best} {by test} e5 *"),
+            vec!["", "Best by test", ""]
+        );
+    }
+
+    #[test]
+    fn annotated_pgn_places_comments_by_ply() {
+        let note = nostr_minions::nostro2::NostrNote {
+            content: "[White \"Carlsen\"]\n[Black \"Nepo\"]\n[Result \"*\"]\n\n1. e4 e5 2. Nf3 *\n"
+                .to_string(),
+            kind: crate::live_game::rooky_game_kind(),
+            ..Default::default()
+        };
+        let game = rooky_core::RookyGame::try_from(note).expect("game parses");
+        let comments = ["Start", "", "A {braced} reply", ""].map(ToString::to_string);
+        let pgn = annotated_pgn(&game, &comments);
+        assert!(pgn.contains("{Start} 1. e4 e5 {A braced reply} 2. Nf3"));
+        assert_eq!(
+            mainline_comments(&pgn),
+            vec!["Start", "", "A braced reply", ""]
+        );
+    }
+}
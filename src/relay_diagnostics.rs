@@ -0,0 +1,114 @@
+//! Relay diagnostics: the NIP-11 information document and a round-trip latency probe.
+
+use futures_util::SinkExt;
+use gloo::net::websocket::{futures::WebSocket, Message};
+
+/// Limits a relay advertises in its NIP-11 document.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(default)]
+pub struct RelayLimitation {
+    pub max_message_length: Option<u64>,
+    pub max_subscriptions: Option<u64>,
+    pub max_filters: Option<u64>,
+    pub max_limit: Option<u64>,
+    pub max_subid_length: Option<u64>,
+    pub max_event_tags: Option<u64>,
+    pub max_content_length: Option<u64>,
+    pub min_pow_difficulty: Option<u64>,
+    pub auth_required: Option<bool>,
+    pub payment_required: Option<bool>,
+    pub restricted_writes: Option<bool>,
+}
+
+impl RelayLimitation {
+    /// The advertised limits as `(field, value)` pairs, skipping those left out.
+    #[must_use]
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        let numbers = [
+            ("max_message_length", self.max_message_length),
+            ("max_subscriptions", self.max_subscriptions),
+            ("max_filters", self.max_filters),
+            ("max_limit", self.max_limit),
+            ("max_subid_length", self.max_subid_length),
+            ("max_event_tags", self.max_event_tags),
+            ("max_content_length", self.max_content_length),
+            ("min_pow_difficulty", self.min_pow_difficulty),
+        ];
+        let flags = [
+            ("auth_required", self.auth_required),
+            ("payment_required", self.payment_required),
+            ("restricted_writes", self.restricted_writes),
+        ];
+        numbers
+            .into_iter()
+            .filter_map(|(name, value)| Some((name, value?.to_string())))
+            .chain(
+                flags
+                    .into_iter()
+                    .filter_map(|(name, value)| Some((name, value?.to_string()))),
+            )
+            .collect()
+    }
+}
+
+/// A relay's NIP-11 information document.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(default)]
+pub struct RelayInfo {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub pubkey: Option<String>,
+    pub contact: Option<String>,
+    pub software: Option<String>,
+    pub version: Option<String>,
+    pub supported_nips: Vec<u32>,
+    pub limitation: Option<RelayLimitation>,
+}
+
+/// The HTTP address serving a relay's NIP-11 document.
+fn info_url(relay_url: &str) -> String {
+    relay_url
+        .replacen("wss://", "https://", 1)
+        .replacen("ws://", "http://", 1)
+}
+
+/// Fetches the NIP-11 information document of `relay_url`.
+///
+/// # Errors
+/// Returns the request error if the relay does not serve a readable document.
+pub async fn fetch_relay_info(relay_url: &str) -> Result<RelayInfo, gloo::net::Error> {
+    let url = info_url(relay_url);
+    let response = gloo::net::http::Request::get(&url)
+        .header("Accept", "application/nostr+json")
+        .send()
+        .await?;
+    if !response.ok() {
+        return Err(gloo::net::Error::GlooError(format!(
+            "{url} returned {}",
+            response.status()
+        )));
+    }
+    response.json().await
+}
+
+/// Milliseconds between sending an empty subscription and the relay ending it,
+/// measured on a fresh connection so the pool's traffic does not skew it.
+pub async fn measure_latency(relay_url: &str) -> Option<f64> {
+    let mut socket = WebSocket::open(relay_url).ok()?;
+    let request = serde_json::json!(["REQ", "latency", { "limit": 0 }]).to_string();
+    let elapsed = crate::relay_list::with_timeout(async {
+        // Sending waits for the connection to open, so the clock starts after the handshake.
+        socket.send(Message::Text(request)).await.ok()?;
+        let started = web_sys::js_sys::Date::now();
+        crate::relay_list::read_until(&mut socket, |message| match message.first() {
+            Some(kind) if kind == "EOSE" || kind == "CLOSED" || kind == "NOTICE" => Some(()),
+            _ => None,
+        })
+        .await?;
+        Some(web_sys::js_sys::Date::now() - started)
+    })
+    .await
+    .flatten();
+    let _ = socket.close(None, None);
+    elapsed
+}
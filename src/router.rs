@@ -9,6 +9,10 @@ pub enum MainRoute {
     Search,
     #[at("/games")]
     MyGames,
+    #[at("/feed")]
+    Feed,
+    #[at("/stats")]
+    Stats,
     #[at("/annotate")]
     NewGame,
     #[at("/detail/:id")]
@@ -48,10 +52,20 @@ pub fn main_panel(props: &MainPanelProps) -> Html {
                     <div class={if matches!(props.route, MainRoute::MyGames) { showing_class } else { hidden_class }}>
                         <crate::pages::GamesPage />
                     </div>
+                    <div class={if matches!(props.route, MainRoute::Feed) { showing_class } else { hidden_class }}>
+                        <crate::pages::FeedPage />
+                    </div>
+                    <div class={if matches!(props.route, MainRoute::Stats) { showing_class } else { hidden_class }}>
+                        <crate::pages::StatsPage />
+                    </div>
                     <div class={if matches!(props.route, MainRoute::Settings) { showing_class } else { hidden_class }}>
                         <div class="flex gap-4 size-full items-center justify-evenly">
                             <crate::pages::RelayManagementPage />
                             <crate::pages::KeyRecoveryPage />
+                            <div class="flex flex-col gap-4">
+                                <crate::pages::OutboxQueuePage />
+                                <crate::pages::InboxResyncPage />
+                            </div>
                         </div>
                     </div>
                     <div class={if matches!(props.route, MainRoute::NewGame) { showing_class } else { hidden_class }}>
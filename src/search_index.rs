@@ -0,0 +1,228 @@
+//! An in-memory inverted index over game headers and move comments.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// The text inside `{}` comments of a PGN.
+fn pgn_comments(pgn: &str) -> impl Iterator<Item = &str> {
+    pgn.split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}').map(|(comment, _)| comment))
+}
+
+/// Searchable text of a game: players, event, site, round, opening and comments.
+#[must_use]
+pub fn searchable_fields(entry: &rooky_core::idb::RookyGameEntry) -> Vec<String> {
+    let game = rooky_core::RookyGame::from(entry);
+    let mut fields = vec![
+        game.white.clone(),
+        game.black.clone(),
+        game.event.to_string(),
+        game.site.to_string(),
+        game.round.to_string(),
+    ];
+    if let Some(opening) = game.opening() {
+        fields.push(opening.name.to_string());
+    }
+    fields.extend(pgn_comments(&entry.note.content).map(ToString::to_string));
+    fields
+}
+
+/// Maps every token to the sorted positions of the games containing it.
+///
+/// Tokens are kept ordered so a prefix lookup is a single range scan. Removed games leave
+/// their postings behind until the index is rebuilt; their position no longer maps to an id.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GameSearchIndex {
+    ids: Vec<Option<String>>,
+    /// Position and indexed note id of every live game, to spot edits.
+    positions: HashMap<String, (u32, Option<String>)>,
+    tokens: BTreeMap<String, Vec<u32>>,
+}
+
+impl GameSearchIndex {
+    #[must_use]
+    pub fn build(entries: &[rooky_core::idb::RookyGameEntry]) -> Self {
+        let mut index = Self::default();
+        for entry in entries {
+            index.insert(entry);
+        }
+        index
+    }
+
+    fn insert(&mut self, entry: &rooky_core::idb::RookyGameEntry) {
+        let position = self.ids.len() as u32;
+        self.ids.push(Some(entry.id.clone()));
+        self.positions
+            .insert(entry.id.clone(), (position, entry.note.id.clone()));
+        for field in searchable_fields(entry) {
+            for token in tokenize(&field) {
+                let postings = self.tokens.entry(token).or_default();
+                if postings.last() != Some(&position) {
+                    postings.push(position);
+                }
+            }
+        }
+    }
+
+    fn remove(&mut self, id: &str) {
+        if let Some((position, _)) = self.positions.remove(id) {
+            self.ids[position as usize] = None;
+        }
+    }
+
+    /// Brings the index in line with `entries`, reading only games that are new or edited.
+    pub fn sync(&mut self, entries: &[rooky_core::idb::RookyGameEntry]) {
+        let live = entries
+            .iter()
+            .map(|entry| entry.id.as_str())
+            .collect::<HashSet<_>>();
+        let gone = self
+            .positions
+            .keys()
+            .filter(|id| !live.contains(id.as_str()))
+            .cloned()
+            .collect::<Vec<_>>();
+        for id in gone {
+            self.remove(&id);
+        }
+        for entry in entries {
+            match self.positions.get(&entry.id) {
+                Some((_, note_id)) if *note_id == entry.note.id => {}
+                Some(_) => {
+                    self.remove(&entry.id);
+                    self.insert(entry);
+                }
+                None => self.insert(entry),
+            }
+        }
+        // Stale postings outnumbering live games make a rebuild cheaper than scanning them.
+        if self.ids.len() > 2 * self.positions.len().max(1) {
+            *self = Self::build(entries);
+        }
+    }
+
+    fn prefix_matches(&self, prefix: &str) -> HashSet<u32> {
+        self.tokens
+            .range(prefix.to_string()..)
+            .take_while(|(token, _)| token.starts_with(prefix))
+            .flat_map(|(_, postings)| postings.iter().copied())
+            .collect()
+    }
+
+    /// Ids of games where every word of `query` starts some indexed word.
+    #[must_use]
+    pub fn search(&self, query: &str) -> HashSet<String> {
+        let mut matches: Option<HashSet<u32>> = None;
+        for token in tokenize(query) {
+            let found = self.prefix_matches(&token);
+            matches = Some(match matches {
+                Some(matches) => matches.intersection(&found).copied().collect(),
+                None => found,
+            });
+            if matches.as_ref().is_some_and(HashSet::is_empty) {
+                break;
+            }
+        }
+        matches
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|position| self.ids.get(position as usize).cloned().flatten())
+            .collect()
+    }
+}
+
+/// Splits `text` into runs, flagging the words that start with a word of `query`.
+#[must_use]
+pub fn highlight_segments(text: &str, query: &str) -> Vec<(String, bool)> {
+    let needles = tokenize(query).collect::<Vec<_>>();
+    let mut segments: Vec<(String, bool)> = vec![];
+    let mut push = |segment: &str, highlighted: bool| match segments.last_mut() {
+        Some((last, last_highlighted)) if *last_highlighted == highlighted => {
+            last.push_str(segment);
+        }
+        _ => segments.push((segment.to_string(), highlighted)),
+    };
+    let mut word_start = None;
+    for (index, c) in text
+        .char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+    {
+        match (c.is_alphanumeric() && index < text.len(), word_start) {
+            (true, None) => word_start = Some(index),
+            (false, Some(start)) => {
+                let word = &text[start..index];
+                let lowered = word.to_lowercase();
+                push(
+                    word,
+                    needles
+                        .iter()
+                        .any(|needle| lowered.starts_with(needle.as_str())),
+                );
+                word_start = None;
+                if index < text.len() {
+                    push(&text[index..index + c.len_utf8()], false);
+                }
+            }
+            (false, None) if index < text.len() => push(&text[index..index + c.len_utf8()], false),
+            _ => {}
+        }
+    }
+    segments
+}
+
+/// The first comment of a game that matches `query`, for showing why it was found.
+#[must_use]
+pub fn matching_comment(entry: &rooky_core::idb::RookyGameEntry, query: &str) -> Option<String> {
+    let needles = tokenize(query).collect::<Vec<_>>();
+    pgn_comments(&entry.note.content)
+        .find(|comment| {
+            tokenize(comment).any(|word| {
+                needles
+                    .iter()
+                    .any(|needle| word.starts_with(needle.as_str()))
+            })
+        })
+        .map(|comment| comment.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, pgn: &str) -> rooky_core::idb::RookyGameEntry {
+        rooky_core::idb::RookyGameEntry {
+            id: id.to_string(),
+            note: nostr_minions::nostro2::NostrNote {
+                id: Some(format!("{id}-{}", pgn.len())),
+                content: pgn.to_string(),
+                kind: crate::live_game::rooky_game_kind(),
+                ..Default::default()
+            },
+            origin: rooky_core::idb::GameOrigin::Annotated,
+        }
+    }
+
+    #[test]
+    fn sync_follows_added_edited_and_removed_games() {
+        let carlsen = entry("a", "[White \"Carlsen\"]\n[Black \"Nepo\"]\n\n1. e4 *\n");
+        let caruana = entry("b", "[White \"Caruana\"]\n[Black \"Ding\"]\n\n1. d4 *\n");
+        let mut index = GameSearchIndex::default();
+        index.sync(&[carlsen.clone(), caruana.clone()]);
+        assert_eq!(index.search("car").len(), 2);
+
+        let edited = entry("b", "[White \"Gukesh\"]\n[Black \"Ding\"]\n\n1. d4 *\n");
+        index.sync(&[carlsen.clone(), edited.clone()]);
+        assert_eq!(index.search("car"), HashSet::from(["a".to_string()]));
+        assert_eq!(index.search("gukesh"), HashSet::from(["b".to_string()]));
+
+        index.sync(&[edited.clone()]);
+        assert!(index.search("carlsen").is_empty());
+        assert_eq!(index, GameSearchIndex::build(&[edited]));
+    }
+}
@@ -0,0 +1,334 @@
+//! Per-player statistics computed from the games in the local database.
+
+use std::collections::HashMap;
+
+const TOP_OPENINGS: usize = 10;
+const TOP_OPPONENTS: usize = 10;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResultRecord {
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+impl ResultRecord {
+    #[must_use]
+    pub const fn games(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Points scored per game, from 0 to 1, counting draws as half a point.
+    #[must_use]
+    pub fn score(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.0;
+        }
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.games() as f64
+    }
+
+    fn add(&mut self, result: PlayerResult) {
+        match result {
+            PlayerResult::Win => self.wins += 1,
+            PlayerResult::Draw => self.draws += 1,
+            PlayerResult::Loss => self.losses += 1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PlayerResult {
+    Win,
+    Draw,
+    Loss,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlayerStats {
+    pub as_white: ResultRecord,
+    pub as_black: ResultRecord,
+    /// Results per month, oldest first, keyed as `YYYY-MM`.
+    pub by_month: Vec<(String, ResultRecord)>,
+    pub openings: Vec<(String, ResultRecord)>,
+    pub opponents: Vec<(String, ResultRecord)>,
+    pub by_origin: Vec<(rooky_core::idb::GameOrigin, ResultRecord)>,
+    /// Average number of full moves per game.
+    pub average_length: f64,
+}
+
+impl PlayerStats {
+    #[must_use]
+    pub fn total(&self) -> ResultRecord {
+        ResultRecord {
+            wins: self.as_white.wins + self.as_black.wins,
+            draws: self.as_white.draws + self.as_black.draws,
+            losses: self.as_white.losses + self.as_black.losses,
+        }
+    }
+}
+
+/// Translation key for a game origin, matching the games list filters.
+#[must_use]
+pub const fn origin_label_key(origin: &rooky_core::idb::GameOrigin) -> &'static str {
+    match origin {
+        rooky_core::idb::GameOrigin::Annotated => "common_annotated",
+        rooky_core::idb::GameOrigin::Received => "common_received",
+        rooky_core::idb::GameOrigin::Public => "common_public",
+        rooky_core::idb::GameOrigin::Unknown => "common_unknown",
+    }
+}
+
+fn normalize_name(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+fn ranked(records: HashMap<String, ResultRecord>, limit: usize) -> Vec<(String, ResultRecord)> {
+    let mut records = records.into_iter().collect::<Vec<_>>();
+    records.sort_by(|(a_name, a), (b_name, b)| {
+        b.games().cmp(&a.games()).then_with(|| a_name.cmp(b_name))
+    });
+    records.truncate(limit);
+    records
+}
+
+/// Player names in the database, most games first.
+#[must_use]
+pub fn player_names(entries: &[rooky_core::idb::RookyGameEntry]) -> Vec<String> {
+    let mut counts: HashMap<String, (String, usize)> = HashMap::new();
+    for entry in entries {
+        let game = rooky_core::RookyGame::from(entry);
+        for name in [game.white, game.black] {
+            if name.trim().is_empty() || name == "?" {
+                continue;
+            }
+            counts
+                .entry(normalize_name(&name))
+                .or_insert_with(|| (name.trim().to_string(), 0))
+                .1 += 1;
+        }
+    }
+    let mut names = counts.into_values().collect::<Vec<_>>();
+    names.sort_by(|(a_name, a), (b_name, b)| b.cmp(a).then_with(|| a_name.cmp(b_name)));
+    names.into_iter().map(|(name, _)| name).collect()
+}
+
+/// Statistics for every game `player` played, matching names case-insensitively.
+#[must_use]
+pub fn player_stats(entries: &[rooky_core::idb::RookyGameEntry], player: &str) -> PlayerStats {
+    let player = normalize_name(player);
+    let mut stats = PlayerStats::default();
+    let mut by_month: HashMap<String, ResultRecord> = HashMap::new();
+    let mut openings: HashMap<String, ResultRecord> = HashMap::new();
+    let mut opponents: HashMap<String, ResultRecord> = HashMap::new();
+    let mut by_origin: Vec<(rooky_core::idb::GameOrigin, ResultRecord)> = vec![];
+    let mut plies = 0;
+
+    for entry in entries {
+        let game = rooky_core::RookyGame::from(entry);
+        let color = if normalize_name(&game.white) == player {
+            shakmaty::Color::White
+        } else if normalize_name(&game.black) == player {
+            shakmaty::Color::Black
+        } else {
+            continue;
+        };
+        let result = match game.outcome {
+            shakmaty::Outcome::Draw => PlayerResult::Draw,
+            shakmaty::Outcome::Decisive { winner } if winner == color => PlayerResult::Win,
+            shakmaty::Outcome::Decisive { .. } => PlayerResult::Loss,
+        };
+        let opponent = match color {
+            shakmaty::Color::White => {
+                stats.as_white.add(result);
+                game.black.trim().to_string()
+            }
+            shakmaty::Color::Black => {
+                stats.as_black.add(result);
+                game.white.trim().to_string()
+            }
+        };
+        by_month
+            .entry(game.date.format("%Y-%m").to_string())
+            .or_default()
+            .add(result);
+        if let Some(opening) = game.opening() {
+            openings
+                .entry(opening.name.to_string())
+                .or_default()
+                .add(result);
+        }
+        opponents.entry(opponent).or_default().add(result);
+        match by_origin
+            .iter_mut()
+            .find(|(origin, _)| *origin == entry.origin)
+        {
+            Some((_, record)) => record.add(result),
+            None => {
+                let mut record = ResultRecord::default();
+                record.add(result);
+                by_origin.push((entry.origin.clone(), record));
+            }
+        }
+        plies += game.moves.len();
+    }
+
+    let games = stats.total().games();
+    if games > 0 {
+        stats.average_length = plies as f64 / games as f64 / 2.0;
+    }
+    let mut by_month = by_month.into_iter().collect::<Vec<_>>();
+    by_month.sort_by(|(a, _), (b, _)| a.cmp(b));
+    stats.by_month = by_month;
+    stats.openings = ranked(openings, TOP_OPENINGS);
+    stats.opponents = ranked(opponents, TOP_OPPONENTS);
+    stats.by_origin = by_origin;
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(
+        id: &str,
+        white: &str,
+        black: &str,
+        date: &str,
+        result: &str,
+        moves: &str,
+        origin: rooky_core::idb::GameOrigin,
+    ) -> rooky_core::idb::RookyGameEntry {
+        rooky_core::idb::RookyGameEntry {
+            id: id.to_string(),
+            note: nostr_minions::nostro2::NostrNote {
+                id: Some(id.to_string()),
+                content: format!(
+                    "[Date \"{date}\"]\n[White \"{white}\"]\n[Black \"{black}\"]\n[Result \"{result}\"]\n\n{moves} {result}\n"
+                ),
+                kind: crate::live_game::rooky_game_kind(),
+                ..Default::default()
+            },
+            origin,
+        }
+    }
+
+    fn games() -> Vec<rooky_core::idb::RookyGameEntry> {
+        vec![
+            entry(
+                "a",
+                "Judit",
+                "Garry",
+                "2024.01.10",
+                "1-0",
+                "1. e4 c5",
+                rooky_core::idb::GameOrigin::Annotated,
+            ),
+            entry(
+                "b",
+                "Garry",
+                " judit ",
+                "2024.01.20",
+                "1/2-1/2",
+                "1. e4 c5",
+                rooky_core::idb::GameOrigin::Public,
+            ),
+            entry(
+                "c",
+                "Anatoly",
+                "JUDIT",
+                "2024.02.05",
+                "1-0",
+                "1. d4 d5 2. c4 e6",
+                rooky_core::idb::GameOrigin::Public,
+            ),
+            entry(
+                "d",
+                "Anatoly",
+                "Garry",
+                "2024.02.06",
+                "0-1",
+                "1. c4",
+                rooky_core::idb::GameOrigin::Public,
+            ),
+        ]
+    }
+
+    #[test]
+    fn tallies_results_by_colour_month_and_origin() {
+        let stats = player_stats(&games(), "Judit");
+        assert_eq!(
+            stats.as_white,
+            ResultRecord {
+                wins: 1,
+                draws: 0,
+                losses: 0
+            }
+        );
+        assert_eq!(
+            stats.as_black,
+            ResultRecord {
+                wins: 0,
+                draws: 1,
+                losses: 1
+            }
+        );
+        assert_eq!(stats.total().games(), 3);
+        assert!((stats.total().score() - 0.5).abs() < f64::EPSILON);
+        let months = stats
+            .by_month
+            .iter()
+            .map(|(month, record)| (month.as_str(), record.games()))
+            .collect::<Vec<_>>();
+        assert_eq!(months, [("2024-01", 2), ("2024-02", 1)]);
+        assert_eq!(
+            stats.opponents,
+            vec![
+                (
+                    "Garry".to_string(),
+                    ResultRecord {
+                        wins: 1,
+                        draws: 1,
+                        losses: 0
+                    }
+                ),
+                (
+                    "Anatoly".to_string(),
+                    ResultRecord {
+                        wins: 0,
+                        draws: 0,
+                        losses: 1
+                    }
+                ),
+            ]
+        );
+        assert_eq!(stats.by_origin.len(), 2);
+        assert!((stats.average_length - 8.0 / 3.0 / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn groups_games_by_opening() {
+        let stats = player_stats(&games(), "judit");
+        let (name, record) = &stats.openings[0];
+        assert!(name.contains("Sicilian"), "{name}");
+        assert_eq!(
+            *record,
+            ResultRecord {
+                wins: 1,
+                draws: 1,
+                losses: 0
+            }
+        );
+        let counted = stats
+            .openings
+            .iter()
+            .map(|(_, record)| record.games())
+            .sum::<usize>();
+        assert!(counted <= stats.total().games());
+    }
+
+    #[test]
+    fn lists_players_by_game_count() {
+        assert_eq!(player_names(&games()), ["Garry", "Judit", "Anatoly"]);
+        assert_eq!(player_stats(&games(), "Magnus"), PlayerStats::default());
+    }
+}
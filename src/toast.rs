@@ -0,0 +1,38 @@
+//! Toasts with text built at runtime, such as translated or counted messages.
+//!
+//! `ToastifyOptions::new_success` and `new_failure` only take static strings, so the text of
+//! their options is replaced before the toast is shown.
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    type Toast;
+    #[wasm_bindgen(js_namespace = window, js_name = Toastify)]
+    fn toastify(options: &JsValue) -> Toast;
+    #[wasm_bindgen(method, js_name = showToast)]
+    fn show_toast(this: &Toast);
+}
+
+fn show_with_text(options: nostr_minions::widgets::toastify::ToastifyOptions, text: &str) {
+    let options = JsValue::from(options);
+    if web_sys::js_sys::Reflect::set(&options, &"text".into(), &text.into()).is_ok() {
+        toastify(&options).show_toast();
+    }
+}
+
+/// Shows `text` in the green success toast.
+pub fn toast_success(text: &str) {
+    show_with_text(
+        nostr_minions::widgets::toastify::ToastifyOptions::new_success(""),
+        text,
+    );
+}
+
+/// Shows `text` in the red failure toast.
+pub fn toast_failure(text: &str) {
+    show_with_text(
+        nostr_minions::widgets::toastify::ToastifyOptions::new_failure(""),
+        text,
+    );
+}